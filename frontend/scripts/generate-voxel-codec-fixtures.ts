@@ -0,0 +1,26 @@
+// Regenerates the compressVoxelData fixtures used by wasm/src/voxel_codec.rs.
+// Run from frontend/: npx tsx scripts/generate-voxel-codec-fixtures.ts
+import { compressVoxelData } from "../src/modeling/lib/voxel-data-utils";
+
+const toHex = (bytes: Uint8Array): string =>
+  Array.from(bytes, (b) => b.toString(16).padStart(2, "0")).join("");
+
+const chunk = new Uint8Array(16 * 4 * 16);
+for (let x = 0; x < 16; x++) {
+  for (let z = 0; z < 16; z++) {
+    chunk[x * 64 + z] = 0x81;
+    if ((x + z) % 5 === 0) {
+      chunk[x * 64 + 16 + z] = 0x82;
+    }
+  }
+}
+
+const fixtures: [string, Uint8Array][] = [
+  ["SMALL_FIXTURE", new Uint8Array([1, 2, 3, 4, 5, 0, 0, 0])],
+  ["CHUNK_FIXTURE", chunk],
+  ["LONG_RUN_FIXTURE", new Uint8Array(70000).fill(3)],
+];
+
+for (const [name, data] of fixtures) {
+  console.log(`const ${name}: &str = "${toHex(compressVoxelData(data))}";`);
+}
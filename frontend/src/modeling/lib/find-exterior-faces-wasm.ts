@@ -3,6 +3,8 @@ import type { MeshArrays } from "./mesh-arrays";
 import type { VoxelFrame } from "./voxel-frame";
 import init, {
   WasmExteriorFacesFinder,
+  expandBlockAtlasMapping,
+  facesPerBlock,
 } from "@/wasm/lunavoxel_wasm";
 
 let wasmInitialized = false;
@@ -48,6 +50,22 @@ export class WasmExteriorFacesFinderWrapper {
     return this.finder;
   }
 
+  private getAtlasMapping(blockAtlasMapping: number[]): Int32Array {
+    const mapping = this.int32AtlasMapping;
+    const stride = facesPerBlock();
+    if (mapping && mapping.length === blockAtlasMapping.length * stride) {
+      let current = true;
+      for (let i = 0; i < blockAtlasMapping.length && current; i++) {
+        current = mapping[i * stride] === blockAtlasMapping[i];
+      }
+      if (current) return mapping;
+    }
+    this.int32AtlasMapping = expandBlockAtlasMapping(
+      Int32Array.from(blockAtlasMapping)
+    );
+    return this.int32AtlasMapping;
+  }
+
   public findExteriorFaces(
     voxelData: Uint8Array,
     textureWidth: number,
@@ -58,18 +76,7 @@ export class WasmExteriorFacesFinderWrapper {
   ): void {
     const finder = this.ensureFinder();
 
-    if (
-      !this.int32AtlasMapping ||
-      this.int32AtlasMapping.length !== blockAtlasMapping.length
-    ) {
-      this.int32AtlasMapping = new Int32Array(blockAtlasMapping.length);
-    }
-    for (let i = 0; i < blockAtlasMapping.length; i++) {
-      this.int32AtlasMapping[i] = blockAtlasMapping[i];
-    }
-
-    const totalVoxels = dimensions.x * dimensions.y * dimensions.z;
-    const maxFaces = totalVoxels * 6;
+    const atlasMapping = this.getAtlasMapping(blockAtlasMapping);
 
     const selectionEmpty = selectionFrame.isEmpty();
     const selectionData = selectionFrame.getData();
@@ -78,12 +85,10 @@ export class WasmExteriorFacesFinderWrapper {
     finder.findExteriorFaces(
       voxelData,
       textureWidth,
-      this.int32AtlasMapping,
+      atlasMapping,
       dimensions.x,
       dimensions.y,
       dimensions.z,
-      maxFaces * 4,
-      maxFaces * 6,
       selectionEmpty ? new Uint8Array(0) : selectionData,
       selectionEmpty ? 0 : selectionDims.x,
       selectionEmpty ? 0 : selectionDims.y,
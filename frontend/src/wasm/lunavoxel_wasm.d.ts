@@ -4,35 +4,197 @@
 export class WasmExteriorFacesFinder {
     free(): void;
     [Symbol.dispose](): void;
-    findExteriorFaces(voxel_data: Uint8Array, texture_width: number, block_atlas_mapping: Int32Array, dim_x: number, dim_y: number, dim_z: number, max_vertices: number, max_indices: number, selection_data: Uint8Array, selection_dim_x: number, selection_dim_y: number, selection_dim_z: number, selection_empty: boolean): void;
+    clearColorPalette(): void;
+    exportMesh(format: number, scale: number, axis: number): Uint8Array;
+    findExteriorFaces(voxel_data: Uint8Array, texture_width: number, block_atlas_mapping: Int32Array, dim_x: number, dim_y: number, dim_z: number, selection_data: Uint8Array, selection_dim_x: number, selection_dim_y: number, selection_dim_z: number, selection_empty: boolean): void;
+    findExteriorFacesLod(voxel_data: Uint8Array, texture_width: number, block_atlas_mapping: Int32Array, dim_x: number, dim_y: number, dim_z: number, lod_factor: number): void;
+    findExteriorFacesWithHalo(voxel_data: Uint8Array, texture_width: number, block_atlas_mapping: Int32Array, dim_x: number, dim_y: number, dim_z: number, selection_data: Uint8Array, selection_dim_x: number, selection_dim_y: number, selection_dim_z: number, selection_empty: boolean): void;
     getAO(): Float32Array;
+    getAOPtr(): number;
+    getBlockTypes(): Float32Array;
+    getBlockTypesPtr(): number;
+    getCavityCount(): number;
+    getCavityVolume(): number;
+    getColors(): Float32Array;
+    getColorsPtr(): number;
+    getFaceIndexRanges(): Uint32Array;
     getIndexCount(): number;
     getIndices(): Uint32Array;
+    getIndicesPtr(): number;
     getIsSelected(): Float32Array;
+    getIsSelectedPtr(): number;
     getNormals(): Float32Array;
+    getNormalsPtr(): number;
+    getPackedVertices(): Uint32Array;
+    getPackedVerticesPtr(): number;
+    getPackedWordsPerVertex(): number;
+    getQuadIds(): Uint32Array;
+    getQuadIdsPtr(): number;
+    getTileOrigins(): Float32Array;
+    getTileOriginsPtr(): number;
+    getTranslucentFaceIndexRanges(): Uint32Array;
+    getTranslucentIndexStart(): number;
     getUVs(): Float32Array;
+    getUVsPtr(): number;
     getVertexCount(): number;
     getVertices(): Float32Array;
+    /**
+     * The `get*Ptr` accessors return byte offsets into `wasmMemory().buffer` so JS
+     * can build `Float32Array`/`Uint32Array` views of length `getVertexCount() * n`
+     * or `getIndexCount()` without copying. A view is invalidated by the next
+     * `findExteriorFaces*` call, which may reallocate the buffers, and by any wasm
+     * allocation that grows linear memory, which detaches the old `ArrayBuffer`.
+     * Read or upload the views immediately and re-create them after every remesh.
+     */
+    getVerticesPtr(): number;
     constructor(max_dimension: number);
+    pickVoxel(quad_id: number, hit_x: number, hit_y: number, hit_z: number): Uint32Array | undefined;
+    setAoCurve(levels: Float32Array): void;
+    setAoEnabled(enabled: boolean): void;
+    setAoStrength(strength: number): void;
+    setBitmaskBackend(enabled: boolean): void;
+    setBlockMaterials(block_classes: Uint8Array): void;
+    setColorPalette(rgba: Float32Array, premultiply_ao: boolean): void;
+    setColorPaletteHex(colors: Uint32Array, premultiply_ao: boolean): void;
+    setExteriorOnly(exterior_only: boolean): void;
+    setPackedOutput(packed: boolean): void;
+    setPickingEnabled(enabled: boolean): void;
+    setSmoothMode(smooth: boolean): void;
+    setTiledUvs(tiled: boolean): void;
 }
 
+export class WasmIncrementalMesher {
+    free(): void;
+    [Symbol.dispose](): void;
+    getAO(): Float32Array;
+    getFaceIndexRanges(): Uint32Array;
+    getIndexCount(): number;
+    getIndices(): Uint32Array;
+    getIsSelected(): Float32Array;
+    getNormals(): Float32Array;
+    getQuadIds(): Uint32Array;
+    getTranslucentFaceIndexRanges(): Uint32Array;
+    getTranslucentIndexStart(): number;
+    getUVs(): Float32Array;
+    getVertexCount(): number;
+    getVertices(): Float32Array;
+    constructor(max_dimension: number);
+    pickVoxel(quad_id: number, hit_x: number, hit_y: number, hit_z: number): Uint32Array | undefined;
+    remeshFull(voxel_data: Uint8Array, texture_width: number, block_atlas_mapping: Int32Array, dim_x: number, dim_y: number, dim_z: number, selection_data: Uint8Array, selection_dim_x: number, selection_dim_y: number, selection_dim_z: number, selection_empty: boolean): void;
+    remeshRegion(voxel_data: Uint8Array, texture_width: number, block_atlas_mapping: Int32Array, dim_x: number, dim_y: number, dim_z: number, min_x: number, min_y: number, min_z: number, max_x: number, max_y: number, max_z: number, selection_data: Uint8Array, selection_dim_x: number, selection_dim_y: number, selection_dim_z: number, selection_empty: boolean): void;
+    setBlockMaterials(block_classes: Uint8Array): void;
+    setPickingEnabled(enabled: boolean): void;
+}
+
+export function buildSelectionOutline(selection_data: Uint8Array, selection_dim_x: number, selection_dim_y: number, selection_dim_z: number): Float32Array;
+
+export function compressVoxelData(voxel_data: Uint8Array): Uint8Array;
+
+export function decompressVoxelData(compressed_data: Uint8Array): Uint8Array;
+
+export function decompressVoxelDataInto(compressed_data: Uint8Array, target: Uint8Array): number;
+
+export function expandBlockAtlasMapping(per_block_mapping: Int32Array): Int32Array;
+
+export function exportVoxelVolume(voxel_data: Uint8Array, dim_x: number, dim_y: number, dim_z: number, format: number, scale: number, axis: number, palette_rgba: Float32Array): Uint8Array;
+
+export function facesPerBlock(): number;
+
+export function fillShape(voxel_data: Uint8Array, dim_x: number, dim_y: number, dim_z: number, shape: number, min_x: number, min_y: number, min_z: number, max_x: number, max_y: number, max_z: number, up_face_dir: number, wall_thickness: number, mode: number, block_type: number, preview: boolean): number;
+
+export function floodFill(voxel_data: Uint8Array, dim_x: number, dim_y: number, dim_z: number, seed_x: number, seed_y: number, seed_z: number, connectivity: number, match_mode: number, max_voxels: number, selection_data: Uint8Array): Uint32Array;
+
+export function raycastVoxels(voxel_data: Uint8Array, dim_x: number, dim_y: number, dim_z: number, origin_x: number, origin_y: number, origin_z: number, direction_x: number, direction_y: number, direction_z: number, max_distance: number): Float32Array | undefined;
+
+export function wasmMemory(): any;
+
 export type InitInput = RequestInfo | URL | Response | BufferSource | WebAssembly.Module;
 
 export interface InitOutput {
     readonly memory: WebAssembly.Memory;
     readonly __wbg_wasmexteriorfacesfinder_free: (a: number, b: number) => void;
-    readonly wasmexteriorfacesfinder_findExteriorFaces: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number, i: number, j: number, k: number, l: number, m: number, n: number, o: number, p: number, q: number) => void;
+    readonly __wbg_wasmincrementalmesher_free: (a: number, b: number) => void;
+    readonly buildSelectionOutline: (a: number, b: number, c: number, d: number, e: number) => [number, number, number, number];
+    readonly compressVoxelData: (a: number, b: number) => [number, number, number, number];
+    readonly decompressVoxelData: (a: number, b: number) => [number, number, number, number];
+    readonly decompressVoxelDataInto: (a: number, b: number, c: number, d: number, e: any) => [number, number, number];
+    readonly expandBlockAtlasMapping: (a: number, b: number) => [number, number];
+    readonly exportVoxelVolume: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number, i: number, j: number) => [number, number, number, number];
+    readonly facesPerBlock: () => number;
+    readonly fillShape: (a: number, b: number, c: any, d: number, e: number, f: number, g: number, h: number, i: number, j: number, k: number, l: number, m: number, n: number, o: number, p: number, q: number, r: number) => [number, number, number];
+    readonly floodFill: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number, i: number, j: number, k: number, l: number, m: number, n: any) => [number, number, number, number];
+    readonly raycastVoxels: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number, i: number, j: number, k: number, l: number) => [number, number, number, number];
+    readonly wasmexteriorfacesfinder_clearColorPalette: (a: number) => void;
+    readonly wasmexteriorfacesfinder_exportMesh: (a: number, b: number, c: number, d: number) => [number, number, number, number];
+    readonly wasmexteriorfacesfinder_findExteriorFaces: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number, i: number, j: number, k: number, l: number, m: number, n: number, o: number) => [number, number];
+    readonly wasmexteriorfacesfinder_findExteriorFacesLod: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number, i: number, j: number) => [number, number];
+    readonly wasmexteriorfacesfinder_findExteriorFacesWithHalo: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number, i: number, j: number, k: number, l: number, m: number, n: number, o: number) => [number, number];
     readonly wasmexteriorfacesfinder_getAO: (a: number) => [number, number];
+    readonly wasmexteriorfacesfinder_getAOPtr: (a: number) => number;
+    readonly wasmexteriorfacesfinder_getBlockTypes: (a: number) => [number, number];
+    readonly wasmexteriorfacesfinder_getBlockTypesPtr: (a: number) => number;
+    readonly wasmexteriorfacesfinder_getCavityCount: (a: number) => number;
+    readonly wasmexteriorfacesfinder_getCavityVolume: (a: number) => number;
+    readonly wasmexteriorfacesfinder_getColors: (a: number) => [number, number];
+    readonly wasmexteriorfacesfinder_getColorsPtr: (a: number) => number;
+    readonly wasmexteriorfacesfinder_getFaceIndexRanges: (a: number) => [number, number];
     readonly wasmexteriorfacesfinder_getIndexCount: (a: number) => number;
     readonly wasmexteriorfacesfinder_getIndices: (a: number) => [number, number];
+    readonly wasmexteriorfacesfinder_getIndicesPtr: (a: number) => number;
     readonly wasmexteriorfacesfinder_getIsSelected: (a: number) => [number, number];
+    readonly wasmexteriorfacesfinder_getIsSelectedPtr: (a: number) => number;
     readonly wasmexteriorfacesfinder_getNormals: (a: number) => [number, number];
+    readonly wasmexteriorfacesfinder_getNormalsPtr: (a: number) => number;
+    readonly wasmexteriorfacesfinder_getPackedVertices: (a: number) => [number, number];
+    readonly wasmexteriorfacesfinder_getPackedVerticesPtr: (a: number) => number;
+    readonly wasmexteriorfacesfinder_getPackedWordsPerVertex: (a: number) => number;
+    readonly wasmexteriorfacesfinder_getQuadIds: (a: number) => [number, number];
+    readonly wasmexteriorfacesfinder_getQuadIdsPtr: (a: number) => number;
+    readonly wasmexteriorfacesfinder_getTileOrigins: (a: number) => [number, number];
+    readonly wasmexteriorfacesfinder_getTileOriginsPtr: (a: number) => number;
+    readonly wasmexteriorfacesfinder_getTranslucentFaceIndexRanges: (a: number) => [number, number];
+    readonly wasmexteriorfacesfinder_getTranslucentIndexStart: (a: number) => number;
     readonly wasmexteriorfacesfinder_getUVs: (a: number) => [number, number];
+    readonly wasmexteriorfacesfinder_getUVsPtr: (a: number) => number;
     readonly wasmexteriorfacesfinder_getVertexCount: (a: number) => number;
     readonly wasmexteriorfacesfinder_getVertices: (a: number) => [number, number];
+    readonly wasmexteriorfacesfinder_getVerticesPtr: (a: number) => number;
     readonly wasmexteriorfacesfinder_new: (a: number) => number;
+    readonly wasmexteriorfacesfinder_pickVoxel: (a: number, b: number, c: number, d: number, e: number) => [number, number];
+    readonly wasmexteriorfacesfinder_setAoCurve: (a: number, b: number, c: number) => [number, number];
+    readonly wasmexteriorfacesfinder_setAoEnabled: (a: number, b: number) => void;
+    readonly wasmexteriorfacesfinder_setAoStrength: (a: number, b: number) => [number, number];
+    readonly wasmexteriorfacesfinder_setBitmaskBackend: (a: number, b: number) => void;
+    readonly wasmexteriorfacesfinder_setBlockMaterials: (a: number, b: number, c: number) => [number, number];
+    readonly wasmexteriorfacesfinder_setColorPalette: (a: number, b: number, c: number, d: number) => [number, number];
+    readonly wasmexteriorfacesfinder_setColorPaletteHex: (a: number, b: number, c: number, d: number) => void;
+    readonly wasmexteriorfacesfinder_setExteriorOnly: (a: number, b: number) => void;
+    readonly wasmexteriorfacesfinder_setPackedOutput: (a: number, b: number) => void;
+    readonly wasmexteriorfacesfinder_setPickingEnabled: (a: number, b: number) => void;
+    readonly wasmexteriorfacesfinder_setSmoothMode: (a: number, b: number) => void;
+    readonly wasmexteriorfacesfinder_setTiledUvs: (a: number, b: number) => void;
+    readonly wasmincrementalmesher_getAO: (a: number) => [number, number];
+    readonly wasmincrementalmesher_getFaceIndexRanges: (a: number) => [number, number];
+    readonly wasmincrementalmesher_getIndexCount: (a: number) => number;
+    readonly wasmincrementalmesher_getIndices: (a: number) => [number, number];
+    readonly wasmincrementalmesher_getIsSelected: (a: number) => [number, number];
+    readonly wasmincrementalmesher_getNormals: (a: number) => [number, number];
+    readonly wasmincrementalmesher_getQuadIds: (a: number) => [number, number];
+    readonly wasmincrementalmesher_getTranslucentFaceIndexRanges: (a: number) => [number, number];
+    readonly wasmincrementalmesher_getTranslucentIndexStart: (a: number) => number;
+    readonly wasmincrementalmesher_getUVs: (a: number) => [number, number];
+    readonly wasmincrementalmesher_getVertexCount: (a: number) => number;
+    readonly wasmincrementalmesher_getVertices: (a: number) => [number, number];
+    readonly wasmincrementalmesher_new: (a: number) => number;
+    readonly wasmincrementalmesher_pickVoxel: (a: number, b: number, c: number, d: number, e: number) => [number, number];
+    readonly wasmincrementalmesher_remeshFull: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number, i: number, j: number, k: number, l: number, m: number, n: number, o: number) => [number, number];
+    readonly wasmincrementalmesher_remeshRegion: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number, i: number, j: number, k: number, l: number, m: number, n: number, o: number, p: number, q: number, r: number, s: number, t: number, u: number) => [number, number];
+    readonly wasmincrementalmesher_setBlockMaterials: (a: number, b: number, c: number) => [number, number];
+    readonly wasmincrementalmesher_setPickingEnabled: (a: number, b: number) => void;
+    readonly wasmMemory: () => any;
     readonly __wbindgen_externrefs: WebAssembly.Table;
     readonly __wbindgen_malloc: (a: number, b: number) => number;
+    readonly __externref_table_dealloc: (a: number) => void;
     readonly __wbindgen_free: (a: number, b: number, c: number) => void;
     readonly __wbindgen_start: () => void;
 }
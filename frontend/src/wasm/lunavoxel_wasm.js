@@ -11,6 +11,68 @@ export class WasmExteriorFacesFinder {
         const ptr = this.__destroy_into_raw();
         wasm.__wbg_wasmexteriorfacesfinder_free(ptr, 0);
     }
+    clearColorPalette() {
+        wasm.wasmexteriorfacesfinder_clearColorPalette(this.__wbg_ptr);
+    }
+    /**
+     * @param {number} format
+     * @param {number} scale
+     * @param {number} axis
+     * @returns {Uint8Array}
+     */
+    exportMesh(format, scale, axis) {
+        const ret = wasm.wasmexteriorfacesfinder_exportMesh(this.__wbg_ptr, format, scale, axis);
+        if (ret[3]) {
+            throw takeFromExternrefTable0(ret[2]);
+        }
+        var v1 = getArrayU8FromWasm0(ret[0], ret[1]).slice();
+        wasm.__wbindgen_free(ret[0], ret[1] * 1, 1);
+        return v1;
+    }
+    /**
+     * @param {Uint8Array} voxel_data
+     * @param {number} texture_width
+     * @param {Int32Array} block_atlas_mapping
+     * @param {number} dim_x
+     * @param {number} dim_y
+     * @param {number} dim_z
+     * @param {Uint8Array} selection_data
+     * @param {number} selection_dim_x
+     * @param {number} selection_dim_y
+     * @param {number} selection_dim_z
+     * @param {boolean} selection_empty
+     */
+    findExteriorFaces(voxel_data, texture_width, block_atlas_mapping, dim_x, dim_y, dim_z, selection_data, selection_dim_x, selection_dim_y, selection_dim_z, selection_empty) {
+        const ptr0 = passArray8ToWasm0(voxel_data, wasm.__wbindgen_malloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ptr1 = passArray32ToWasm0(block_atlas_mapping, wasm.__wbindgen_malloc);
+        const len1 = WASM_VECTOR_LEN;
+        const ptr2 = passArray8ToWasm0(selection_data, wasm.__wbindgen_malloc);
+        const len2 = WASM_VECTOR_LEN;
+        const ret = wasm.wasmexteriorfacesfinder_findExteriorFaces(this.__wbg_ptr, ptr0, len0, texture_width, ptr1, len1, dim_x, dim_y, dim_z, ptr2, len2, selection_dim_x, selection_dim_y, selection_dim_z, selection_empty);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
+    }
+    /**
+     * @param {Uint8Array} voxel_data
+     * @param {number} texture_width
+     * @param {Int32Array} block_atlas_mapping
+     * @param {number} dim_x
+     * @param {number} dim_y
+     * @param {number} dim_z
+     * @param {number} lod_factor
+     */
+    findExteriorFacesLod(voxel_data, texture_width, block_atlas_mapping, dim_x, dim_y, dim_z, lod_factor) {
+        const ptr0 = passArray8ToWasm0(voxel_data, wasm.__wbindgen_malloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ptr1 = passArray32ToWasm0(block_atlas_mapping, wasm.__wbindgen_malloc);
+        const len1 = WASM_VECTOR_LEN;
+        const ret = wasm.wasmexteriorfacesfinder_findExteriorFacesLod(this.__wbg_ptr, ptr0, len0, texture_width, ptr1, len1, dim_x, dim_y, dim_z, lod_factor);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
+    }
     /**
      * @param {Uint8Array} voxel_data
      * @param {number} texture_width
@@ -18,22 +80,23 @@ export class WasmExteriorFacesFinder {
      * @param {number} dim_x
      * @param {number} dim_y
      * @param {number} dim_z
-     * @param {number} max_vertices
-     * @param {number} max_indices
      * @param {Uint8Array} selection_data
      * @param {number} selection_dim_x
      * @param {number} selection_dim_y
      * @param {number} selection_dim_z
      * @param {boolean} selection_empty
      */
-    findExteriorFaces(voxel_data, texture_width, block_atlas_mapping, dim_x, dim_y, dim_z, max_vertices, max_indices, selection_data, selection_dim_x, selection_dim_y, selection_dim_z, selection_empty) {
+    findExteriorFacesWithHalo(voxel_data, texture_width, block_atlas_mapping, dim_x, dim_y, dim_z, selection_data, selection_dim_x, selection_dim_y, selection_dim_z, selection_empty) {
         const ptr0 = passArray8ToWasm0(voxel_data, wasm.__wbindgen_malloc);
         const len0 = WASM_VECTOR_LEN;
         const ptr1 = passArray32ToWasm0(block_atlas_mapping, wasm.__wbindgen_malloc);
         const len1 = WASM_VECTOR_LEN;
         const ptr2 = passArray8ToWasm0(selection_data, wasm.__wbindgen_malloc);
         const len2 = WASM_VECTOR_LEN;
-        wasm.wasmexteriorfacesfinder_findExteriorFaces(this.__wbg_ptr, ptr0, len0, texture_width, ptr1, len1, dim_x, dim_y, dim_z, max_vertices, max_indices, ptr2, len2, selection_dim_x, selection_dim_y, selection_dim_z, selection_empty);
+        const ret = wasm.wasmexteriorfacesfinder_findExteriorFacesWithHalo(this.__wbg_ptr, ptr0, len0, texture_width, ptr1, len1, dim_x, dim_y, dim_z, ptr2, len2, selection_dim_x, selection_dim_y, selection_dim_z, selection_empty);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
     }
     /**
      * @returns {Float32Array}
@@ -44,6 +107,68 @@ export class WasmExteriorFacesFinder {
         wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
         return v1;
     }
+    /**
+     * @returns {number}
+     */
+    getAOPtr() {
+        const ret = wasm.wasmexteriorfacesfinder_getAOPtr(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * @returns {Float32Array}
+     */
+    getBlockTypes() {
+        const ret = wasm.wasmexteriorfacesfinder_getBlockTypes(this.__wbg_ptr);
+        var v1 = getArrayF32FromWasm0(ret[0], ret[1]).slice();
+        wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+        return v1;
+    }
+    /**
+     * @returns {number}
+     */
+    getBlockTypesPtr() {
+        const ret = wasm.wasmexteriorfacesfinder_getBlockTypesPtr(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * @returns {number}
+     */
+    getCavityCount() {
+        const ret = wasm.wasmexteriorfacesfinder_getCavityCount(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * @returns {number}
+     */
+    getCavityVolume() {
+        const ret = wasm.wasmexteriorfacesfinder_getCavityVolume(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * @returns {Float32Array}
+     */
+    getColors() {
+        const ret = wasm.wasmexteriorfacesfinder_getColors(this.__wbg_ptr);
+        var v1 = getArrayF32FromWasm0(ret[0], ret[1]).slice();
+        wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+        return v1;
+    }
+    /**
+     * @returns {number}
+     */
+    getColorsPtr() {
+        const ret = wasm.wasmexteriorfacesfinder_getColorsPtr(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * @returns {Uint32Array}
+     */
+    getFaceIndexRanges() {
+        const ret = wasm.wasmexteriorfacesfinder_getFaceIndexRanges(this.__wbg_ptr);
+        var v1 = getArrayU32FromWasm0(ret[0], ret[1]).slice();
+        wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+        return v1;
+    }
     /**
      * @returns {number}
      */
@@ -60,6 +185,13 @@ export class WasmExteriorFacesFinder {
         wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
         return v1;
     }
+    /**
+     * @returns {number}
+     */
+    getIndicesPtr() {
+        const ret = wasm.wasmexteriorfacesfinder_getIndicesPtr(this.__wbg_ptr);
+        return ret >>> 0;
+    }
     /**
      * @returns {Float32Array}
      */
@@ -69,6 +201,13 @@ export class WasmExteriorFacesFinder {
         wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
         return v1;
     }
+    /**
+     * @returns {number}
+     */
+    getIsSelectedPtr() {
+        const ret = wasm.wasmexteriorfacesfinder_getIsSelectedPtr(this.__wbg_ptr);
+        return ret >>> 0;
+    }
     /**
      * @returns {Float32Array}
      */
@@ -78,6 +217,84 @@ export class WasmExteriorFacesFinder {
         wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
         return v1;
     }
+    /**
+     * @returns {number}
+     */
+    getNormalsPtr() {
+        const ret = wasm.wasmexteriorfacesfinder_getNormalsPtr(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * @returns {Uint32Array}
+     */
+    getPackedVertices() {
+        const ret = wasm.wasmexteriorfacesfinder_getPackedVertices(this.__wbg_ptr);
+        var v1 = getArrayU32FromWasm0(ret[0], ret[1]).slice();
+        wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+        return v1;
+    }
+    /**
+     * @returns {number}
+     */
+    getPackedVerticesPtr() {
+        const ret = wasm.wasmexteriorfacesfinder_getPackedVerticesPtr(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * @returns {number}
+     */
+    getPackedWordsPerVertex() {
+        const ret = wasm.wasmexteriorfacesfinder_getPackedWordsPerVertex(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * @returns {Uint32Array}
+     */
+    getQuadIds() {
+        const ret = wasm.wasmexteriorfacesfinder_getQuadIds(this.__wbg_ptr);
+        var v1 = getArrayU32FromWasm0(ret[0], ret[1]).slice();
+        wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+        return v1;
+    }
+    /**
+     * @returns {number}
+     */
+    getQuadIdsPtr() {
+        const ret = wasm.wasmexteriorfacesfinder_getQuadIdsPtr(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * @returns {Float32Array}
+     */
+    getTileOrigins() {
+        const ret = wasm.wasmexteriorfacesfinder_getTileOrigins(this.__wbg_ptr);
+        var v1 = getArrayF32FromWasm0(ret[0], ret[1]).slice();
+        wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+        return v1;
+    }
+    /**
+     * @returns {number}
+     */
+    getTileOriginsPtr() {
+        const ret = wasm.wasmexteriorfacesfinder_getTileOriginsPtr(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * @returns {Uint32Array}
+     */
+    getTranslucentFaceIndexRanges() {
+        const ret = wasm.wasmexteriorfacesfinder_getTranslucentFaceIndexRanges(this.__wbg_ptr);
+        var v1 = getArrayU32FromWasm0(ret[0], ret[1]).slice();
+        wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+        return v1;
+    }
+    /**
+     * @returns {number}
+     */
+    getTranslucentIndexStart() {
+        const ret = wasm.wasmexteriorfacesfinder_getTranslucentIndexStart(this.__wbg_ptr);
+        return ret >>> 0;
+    }
     /**
      * @returns {Float32Array}
      */
@@ -87,6 +304,13 @@ export class WasmExteriorFacesFinder {
         wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
         return v1;
     }
+    /**
+     * @returns {number}
+     */
+    getUVsPtr() {
+        const ret = wasm.wasmexteriorfacesfinder_getUVsPtr(this.__wbg_ptr);
+        return ret >>> 0;
+    }
     /**
      * @returns {number}
      */
@@ -103,6 +327,19 @@ export class WasmExteriorFacesFinder {
         wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
         return v1;
     }
+    /**
+     * The `get*Ptr` accessors return byte offsets into `wasmMemory().buffer` so JS
+     * can build `Float32Array`/`Uint32Array` views of length `getVertexCount() * n`
+     * or `getIndexCount()` without copying. A view is invalidated by the next
+     * `findExteriorFaces*` call, which may reallocate the buffers, and by any wasm
+     * allocation that grows linear memory, which detaches the old `ArrayBuffer`.
+     * Read or upload the views immediately and re-create them after every remesh.
+     * @returns {number}
+     */
+    getVerticesPtr() {
+        const ret = wasm.wasmexteriorfacesfinder_getVerticesPtr(this.__wbg_ptr);
+        return ret >>> 0;
+    }
     /**
      * @param {number} max_dimension
      */
@@ -112,12 +349,555 @@ export class WasmExteriorFacesFinder {
         WasmExteriorFacesFinderFinalization.register(this, this.__wbg_ptr, this);
         return this;
     }
+    /**
+     * @param {number} quad_id
+     * @param {number} hit_x
+     * @param {number} hit_y
+     * @param {number} hit_z
+     * @returns {Uint32Array | undefined}
+     */
+    pickVoxel(quad_id, hit_x, hit_y, hit_z) {
+        const ret = wasm.wasmexteriorfacesfinder_pickVoxel(this.__wbg_ptr, quad_id, hit_x, hit_y, hit_z);
+        let v1;
+        if (ret[0] !== 0) {
+            v1 = getArrayU32FromWasm0(ret[0], ret[1]).slice();
+            wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+        }
+        return v1;
+    }
+    /**
+     * @param {Float32Array} levels
+     */
+    setAoCurve(levels) {
+        const ptr0 = passArrayF32ToWasm0(levels, wasm.__wbindgen_malloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.wasmexteriorfacesfinder_setAoCurve(this.__wbg_ptr, ptr0, len0);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
+    }
+    /**
+     * @param {boolean} enabled
+     */
+    setAoEnabled(enabled) {
+        wasm.wasmexteriorfacesfinder_setAoEnabled(this.__wbg_ptr, enabled);
+    }
+    /**
+     * @param {number} strength
+     */
+    setAoStrength(strength) {
+        const ret = wasm.wasmexteriorfacesfinder_setAoStrength(this.__wbg_ptr, strength);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
+    }
+    /**
+     * @param {boolean} enabled
+     */
+    setBitmaskBackend(enabled) {
+        wasm.wasmexteriorfacesfinder_setBitmaskBackend(this.__wbg_ptr, enabled);
+    }
+    /**
+     * @param {Uint8Array} block_classes
+     */
+    setBlockMaterials(block_classes) {
+        const ptr0 = passArray8ToWasm0(block_classes, wasm.__wbindgen_malloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.wasmexteriorfacesfinder_setBlockMaterials(this.__wbg_ptr, ptr0, len0);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
+    }
+    /**
+     * @param {Float32Array} rgba
+     * @param {boolean} premultiply_ao
+     */
+    setColorPalette(rgba, premultiply_ao) {
+        const ptr0 = passArrayF32ToWasm0(rgba, wasm.__wbindgen_malloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.wasmexteriorfacesfinder_setColorPalette(this.__wbg_ptr, ptr0, len0, premultiply_ao);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
+    }
+    /**
+     * @param {Uint32Array} colors
+     * @param {boolean} premultiply_ao
+     */
+    setColorPaletteHex(colors, premultiply_ao) {
+        const ptr0 = passArray32ToWasm0(colors, wasm.__wbindgen_malloc);
+        const len0 = WASM_VECTOR_LEN;
+        wasm.wasmexteriorfacesfinder_setColorPaletteHex(this.__wbg_ptr, ptr0, len0, premultiply_ao);
+    }
+    /**
+     * @param {boolean} exterior_only
+     */
+    setExteriorOnly(exterior_only) {
+        wasm.wasmexteriorfacesfinder_setExteriorOnly(this.__wbg_ptr, exterior_only);
+    }
+    /**
+     * @param {boolean} packed
+     */
+    setPackedOutput(packed) {
+        wasm.wasmexteriorfacesfinder_setPackedOutput(this.__wbg_ptr, packed);
+    }
+    /**
+     * @param {boolean} enabled
+     */
+    setPickingEnabled(enabled) {
+        wasm.wasmexteriorfacesfinder_setPickingEnabled(this.__wbg_ptr, enabled);
+    }
+    /**
+     * @param {boolean} smooth
+     */
+    setSmoothMode(smooth) {
+        wasm.wasmexteriorfacesfinder_setSmoothMode(this.__wbg_ptr, smooth);
+    }
+    /**
+     * @param {boolean} tiled
+     */
+    setTiledUvs(tiled) {
+        wasm.wasmexteriorfacesfinder_setTiledUvs(this.__wbg_ptr, tiled);
+    }
 }
 if (Symbol.dispose) WasmExteriorFacesFinder.prototype[Symbol.dispose] = WasmExteriorFacesFinder.prototype.free;
 
+export class WasmIncrementalMesher {
+    __destroy_into_raw() {
+        const ptr = this.__wbg_ptr;
+        this.__wbg_ptr = 0;
+        WasmIncrementalMesherFinalization.unregister(this);
+        return ptr;
+    }
+    free() {
+        const ptr = this.__destroy_into_raw();
+        wasm.__wbg_wasmincrementalmesher_free(ptr, 0);
+    }
+    /**
+     * @returns {Float32Array}
+     */
+    getAO() {
+        const ret = wasm.wasmincrementalmesher_getAO(this.__wbg_ptr);
+        var v1 = getArrayF32FromWasm0(ret[0], ret[1]).slice();
+        wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+        return v1;
+    }
+    /**
+     * @returns {Uint32Array}
+     */
+    getFaceIndexRanges() {
+        const ret = wasm.wasmincrementalmesher_getFaceIndexRanges(this.__wbg_ptr);
+        var v1 = getArrayU32FromWasm0(ret[0], ret[1]).slice();
+        wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+        return v1;
+    }
+    /**
+     * @returns {number}
+     */
+    getIndexCount() {
+        const ret = wasm.wasmincrementalmesher_getIndexCount(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * @returns {Uint32Array}
+     */
+    getIndices() {
+        const ret = wasm.wasmincrementalmesher_getIndices(this.__wbg_ptr);
+        var v1 = getArrayU32FromWasm0(ret[0], ret[1]).slice();
+        wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+        return v1;
+    }
+    /**
+     * @returns {Float32Array}
+     */
+    getIsSelected() {
+        const ret = wasm.wasmincrementalmesher_getIsSelected(this.__wbg_ptr);
+        var v1 = getArrayF32FromWasm0(ret[0], ret[1]).slice();
+        wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+        return v1;
+    }
+    /**
+     * @returns {Float32Array}
+     */
+    getNormals() {
+        const ret = wasm.wasmincrementalmesher_getNormals(this.__wbg_ptr);
+        var v1 = getArrayF32FromWasm0(ret[0], ret[1]).slice();
+        wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+        return v1;
+    }
+    /**
+     * @returns {Uint32Array}
+     */
+    getQuadIds() {
+        const ret = wasm.wasmincrementalmesher_getQuadIds(this.__wbg_ptr);
+        var v1 = getArrayU32FromWasm0(ret[0], ret[1]).slice();
+        wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+        return v1;
+    }
+    /**
+     * @returns {Uint32Array}
+     */
+    getTranslucentFaceIndexRanges() {
+        const ret = wasm.wasmincrementalmesher_getTranslucentFaceIndexRanges(this.__wbg_ptr);
+        var v1 = getArrayU32FromWasm0(ret[0], ret[1]).slice();
+        wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+        return v1;
+    }
+    /**
+     * @returns {number}
+     */
+    getTranslucentIndexStart() {
+        const ret = wasm.wasmincrementalmesher_getTranslucentIndexStart(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * @returns {Float32Array}
+     */
+    getUVs() {
+        const ret = wasm.wasmincrementalmesher_getUVs(this.__wbg_ptr);
+        var v1 = getArrayF32FromWasm0(ret[0], ret[1]).slice();
+        wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+        return v1;
+    }
+    /**
+     * @returns {number}
+     */
+    getVertexCount() {
+        const ret = wasm.wasmincrementalmesher_getVertexCount(this.__wbg_ptr);
+        return ret >>> 0;
+    }
+    /**
+     * @returns {Float32Array}
+     */
+    getVertices() {
+        const ret = wasm.wasmincrementalmesher_getVertices(this.__wbg_ptr);
+        var v1 = getArrayF32FromWasm0(ret[0], ret[1]).slice();
+        wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+        return v1;
+    }
+    /**
+     * @param {number} max_dimension
+     */
+    constructor(max_dimension) {
+        const ret = wasm.wasmincrementalmesher_new(max_dimension);
+        this.__wbg_ptr = ret >>> 0;
+        WasmIncrementalMesherFinalization.register(this, this.__wbg_ptr, this);
+        return this;
+    }
+    /**
+     * @param {number} quad_id
+     * @param {number} hit_x
+     * @param {number} hit_y
+     * @param {number} hit_z
+     * @returns {Uint32Array | undefined}
+     */
+    pickVoxel(quad_id, hit_x, hit_y, hit_z) {
+        const ret = wasm.wasmincrementalmesher_pickVoxel(this.__wbg_ptr, quad_id, hit_x, hit_y, hit_z);
+        let v1;
+        if (ret[0] !== 0) {
+            v1 = getArrayU32FromWasm0(ret[0], ret[1]).slice();
+            wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+        }
+        return v1;
+    }
+    /**
+     * @param {Uint8Array} voxel_data
+     * @param {number} texture_width
+     * @param {Int32Array} block_atlas_mapping
+     * @param {number} dim_x
+     * @param {number} dim_y
+     * @param {number} dim_z
+     * @param {Uint8Array} selection_data
+     * @param {number} selection_dim_x
+     * @param {number} selection_dim_y
+     * @param {number} selection_dim_z
+     * @param {boolean} selection_empty
+     */
+    remeshFull(voxel_data, texture_width, block_atlas_mapping, dim_x, dim_y, dim_z, selection_data, selection_dim_x, selection_dim_y, selection_dim_z, selection_empty) {
+        const ptr0 = passArray8ToWasm0(voxel_data, wasm.__wbindgen_malloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ptr1 = passArray32ToWasm0(block_atlas_mapping, wasm.__wbindgen_malloc);
+        const len1 = WASM_VECTOR_LEN;
+        const ptr2 = passArray8ToWasm0(selection_data, wasm.__wbindgen_malloc);
+        const len2 = WASM_VECTOR_LEN;
+        const ret = wasm.wasmincrementalmesher_remeshFull(this.__wbg_ptr, ptr0, len0, texture_width, ptr1, len1, dim_x, dim_y, dim_z, ptr2, len2, selection_dim_x, selection_dim_y, selection_dim_z, selection_empty);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
+    }
+    /**
+     * @param {Uint8Array} voxel_data
+     * @param {number} texture_width
+     * @param {Int32Array} block_atlas_mapping
+     * @param {number} dim_x
+     * @param {number} dim_y
+     * @param {number} dim_z
+     * @param {number} min_x
+     * @param {number} min_y
+     * @param {number} min_z
+     * @param {number} max_x
+     * @param {number} max_y
+     * @param {number} max_z
+     * @param {Uint8Array} selection_data
+     * @param {number} selection_dim_x
+     * @param {number} selection_dim_y
+     * @param {number} selection_dim_z
+     * @param {boolean} selection_empty
+     */
+    remeshRegion(voxel_data, texture_width, block_atlas_mapping, dim_x, dim_y, dim_z, min_x, min_y, min_z, max_x, max_y, max_z, selection_data, selection_dim_x, selection_dim_y, selection_dim_z, selection_empty) {
+        const ptr0 = passArray8ToWasm0(voxel_data, wasm.__wbindgen_malloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ptr1 = passArray32ToWasm0(block_atlas_mapping, wasm.__wbindgen_malloc);
+        const len1 = WASM_VECTOR_LEN;
+        const ptr2 = passArray8ToWasm0(selection_data, wasm.__wbindgen_malloc);
+        const len2 = WASM_VECTOR_LEN;
+        const ret = wasm.wasmincrementalmesher_remeshRegion(this.__wbg_ptr, ptr0, len0, texture_width, ptr1, len1, dim_x, dim_y, dim_z, min_x, min_y, min_z, max_x, max_y, max_z, ptr2, len2, selection_dim_x, selection_dim_y, selection_dim_z, selection_empty);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
+    }
+    /**
+     * @param {Uint8Array} block_classes
+     */
+    setBlockMaterials(block_classes) {
+        const ptr0 = passArray8ToWasm0(block_classes, wasm.__wbindgen_malloc);
+        const len0 = WASM_VECTOR_LEN;
+        const ret = wasm.wasmincrementalmesher_setBlockMaterials(this.__wbg_ptr, ptr0, len0);
+        if (ret[1]) {
+            throw takeFromExternrefTable0(ret[0]);
+        }
+    }
+    /**
+     * @param {boolean} enabled
+     */
+    setPickingEnabled(enabled) {
+        wasm.wasmincrementalmesher_setPickingEnabled(this.__wbg_ptr, enabled);
+    }
+}
+if (Symbol.dispose) WasmIncrementalMesher.prototype[Symbol.dispose] = WasmIncrementalMesher.prototype.free;
+
+/**
+ * @param {Uint8Array} selection_data
+ * @param {number} selection_dim_x
+ * @param {number} selection_dim_y
+ * @param {number} selection_dim_z
+ * @returns {Float32Array}
+ */
+export function buildSelectionOutline(selection_data, selection_dim_x, selection_dim_y, selection_dim_z) {
+    const ptr0 = passArray8ToWasm0(selection_data, wasm.__wbindgen_malloc);
+    const len0 = WASM_VECTOR_LEN;
+    const ret = wasm.buildSelectionOutline(ptr0, len0, selection_dim_x, selection_dim_y, selection_dim_z);
+    if (ret[3]) {
+        throw takeFromExternrefTable0(ret[2]);
+    }
+    var v2 = getArrayF32FromWasm0(ret[0], ret[1]).slice();
+    wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+    return v2;
+}
+
+/**
+ * @param {Uint8Array} voxel_data
+ * @returns {Uint8Array}
+ */
+export function compressVoxelData(voxel_data) {
+    const ptr0 = passArray8ToWasm0(voxel_data, wasm.__wbindgen_malloc);
+    const len0 = WASM_VECTOR_LEN;
+    const ret = wasm.compressVoxelData(ptr0, len0);
+    if (ret[3]) {
+        throw takeFromExternrefTable0(ret[2]);
+    }
+    var v2 = getArrayU8FromWasm0(ret[0], ret[1]).slice();
+    wasm.__wbindgen_free(ret[0], ret[1] * 1, 1);
+    return v2;
+}
+
+/**
+ * @param {Uint8Array} compressed_data
+ * @returns {Uint8Array}
+ */
+export function decompressVoxelData(compressed_data) {
+    const ptr0 = passArray8ToWasm0(compressed_data, wasm.__wbindgen_malloc);
+    const len0 = WASM_VECTOR_LEN;
+    const ret = wasm.decompressVoxelData(ptr0, len0);
+    if (ret[3]) {
+        throw takeFromExternrefTable0(ret[2]);
+    }
+    var v2 = getArrayU8FromWasm0(ret[0], ret[1]).slice();
+    wasm.__wbindgen_free(ret[0], ret[1] * 1, 1);
+    return v2;
+}
+
+/**
+ * @param {Uint8Array} compressed_data
+ * @param {Uint8Array} target
+ * @returns {number}
+ */
+export function decompressVoxelDataInto(compressed_data, target) {
+    const ptr0 = passArray8ToWasm0(compressed_data, wasm.__wbindgen_malloc);
+    const len0 = WASM_VECTOR_LEN;
+    var ptr1 = passArray8ToWasm0(target, wasm.__wbindgen_malloc);
+    var len1 = WASM_VECTOR_LEN;
+    const ret = wasm.decompressVoxelDataInto(ptr0, len0, ptr1, len1, target);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return ret[0] >>> 0;
+}
+
+/**
+ * @param {Int32Array} per_block_mapping
+ * @returns {Int32Array}
+ */
+export function expandBlockAtlasMapping(per_block_mapping) {
+    const ptr0 = passArray32ToWasm0(per_block_mapping, wasm.__wbindgen_malloc);
+    const len0 = WASM_VECTOR_LEN;
+    const ret = wasm.expandBlockAtlasMapping(ptr0, len0);
+    var v2 = getArrayI32FromWasm0(ret[0], ret[1]).slice();
+    wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+    return v2;
+}
+
+/**
+ * @param {Uint8Array} voxel_data
+ * @param {number} dim_x
+ * @param {number} dim_y
+ * @param {number} dim_z
+ * @param {number} format
+ * @param {number} scale
+ * @param {number} axis
+ * @param {Float32Array} palette_rgba
+ * @returns {Uint8Array}
+ */
+export function exportVoxelVolume(voxel_data, dim_x, dim_y, dim_z, format, scale, axis, palette_rgba) {
+    const ptr0 = passArray8ToWasm0(voxel_data, wasm.__wbindgen_malloc);
+    const len0 = WASM_VECTOR_LEN;
+    const ptr1 = passArrayF32ToWasm0(palette_rgba, wasm.__wbindgen_malloc);
+    const len1 = WASM_VECTOR_LEN;
+    const ret = wasm.exportVoxelVolume(ptr0, len0, dim_x, dim_y, dim_z, format, scale, axis, ptr1, len1);
+    if (ret[3]) {
+        throw takeFromExternrefTable0(ret[2]);
+    }
+    var v3 = getArrayU8FromWasm0(ret[0], ret[1]).slice();
+    wasm.__wbindgen_free(ret[0], ret[1] * 1, 1);
+    return v3;
+}
+
+/**
+ * @returns {number}
+ */
+export function facesPerBlock() {
+    const ret = wasm.facesPerBlock();
+    return ret >>> 0;
+}
+
+/**
+ * @param {Uint8Array} voxel_data
+ * @param {number} dim_x
+ * @param {number} dim_y
+ * @param {number} dim_z
+ * @param {number} shape
+ * @param {number} min_x
+ * @param {number} min_y
+ * @param {number} min_z
+ * @param {number} max_x
+ * @param {number} max_y
+ * @param {number} max_z
+ * @param {number} up_face_dir
+ * @param {number} wall_thickness
+ * @param {number} mode
+ * @param {number} block_type
+ * @param {boolean} preview
+ * @returns {number}
+ */
+export function fillShape(voxel_data, dim_x, dim_y, dim_z, shape, min_x, min_y, min_z, max_x, max_y, max_z, up_face_dir, wall_thickness, mode, block_type, preview) {
+    var ptr0 = passArray8ToWasm0(voxel_data, wasm.__wbindgen_malloc);
+    var len0 = WASM_VECTOR_LEN;
+    const ret = wasm.fillShape(ptr0, len0, voxel_data, dim_x, dim_y, dim_z, shape, min_x, min_y, min_z, max_x, max_y, max_z, up_face_dir, wall_thickness, mode, block_type, preview);
+    if (ret[2]) {
+        throw takeFromExternrefTable0(ret[1]);
+    }
+    return ret[0] >>> 0;
+}
+
+/**
+ * @param {Uint8Array} voxel_data
+ * @param {number} dim_x
+ * @param {number} dim_y
+ * @param {number} dim_z
+ * @param {number} seed_x
+ * @param {number} seed_y
+ * @param {number} seed_z
+ * @param {number} connectivity
+ * @param {number} match_mode
+ * @param {number} max_voxels
+ * @param {Uint8Array} selection_data
+ * @returns {Uint32Array}
+ */
+export function floodFill(voxel_data, dim_x, dim_y, dim_z, seed_x, seed_y, seed_z, connectivity, match_mode, max_voxels, selection_data) {
+    const ptr0 = passArray8ToWasm0(voxel_data, wasm.__wbindgen_malloc);
+    const len0 = WASM_VECTOR_LEN;
+    var ptr1 = passArray8ToWasm0(selection_data, wasm.__wbindgen_malloc);
+    var len1 = WASM_VECTOR_LEN;
+    const ret = wasm.floodFill(ptr0, len0, dim_x, dim_y, dim_z, seed_x, seed_y, seed_z, connectivity, match_mode, max_voxels, ptr1, len1, selection_data);
+    if (ret[3]) {
+        throw takeFromExternrefTable0(ret[2]);
+    }
+    var v3 = getArrayU32FromWasm0(ret[0], ret[1]).slice();
+    wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+    return v3;
+}
+
+/**
+ * @param {Uint8Array} voxel_data
+ * @param {number} dim_x
+ * @param {number} dim_y
+ * @param {number} dim_z
+ * @param {number} origin_x
+ * @param {number} origin_y
+ * @param {number} origin_z
+ * @param {number} direction_x
+ * @param {number} direction_y
+ * @param {number} direction_z
+ * @param {number} max_distance
+ * @returns {Float32Array | undefined}
+ */
+export function raycastVoxels(voxel_data, dim_x, dim_y, dim_z, origin_x, origin_y, origin_z, direction_x, direction_y, direction_z, max_distance) {
+    const ptr0 = passArray8ToWasm0(voxel_data, wasm.__wbindgen_malloc);
+    const len0 = WASM_VECTOR_LEN;
+    const ret = wasm.raycastVoxels(ptr0, len0, dim_x, dim_y, dim_z, origin_x, origin_y, origin_z, direction_x, direction_y, direction_z, max_distance);
+    if (ret[3]) {
+        throw takeFromExternrefTable0(ret[2]);
+    }
+    let v2;
+    if (ret[0] !== 0) {
+        v2 = getArrayF32FromWasm0(ret[0], ret[1]).slice();
+        wasm.__wbindgen_free(ret[0], ret[1] * 4, 4);
+    }
+    return v2;
+}
+
+/**
+ * @returns {any}
+ */
+export function wasmMemory() {
+    const ret = wasm.wasmMemory();
+    return ret;
+}
+
 function __wbg_get_imports() {
     const import0 = {
         __proto__: null,
+        __wbg_Error_8c4e43fe74559d73: function(arg0, arg1) {
+            const ret = Error(getStringFromWasm0(arg0, arg1));
+            return ret;
+        },
+        __wbg___wbindgen_copy_to_typed_array_fc0809a4dec43528: function(arg0, arg1, arg2) {
+            new Uint8Array(arg2.buffer, arg2.byteOffset, arg2.byteLength).set(getArrayU8FromWasm0(arg0, arg1));
+        },
+        __wbg___wbindgen_memory_bd1fbcf21fbef3c8: function() {
+            const ret = wasm.memory;
+            return ret;
+        },
         __wbg___wbindgen_throw_be289d5034ed271b: function(arg0, arg1) {
             throw new Error(getStringFromWasm0(arg0, arg1));
         },
@@ -140,17 +920,30 @@ function __wbg_get_imports() {
 const WasmExteriorFacesFinderFinalization = (typeof FinalizationRegistry === 'undefined')
     ? { register: () => {}, unregister: () => {} }
     : new FinalizationRegistry(ptr => wasm.__wbg_wasmexteriorfacesfinder_free(ptr >>> 0, 1));
+const WasmIncrementalMesherFinalization = (typeof FinalizationRegistry === 'undefined')
+    ? { register: () => {}, unregister: () => {} }
+    : new FinalizationRegistry(ptr => wasm.__wbg_wasmincrementalmesher_free(ptr >>> 0, 1));
 
 function getArrayF32FromWasm0(ptr, len) {
     ptr = ptr >>> 0;
     return getFloat32ArrayMemory0().subarray(ptr / 4, ptr / 4 + len);
 }
 
+function getArrayI32FromWasm0(ptr, len) {
+    ptr = ptr >>> 0;
+    return getInt32ArrayMemory0().subarray(ptr / 4, ptr / 4 + len);
+}
+
 function getArrayU32FromWasm0(ptr, len) {
     ptr = ptr >>> 0;
     return getUint32ArrayMemory0().subarray(ptr / 4, ptr / 4 + len);
 }
 
+function getArrayU8FromWasm0(ptr, len) {
+    ptr = ptr >>> 0;
+    return getUint8ArrayMemory0().subarray(ptr / 1, ptr / 1 + len);
+}
+
 let cachedFloat32ArrayMemory0 = null;
 function getFloat32ArrayMemory0() {
     if (cachedFloat32ArrayMemory0 === null || cachedFloat32ArrayMemory0.byteLength === 0) {
@@ -159,6 +952,14 @@ function getFloat32ArrayMemory0() {
     return cachedFloat32ArrayMemory0;
 }
 
+let cachedInt32ArrayMemory0 = null;
+function getInt32ArrayMemory0() {
+    if (cachedInt32ArrayMemory0 === null || cachedInt32ArrayMemory0.byteLength === 0) {
+        cachedInt32ArrayMemory0 = new Int32Array(wasm.memory.buffer);
+    }
+    return cachedInt32ArrayMemory0;
+}
+
 function getStringFromWasm0(ptr, len) {
     ptr = ptr >>> 0;
     return decodeText(ptr, len);
@@ -194,6 +995,19 @@ function passArray8ToWasm0(arg, malloc) {
     return ptr;
 }
 
+function passArrayF32ToWasm0(arg, malloc) {
+    const ptr = malloc(arg.length * 4, 4) >>> 0;
+    getFloat32ArrayMemory0().set(arg, ptr / 4);
+    WASM_VECTOR_LEN = arg.length;
+    return ptr;
+}
+
+function takeFromExternrefTable0(idx) {
+    const value = wasm.__wbindgen_externrefs.get(idx);
+    wasm.__externref_table_dealloc(idx);
+    return value;
+}
+
 let cachedTextDecoder = new TextDecoder('utf-8', { ignoreBOM: true, fatal: true });
 cachedTextDecoder.decode();
 const MAX_SAFARI_DECODE_BYTES = 2146435072;
@@ -215,6 +1029,7 @@ function __wbg_finalize_init(instance, module) {
     wasm = instance.exports;
     wasmModule = module;
     cachedFloat32ArrayMemory0 = null;
+    cachedInt32ArrayMemory0 = null;
     cachedUint32ArrayMemory0 = null;
     cachedUint8ArrayMemory0 = null;
     wasm.__wbindgen_start();
@@ -2,17 +2,87 @@
 /* eslint-disable */
 export const memory: WebAssembly.Memory;
 export const __wbg_wasmexteriorfacesfinder_free: (a: number, b: number) => void;
-export const wasmexteriorfacesfinder_findExteriorFaces: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number, i: number, j: number, k: number, l: number, m: number, n: number, o: number, p: number, q: number) => void;
+export const __wbg_wasmincrementalmesher_free: (a: number, b: number) => void;
+export const buildSelectionOutline: (a: number, b: number, c: number, d: number, e: number) => [number, number, number, number];
+export const compressVoxelData: (a: number, b: number) => [number, number, number, number];
+export const decompressVoxelData: (a: number, b: number) => [number, number, number, number];
+export const decompressVoxelDataInto: (a: number, b: number, c: number, d: number, e: any) => [number, number, number];
+export const expandBlockAtlasMapping: (a: number, b: number) => [number, number];
+export const exportVoxelVolume: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number, i: number, j: number) => [number, number, number, number];
+export const facesPerBlock: () => number;
+export const fillShape: (a: number, b: number, c: any, d: number, e: number, f: number, g: number, h: number, i: number, j: number, k: number, l: number, m: number, n: number, o: number, p: number, q: number, r: number) => [number, number, number];
+export const floodFill: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number, i: number, j: number, k: number, l: number, m: number, n: any) => [number, number, number, number];
+export const raycastVoxels: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number, i: number, j: number, k: number, l: number) => [number, number, number, number];
+export const wasmexteriorfacesfinder_clearColorPalette: (a: number) => void;
+export const wasmexteriorfacesfinder_exportMesh: (a: number, b: number, c: number, d: number) => [number, number, number, number];
+export const wasmexteriorfacesfinder_findExteriorFaces: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number, i: number, j: number, k: number, l: number, m: number, n: number, o: number) => [number, number];
+export const wasmexteriorfacesfinder_findExteriorFacesLod: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number, i: number, j: number) => [number, number];
+export const wasmexteriorfacesfinder_findExteriorFacesWithHalo: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number, i: number, j: number, k: number, l: number, m: number, n: number, o: number) => [number, number];
 export const wasmexteriorfacesfinder_getAO: (a: number) => [number, number];
+export const wasmexteriorfacesfinder_getAOPtr: (a: number) => number;
+export const wasmexteriorfacesfinder_getBlockTypes: (a: number) => [number, number];
+export const wasmexteriorfacesfinder_getBlockTypesPtr: (a: number) => number;
+export const wasmexteriorfacesfinder_getCavityCount: (a: number) => number;
+export const wasmexteriorfacesfinder_getCavityVolume: (a: number) => number;
+export const wasmexteriorfacesfinder_getColors: (a: number) => [number, number];
+export const wasmexteriorfacesfinder_getColorsPtr: (a: number) => number;
+export const wasmexteriorfacesfinder_getFaceIndexRanges: (a: number) => [number, number];
 export const wasmexteriorfacesfinder_getIndexCount: (a: number) => number;
 export const wasmexteriorfacesfinder_getIndices: (a: number) => [number, number];
+export const wasmexteriorfacesfinder_getIndicesPtr: (a: number) => number;
 export const wasmexteriorfacesfinder_getIsSelected: (a: number) => [number, number];
+export const wasmexteriorfacesfinder_getIsSelectedPtr: (a: number) => number;
 export const wasmexteriorfacesfinder_getNormals: (a: number) => [number, number];
+export const wasmexteriorfacesfinder_getNormalsPtr: (a: number) => number;
+export const wasmexteriorfacesfinder_getPackedVertices: (a: number) => [number, number];
+export const wasmexteriorfacesfinder_getPackedVerticesPtr: (a: number) => number;
+export const wasmexteriorfacesfinder_getPackedWordsPerVertex: (a: number) => number;
+export const wasmexteriorfacesfinder_getQuadIds: (a: number) => [number, number];
+export const wasmexteriorfacesfinder_getQuadIdsPtr: (a: number) => number;
+export const wasmexteriorfacesfinder_getTileOrigins: (a: number) => [number, number];
+export const wasmexteriorfacesfinder_getTileOriginsPtr: (a: number) => number;
+export const wasmexteriorfacesfinder_getTranslucentFaceIndexRanges: (a: number) => [number, number];
+export const wasmexteriorfacesfinder_getTranslucentIndexStart: (a: number) => number;
 export const wasmexteriorfacesfinder_getUVs: (a: number) => [number, number];
+export const wasmexteriorfacesfinder_getUVsPtr: (a: number) => number;
 export const wasmexteriorfacesfinder_getVertexCount: (a: number) => number;
 export const wasmexteriorfacesfinder_getVertices: (a: number) => [number, number];
+export const wasmexteriorfacesfinder_getVerticesPtr: (a: number) => number;
 export const wasmexteriorfacesfinder_new: (a: number) => number;
+export const wasmexteriorfacesfinder_pickVoxel: (a: number, b: number, c: number, d: number, e: number) => [number, number];
+export const wasmexteriorfacesfinder_setAoCurve: (a: number, b: number, c: number) => [number, number];
+export const wasmexteriorfacesfinder_setAoEnabled: (a: number, b: number) => void;
+export const wasmexteriorfacesfinder_setAoStrength: (a: number, b: number) => [number, number];
+export const wasmexteriorfacesfinder_setBitmaskBackend: (a: number, b: number) => void;
+export const wasmexteriorfacesfinder_setBlockMaterials: (a: number, b: number, c: number) => [number, number];
+export const wasmexteriorfacesfinder_setColorPalette: (a: number, b: number, c: number, d: number) => [number, number];
+export const wasmexteriorfacesfinder_setColorPaletteHex: (a: number, b: number, c: number, d: number) => void;
+export const wasmexteriorfacesfinder_setExteriorOnly: (a: number, b: number) => void;
+export const wasmexteriorfacesfinder_setPackedOutput: (a: number, b: number) => void;
+export const wasmexteriorfacesfinder_setPickingEnabled: (a: number, b: number) => void;
+export const wasmexteriorfacesfinder_setSmoothMode: (a: number, b: number) => void;
+export const wasmexteriorfacesfinder_setTiledUvs: (a: number, b: number) => void;
+export const wasmincrementalmesher_getAO: (a: number) => [number, number];
+export const wasmincrementalmesher_getFaceIndexRanges: (a: number) => [number, number];
+export const wasmincrementalmesher_getIndexCount: (a: number) => number;
+export const wasmincrementalmesher_getIndices: (a: number) => [number, number];
+export const wasmincrementalmesher_getIsSelected: (a: number) => [number, number];
+export const wasmincrementalmesher_getNormals: (a: number) => [number, number];
+export const wasmincrementalmesher_getQuadIds: (a: number) => [number, number];
+export const wasmincrementalmesher_getTranslucentFaceIndexRanges: (a: number) => [number, number];
+export const wasmincrementalmesher_getTranslucentIndexStart: (a: number) => number;
+export const wasmincrementalmesher_getUVs: (a: number) => [number, number];
+export const wasmincrementalmesher_getVertexCount: (a: number) => number;
+export const wasmincrementalmesher_getVertices: (a: number) => [number, number];
+export const wasmincrementalmesher_new: (a: number) => number;
+export const wasmincrementalmesher_pickVoxel: (a: number, b: number, c: number, d: number, e: number) => [number, number];
+export const wasmincrementalmesher_remeshFull: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number, i: number, j: number, k: number, l: number, m: number, n: number, o: number) => [number, number];
+export const wasmincrementalmesher_remeshRegion: (a: number, b: number, c: number, d: number, e: number, f: number, g: number, h: number, i: number, j: number, k: number, l: number, m: number, n: number, o: number, p: number, q: number, r: number, s: number, t: number, u: number) => [number, number];
+export const wasmincrementalmesher_setBlockMaterials: (a: number, b: number, c: number) => [number, number];
+export const wasmincrementalmesher_setPickingEnabled: (a: number, b: number) => void;
+export const wasmMemory: () => any;
 export const __wbindgen_externrefs: WebAssembly.Table;
 export const __wbindgen_malloc: (a: number, b: number) => number;
+export const __externref_table_dealloc: (a: number) => void;
 export const __wbindgen_free: (a: number, b: number, c: number) => void;
 export const __wbindgen_start: () => void;
@@ -1,6 +1,9 @@
 use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use lunavoxel_wasm::bitmask_backend::MeshingBackend;
 use lunavoxel_wasm::find_exterior_faces::ExteriorFacesFinder;
+use lunavoxel_wasm::incremental_mesher::IncrementalMesher;
 use lunavoxel_wasm::mesh_arrays::MeshArrays;
+use lunavoxel_wasm::texture_coords::expand_block_atlas_mapping;
 
 fn create_filled_voxel_data(dim_x: usize, dim_y: usize, dim_z: usize) -> Vec<u8> {
     vec![1u8; dim_x * dim_y * dim_z]
@@ -26,7 +29,7 @@ fn bench_find_exterior_faces(c: &mut Criterion) {
     {
         let (dx, dy, dz) = (32, 32, 32);
         let data = create_filled_voxel_data(dx, dy, dz);
-        let mapping: Vec<i32> = (0..2).collect();
+        let mapping = expand_block_atlas_mapping(&[0, 1]);
         let sel = vec![0u8; dx * dy * dz];
         let max_dim = dx.max(dy).max(dz);
         let mut finder = ExteriorFacesFinder::new(max_dim);
@@ -52,12 +55,34 @@ fn bench_find_exterior_faces(c: &mut Criterion) {
                 );
             });
         });
+
+        let mut bitmask_finder = ExteriorFacesFinder::new(max_dim);
+        bitmask_finder.set_backend(MeshingBackend::Bitmask);
+
+        group.bench_function("solid_32x32x32_bitmask", |b| {
+            b.iter(|| {
+                bitmask_finder.find_exterior_faces(
+                    black_box(&data),
+                    4,
+                    &mapping,
+                    dx,
+                    dy,
+                    dz,
+                    &mut mesh_arrays,
+                    &sel,
+                    dx,
+                    dy,
+                    dz,
+                    true,
+                );
+            });
+        });
     }
 
     {
         let (dx, dy, dz) = (32, 32, 32);
         let data = create_sparse_voxel_data(dx, dy, dz);
-        let mapping: Vec<i32> = (0..2).collect();
+        let mapping = expand_block_atlas_mapping(&[0, 1]);
         let sel = vec![0u8; dx * dy * dz];
         let max_dim = dx.max(dy).max(dz);
         let mut finder = ExteriorFacesFinder::new(max_dim);
@@ -83,12 +108,34 @@ fn bench_find_exterior_faces(c: &mut Criterion) {
                 );
             });
         });
+
+        let mut bitmask_finder = ExteriorFacesFinder::new(max_dim);
+        bitmask_finder.set_backend(MeshingBackend::Bitmask);
+
+        group.bench_function("sparse_32x32x32_bitmask", |b| {
+            b.iter(|| {
+                bitmask_finder.find_exterior_faces(
+                    black_box(&data),
+                    4,
+                    &mapping,
+                    dx,
+                    dy,
+                    dz,
+                    &mut mesh_arrays,
+                    &sel,
+                    dx,
+                    dy,
+                    dz,
+                    true,
+                );
+            });
+        });
     }
 
     {
         let (dx, dy, dz) = (64, 64, 64);
         let data = create_filled_voxel_data(dx, dy, dz);
-        let mapping: Vec<i32> = (0..2).collect();
+        let mapping = expand_block_atlas_mapping(&[0, 1]);
         let sel = vec![0u8; dx * dy * dz];
         let max_dim = dx.max(dy).max(dz);
         let mut finder = ExteriorFacesFinder::new(max_dim);
@@ -114,12 +161,34 @@ fn bench_find_exterior_faces(c: &mut Criterion) {
                 );
             });
         });
+
+        let mut bitmask_finder = ExteriorFacesFinder::new(max_dim);
+        bitmask_finder.set_backend(MeshingBackend::Bitmask);
+
+        group.bench_function("solid_64x64x64_bitmask", |b| {
+            b.iter(|| {
+                bitmask_finder.find_exterior_faces(
+                    black_box(&data),
+                    4,
+                    &mapping,
+                    dx,
+                    dy,
+                    dz,
+                    &mut mesh_arrays,
+                    &sel,
+                    dx,
+                    dy,
+                    dz,
+                    true,
+                );
+            });
+        });
     }
 
     {
         let (dx, dy, dz) = (64, 64, 64);
         let data = create_sparse_voxel_data(dx, dy, dz);
-        let mapping: Vec<i32> = (0..2).collect();
+        let mapping = expand_block_atlas_mapping(&[0, 1]);
         let sel = vec![0u8; dx * dy * dz];
         let max_dim = dx.max(dy).max(dz);
         let mut finder = ExteriorFacesFinder::new(max_dim);
@@ -145,10 +214,91 @@ fn bench_find_exterior_faces(c: &mut Criterion) {
                 );
             });
         });
+
+        let mut bitmask_finder = ExteriorFacesFinder::new(max_dim);
+        bitmask_finder.set_backend(MeshingBackend::Bitmask);
+
+        group.bench_function("sparse_64x64x64_bitmask", |b| {
+            b.iter(|| {
+                bitmask_finder.find_exterior_faces(
+                    black_box(&data),
+                    4,
+                    &mapping,
+                    dx,
+                    dy,
+                    dz,
+                    &mut mesh_arrays,
+                    &sel,
+                    dx,
+                    dy,
+                    dz,
+                    true,
+                );
+            });
+        });
+    }
+
+    group.finish();
+}
+
+fn bench_incremental_remesh(c: &mut Criterion) {
+    let mut group = c.benchmark_group("incremental_remesh");
+
+    let (dx, dy, dz) = (64, 64, 64);
+    let mapping = expand_block_atlas_mapping(&[0, 1]);
+    let edit = [32, 32, 32];
+    let edit_idx = edit[0] * dy * dz + edit[1] * dz + edit[2];
+
+    for (name, mut data) in [
+        ("solid_64x64x64", create_filled_voxel_data(dx, dy, dz)),
+        ("sparse_64x64x64", create_sparse_voxel_data(dx, dy, dz)),
+    ] {
+        let mut mesher = IncrementalMesher::new(dx.max(dy).max(dz));
+        mesher.remesh_full(&data, 4, &mapping, dx, dy, dz, &[], 0, 0, 0, true);
+
+        group.bench_function(format!("full_remesh_{name}"), |b| {
+            b.iter(|| {
+                data[edit_idx] ^= 1;
+                mesher.remesh_full(
+                    black_box(&data),
+                    4,
+                    &mapping,
+                    dx,
+                    dy,
+                    dz,
+                    &[],
+                    0,
+                    0,
+                    0,
+                    true,
+                );
+            });
+        });
+
+        group.bench_function(format!("single_voxel_edit_{name}"), |b| {
+            b.iter(|| {
+                data[edit_idx] ^= 1;
+                mesher.remesh_region(
+                    black_box(&data),
+                    4,
+                    &mapping,
+                    dx,
+                    dy,
+                    dz,
+                    edit,
+                    edit,
+                    &[],
+                    0,
+                    0,
+                    0,
+                    true,
+                );
+            });
+        });
     }
 
     group.finish();
 }
 
-criterion_group!(benches, bench_find_exterior_faces);
+criterion_group!(benches, bench_find_exterior_faces, bench_incremental_remesh);
 criterion_main!(benches);
@@ -0,0 +1,62 @@
+use criterion::{Criterion, black_box, criterion_group, criterion_main};
+use lunavoxel_wasm::voxel_codec::{compress_voxel_data, decompress_into, decompress_voxel_data};
+
+fn create_project_voxel_data(dim_x: usize, dim_y: usize, dim_z: usize) -> Vec<u8> {
+    let mut data = vec![0u8; dim_x * dim_y * dim_z];
+    for x in 0..dim_x {
+        for z in 0..dim_z {
+            let height = (dim_y as f32 * 0.3
+                + (x as f32 * 0.21).sin() * 4.0
+                + (z as f32 * 0.17).cos() * 3.0) as usize;
+            for y in 0..height.min(dim_y) {
+                let block_type = match height - y {
+                    1 => 2,
+                    2..=4 => 3,
+                    _ => 4,
+                };
+                data[x * dim_y * dim_z + y * dim_z + z] = 0x80 | block_type;
+            }
+        }
+    }
+
+    let (min, max) = (dim_x / 4, dim_x / 2);
+    for x in min..max {
+        for y in dim_y / 3..dim_y / 3 + 12 {
+            for z in min..max {
+                let wall = x == min || x == max - 1 || z == min || z == max - 1;
+                let window = wall && y % 4 == 2 && (x + z) % 3 == 0;
+                if wall && !window {
+                    data[x * dim_y * dim_z + y * dim_z + z] = 0x80 | 5;
+                }
+            }
+        }
+    }
+    data
+}
+
+fn bench_voxel_codec(c: &mut Criterion) {
+    let mut group = c.benchmark_group("voxel_codec");
+
+    for (name, dim) in [("chunk_32", 32), ("project_128", 128)] {
+        let data = create_project_voxel_data(dim, dim, dim);
+        let compressed = compress_voxel_data(&data).unwrap();
+        let mut target = vec![0u8; data.len()];
+
+        group.bench_function(format!("compress_{name}"), |b| {
+            b.iter(|| compress_voxel_data(black_box(&data)).unwrap());
+        });
+
+        group.bench_function(format!("decompress_{name}"), |b| {
+            b.iter(|| decompress_voxel_data(black_box(&compressed)).unwrap());
+        });
+
+        group.bench_function(format!("decompress_into_{name}"), |b| {
+            b.iter(|| decompress_into(black_box(&compressed), &mut target).unwrap());
+        });
+    }
+
+    group.finish();
+}
+
+criterion_group!(benches, bench_voxel_codec);
+criterion_main!(benches);
@@ -1,5 +1,54 @@
+use crate::block_materials::BlockMaterials;
+use crate::validation::MeshingError;
+
 pub const OCCLUSION_LEVELS: [f32; 4] = [1.0, 0.9, 0.85, 0.75];
 
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct AoConfig {
+    pub levels: [f32; 4],
+    pub strength: f32,
+    pub enabled: bool,
+}
+
+impl Default for AoConfig {
+    fn default() -> Self {
+        Self {
+            levels: OCCLUSION_LEVELS,
+            strength: 1.0,
+            enabled: true,
+        }
+    }
+}
+
+impl AoConfig {
+    pub fn disabled() -> Self {
+        Self {
+            enabled: false,
+            ..Self::default()
+        }
+    }
+
+    pub fn validate(&self) -> Result<(), MeshingError> {
+        let in_range = |value: f32| (0.0..=1.0).contains(&value);
+        if !in_range(self.strength) {
+            return Err(MeshingError::InvalidAoStrength);
+        }
+        if let Some(index) = self.levels.iter().position(|&level| !in_range(level)) {
+            return Err(MeshingError::InvalidAoLevel { index });
+        }
+        Ok(())
+    }
+
+    #[inline(always)]
+    pub fn level(&self, occlusion_count: u8) -> f32 {
+        if !self.enabled {
+            return 1.0;
+        }
+        let base = self.levels[occlusion_count as usize];
+        (1.0 - (1.0 - base) * self.strength).clamp(0.0, 1.0)
+    }
+}
+
 pub struct AoOffsets {
     pub offsets: [i32; 8],
     pub u_axis: usize,
@@ -55,6 +104,7 @@ pub fn calculate_ambient_occlusion(
     voxel_data: &[u8],
     center_idx: i32,
     ao_offsets: &[i32; 8],
+    materials: &BlockMaterials,
 ) -> u8 {
     if nn < 0 || nn >= dim_n {
         return 0;
@@ -66,37 +116,37 @@ pub fn calculate_ambient_occlusion(
     let v_pos_ok = nv < dim_v - 1;
 
     #[inline(always)]
-    fn is_solid(voxel_data: &[u8], idx: i32) -> bool {
-        unsafe { (*voxel_data.get_unchecked(idx as usize) & 0x7F) != 0 }
+    fn is_solid(voxel_data: &[u8], materials: &BlockMaterials, idx: i32) -> bool {
+        materials.casts_ao(unsafe { *voxel_data.get_unchecked(idx as usize) })
     }
 
-    let side1_neg = u_neg_ok && is_solid(voxel_data, center_idx + ao_offsets[0]);
-    let side1_pos = u_pos_ok && is_solid(voxel_data, center_idx + ao_offsets[1]);
-    let side2_neg = v_neg_ok && is_solid(voxel_data, center_idx + ao_offsets[2]);
-    let side2_pos = v_pos_ok && is_solid(voxel_data, center_idx + ao_offsets[3]);
+    let side1_neg = u_neg_ok && is_solid(voxel_data, materials, center_idx + ao_offsets[0]);
+    let side1_pos = u_pos_ok && is_solid(voxel_data, materials, center_idx + ao_offsets[1]);
+    let side2_neg = v_neg_ok && is_solid(voxel_data, materials, center_idx + ao_offsets[2]);
+    let side2_pos = v_pos_ok && is_solid(voxel_data, materials, center_idx + ao_offsets[3]);
 
     let occ00 = if side1_neg && side2_neg {
         3
     } else {
-        let corner_nn = u_neg_ok && v_neg_ok && is_solid(voxel_data, center_idx + ao_offsets[4]);
+        let corner_nn = u_neg_ok && v_neg_ok && is_solid(voxel_data, materials, center_idx + ao_offsets[4]);
         (side1_neg as u8) + (side2_neg as u8) + (corner_nn as u8)
     };
     let occ10 = if side1_pos && side2_neg {
         3
     } else {
-        let corner_pn = u_pos_ok && v_neg_ok && is_solid(voxel_data, center_idx + ao_offsets[5]);
+        let corner_pn = u_pos_ok && v_neg_ok && is_solid(voxel_data, materials, center_idx + ao_offsets[5]);
         (side1_pos as u8) + (side2_neg as u8) + (corner_pn as u8)
     };
     let occ11 = if side1_pos && side2_pos {
         3
     } else {
-        let corner_pp = u_pos_ok && v_pos_ok && is_solid(voxel_data, center_idx + ao_offsets[7]);
+        let corner_pp = u_pos_ok && v_pos_ok && is_solid(voxel_data, materials, center_idx + ao_offsets[7]);
         (side1_pos as u8) + (side2_pos as u8) + (corner_pp as u8)
     };
     let occ01 = if side1_neg && side2_pos {
         3
     } else {
-        let corner_np = u_neg_ok && v_pos_ok && is_solid(voxel_data, center_idx + ao_offsets[6]);
+        let corner_np = u_neg_ok && v_pos_ok && is_solid(voxel_data, materials, center_idx + ao_offsets[6]);
         (side1_neg as u8) + (side2_pos as u8) + (corner_np as u8)
     };
 
@@ -0,0 +1,703 @@
+use crate::block_materials::{BlockMaterials, MaterialClass};
+use crate::exterior_fill::ExteriorFill;
+use crate::find_exterior_faces::is_selection_set;
+
+const SOLID: u8 = 1;
+const OPAQUE: u8 = 2;
+const CASTS_AO: u8 = 4;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum MeshingBackend {
+    #[default]
+    Scalar,
+    Bitmask,
+}
+
+#[derive(Clone, Copy)]
+pub(crate) struct FaceWord {
+    pub visible: u64,
+    pub ambiguous: u64,
+    pub selected: u64,
+}
+
+#[derive(Default)]
+pub(crate) struct BitmaskColumns {
+    rows_per_layer: [usize; 3],
+    words_per_row: [usize; 3],
+    solid: [Vec<u64>; 3],
+    opaque: [Vec<u64>; 3],
+    casts_ao: [Vec<u64>; 3],
+    selected: [Vec<u64>; 3],
+    sealed: [Vec<u64>; 3],
+    all_opaque: bool,
+    has_selection: bool,
+    has_sealed: bool,
+    faces: Vec<u64>,
+    face_keys: Vec<u64>,
+    face_words_per_row: usize,
+    face_rows: usize,
+}
+
+impl BitmaskColumns {
+    pub fn build(
+        &mut self,
+        voxel_data: &[u8],
+        data_dims: [usize; 3],
+        origin: [usize; 3],
+        dims: [usize; 3],
+        materials: &BlockMaterials,
+        exterior_fill: Option<&ExteriorFill>,
+        selection_data: &[u8],
+        selection_dim_x: usize,
+        selection_dim_y: usize,
+        selection_dim_z: usize,
+        selection_empty: bool,
+    ) {
+        self.has_selection = !selection_empty;
+        self.has_sealed = exterior_fill.is_some();
+        self.all_opaque = true;
+
+        let mut cell_flags = [0u8; 128];
+        for (block_type, flags) in cell_flags.iter_mut().enumerate().skip(1) {
+            let block_type = block_type as u8;
+            *flags = SOLID;
+            if materials.class(block_type) == MaterialClass::Opaque {
+                *flags |= OPAQUE;
+            } else {
+                self.all_opaque = false;
+            }
+            if materials.casts_ao(block_type) {
+                *flags |= CASTS_AO;
+            }
+        }
+
+        let padded = dims.map(|size| size + 2);
+        for axis in 0..3 {
+            let u = (axis + 1) % 3;
+            let v = (axis + 2) % 3;
+            self.rows_per_layer[axis] = padded[v];
+            self.words_per_row[axis] = dims[u].div_ceil(64) + 1;
+            let len = padded[axis] * padded[v] * self.words_per_row[axis];
+            reset_bits(&mut self.solid[axis], len);
+            if !self.all_opaque {
+                reset_bits(&mut self.opaque[axis], len);
+                reset_bits(&mut self.casts_ao[axis], len);
+            }
+            if self.has_selection {
+                reset_bits(&mut self.selected[axis], len);
+            }
+            if self.has_sealed {
+                reset_bits(&mut self.sealed[axis], len);
+            }
+        }
+
+        // Pack Y layers with Z-bit rows straight from the voxel rows, then
+        // transpose them into the X and Z sweeps.
+        let words = self.words_per_row[1];
+        let [x_range, y_range, z_range] =
+            [0, 1, 2].map(|axis| padded_range(origin[axis], dims[axis], data_dims[axis]));
+        for y in y_range.0..y_range.1 {
+            for x in x_range.0..x_range.1 {
+                let row_idx = (origin[0] + x - 1) * data_dims[1] * data_dims[2]
+                    + (origin[1] + y - 1) * data_dims[2]
+                    + origin[2]
+                    + z_range.0
+                    - 1;
+                let row = &voxel_data[row_idx..row_idx + z_range.1 - z_range.0];
+                let bits = (y * padded[0] + x) * words;
+                for (z, &value) in (z_range.0..).zip(row) {
+                    let flags = cell_flags[(value & 0x7F) as usize] as u64;
+                    let (word, shift) = (bits + z / 64, z % 64);
+                    self.solid[1][word] |= (flags & 1) << shift;
+                    if !self.all_opaque {
+                        self.opaque[1][word] |= (flags >> 1 & 1) << shift;
+                        self.casts_ao[1][word] |= (flags >> 2 & 1) << shift;
+                    }
+                }
+
+                if (!self.has_selection && !self.has_sealed) || x == 0 || y == 0 {
+                    continue;
+                }
+                let in_region = x <= dims[0] && y <= dims[1];
+                for z in z_range.0.max(1)..z_range.1 {
+                    let (word, bit) = (bits + z / 64, 1 << (z % 64));
+                    let (lx, ly, lz) = (x - 1, y - 1, z - 1);
+                    if self.has_selection
+                        && is_selection_set(
+                            selection_data,
+                            lx,
+                            ly,
+                            lz,
+                            selection_dim_x,
+                            selection_dim_y,
+                            selection_dim_z,
+                        )
+                    {
+                        self.selected[1][word] |= bit;
+                    }
+                    if let Some(exterior_fill) = exterior_fill
+                        && in_region
+                        && z <= dims[2]
+                        && self.solid[1][word] & bit == 0
+                        && !exterior_fill.is_outside(lx, ly, lz)
+                    {
+                        self.sealed[1][word] |= bit;
+                    }
+                }
+            }
+        }
+
+        transpose_sweeps(&mut self.solid, padded, self.words_per_row);
+        if !self.all_opaque {
+            transpose_sweeps(&mut self.opaque, padded, self.words_per_row);
+            transpose_sweeps(&mut self.casts_ao, padded, self.words_per_row);
+        }
+        if self.has_selection {
+            transpose_sweeps(&mut self.selected, padded, self.words_per_row);
+        }
+        if self.has_sealed {
+            transpose_sweeps(&mut self.sealed, padded, self.words_per_row);
+        }
+    }
+
+    #[inline(always)]
+    fn opaque(&self, axis: usize) -> &[u64] {
+        if self.all_opaque {
+            &self.solid[axis]
+        } else {
+            &self.opaque[axis]
+        }
+    }
+
+    #[inline(always)]
+    fn casts_ao(&self, axis: usize) -> &[u64] {
+        if self.all_opaque {
+            &self.solid[axis]
+        } else {
+            &self.casts_ao[axis]
+        }
+    }
+
+    #[inline(always)]
+    fn row_start(&self, axis: usize, layer: usize, row: usize) -> usize {
+        (layer * self.rows_per_layer[axis] + row) * self.words_per_row[axis]
+    }
+
+    pub fn begin_slice(&mut self, u_size: usize, v_size: usize) {
+        self.face_words_per_row = u_size.div_ceil(64);
+        self.face_rows = v_size;
+        let len = self.face_rows * self.face_words_per_row;
+        reset_bits(&mut self.faces, len);
+        if self.face_keys.len() < len * 64 {
+            self.face_keys.resize(len * 64, 0);
+        }
+    }
+
+    pub fn face_words_per_row(&self) -> usize {
+        self.face_words_per_row
+    }
+
+    #[inline(always)]
+    pub fn face_word(
+        &self,
+        axis: usize,
+        dir: i32,
+        depth: usize,
+        u_size: usize,
+        iv: usize,
+        word: usize,
+    ) -> FaceWord {
+        let layer = depth + 1;
+        let neighbor_layer = (layer as i32 + dir) as usize;
+        let row = self.row_start(axis, layer, iv + 1);
+        let neighbor_row = self.row_start(axis, neighbor_layer, iv + 1);
+        let cells = |bits: &[u64], start: usize| window(bits, start, word * 64 + 1);
+
+        let solid = cells(&self.solid[axis], row);
+        let neighbor_solid = cells(&self.solid[axis], neighbor_row);
+        let neighbor_opaque = cells(self.opaque(axis), neighbor_row);
+        let mut visible = solid & !neighbor_solid;
+        if self.has_sealed {
+            visible &= !cells(&self.sealed[axis], neighbor_row);
+        }
+        let ambiguous = solid & neighbor_solid & !neighbor_opaque;
+        let selected = if self.has_selection {
+            let selected = cells(&self.selected[axis], row);
+            visible |= selected & !solid & !cells(&self.selected[axis], neighbor_row);
+            selected
+        } else {
+            0
+        };
+
+        let valid = low_bits(u_size - word * 64);
+        FaceWord {
+            visible: visible & valid,
+            ambiguous: ambiguous & valid,
+            selected,
+        }
+    }
+
+    #[inline(always)]
+    pub fn occlusion(
+        &self,
+        axis: usize,
+        dir: i32,
+        depth: usize,
+        iv: usize,
+        word: usize,
+        faces: u64,
+    ) -> [u8; 64] {
+        let planes = self.occlusion_planes(axis, dir, depth, iv, word);
+        let mut occlusion = [0u8; 64];
+        for (column, cells) in occlusion.chunks_exact_mut(8).enumerate() {
+            if (faces >> (column * 8)) & 0xFF == 0 {
+                continue;
+            }
+            let rows = planes.iter().enumerate().fold(0u64, |rows, (plane, bits)| {
+                rows | ((bits >> (column * 8)) & 0xFF) << (plane * 8)
+            });
+            cells.copy_from_slice(&transpose_bytes(rows).to_le_bytes());
+        }
+        occlusion
+    }
+
+    #[inline(always)]
+    fn occlusion_planes(
+        &self,
+        axis: usize,
+        dir: i32,
+        depth: usize,
+        iv: usize,
+        word: usize,
+    ) -> [u64; 8] {
+        let neighbor_layer = (depth as i32 + 1 + dir) as usize;
+        let casts_ao = self.casts_ao(axis);
+        let center = self.row_start(axis, neighbor_layer, iv + 1);
+        let below = center - self.words_per_row[axis];
+        let above = center + self.words_per_row[axis];
+        let start = word * 64;
+
+        let left = window(casts_ao, center, start);
+        let right = window(casts_ao, center, start + 2);
+        let down = window(casts_ao, below, start + 1);
+        let up = window(casts_ao, above, start + 1);
+        let down_left = window(casts_ao, below, start);
+        let down_right = window(casts_ao, below, start + 2);
+        let up_left = window(casts_ao, above, start);
+        let up_right = window(casts_ao, above, start + 2);
+
+        // Face Y's AO tangents are (X, Z), which is the sweep's (v, u).
+        let (u_neg, u_pos, v_neg, v_pos, corner_pn, corner_np) = if axis == 1 {
+            (down, up, left, right, up_left, down_right)
+        } else {
+            (left, right, down, up, down_right, up_left)
+        };
+        let corners = [
+            (u_neg, v_neg, down_left),
+            (u_pos, v_neg, corner_pn),
+            (u_pos, v_pos, up_right),
+            (u_neg, v_pos, corner_np),
+        ];
+
+        let mut planes = [0u64; 8];
+        for (corner, (side1, side2, diagonal)) in corners.into_iter().enumerate() {
+            let both = side1 & side2;
+            planes[corner * 2] = (side1 ^ side2 ^ diagonal) | both;
+            planes[corner * 2 + 1] = both | (diagonal & (side1 | side2));
+        }
+        planes
+    }
+
+    #[inline(always)]
+    pub fn set_face_word(&mut self, iv: usize, word: usize, bits: u64) {
+        self.faces[iv * self.face_words_per_row + word] = bits;
+    }
+
+    #[inline(always)]
+    pub fn set_face_key(&mut self, iu: usize, iv: usize, key: u64) {
+        self.face_keys[iv * self.face_words_per_row * 64 + iu] = key;
+    }
+
+    #[inline(always)]
+    pub fn face_key(&self, iu: usize, iv: usize) -> u64 {
+        self.face_keys[iv * self.face_words_per_row * 64 + iu]
+    }
+
+    #[inline(always)]
+    pub fn take_quad(&mut self, i: usize, j: usize) -> (usize, usize) {
+        let words = self.face_words_per_row;
+        let row_len = words * 64;
+        let key = self.face_keys[j * row_len + i];
+        let same_key = |keys: &[u64], row: usize, width: usize| {
+            let start = row * row_len + i;
+            keys[start..start + width]
+                .iter()
+                .position(|&cell_key| cell_key != key)
+                .unwrap_or(width)
+        };
+
+        let (word, shift) = (i / 64, i % 64);
+        let run = (!(self.faces[j * words + word] >> shift)).trailing_zeros() as usize;
+        if shift + run < 64 || word + 1 == words {
+            let width = if run == 1 {
+                1
+            } else {
+                same_key(&self.face_keys, j, run)
+            };
+            let span = low_bits(width) << shift;
+            let mut height = 1;
+            while j + height < self.face_rows
+                && self.faces[(j + height) * words + word] & span == span
+                && same_key(&self.face_keys, j + height, width) == width
+            {
+                height += 1;
+            }
+            for row in j..j + height {
+                self.faces[row * words + word] &= !span;
+            }
+            return (width, height);
+        }
+
+        let run = face_run(&self.faces[j * words..(j + 1) * words], i);
+        let width = same_key(&self.face_keys, j, run);
+
+        let mut height = 1;
+        while j + height < self.face_rows
+            && row_spans(words, i, width)
+                .all(|(word, span)| self.faces[(j + height) * words + word] & span == span)
+            && same_key(&self.face_keys, j + height, width) == width
+        {
+            height += 1;
+        }
+
+        for row in j..j + height {
+            for (word, span) in row_spans(words, i, width) {
+                self.faces[row * words + word] &= !span;
+            }
+        }
+        (width, height)
+    }
+
+    #[inline(always)]
+    pub fn face_bits(&self, iv: usize, word: usize) -> u64 {
+        self.faces[iv * self.face_words_per_row + word]
+    }
+}
+
+fn face_run(row: &[u64], start: usize) -> usize {
+    let mut run = 0;
+    loop {
+        let bit = start + run;
+        let word = bit / 64;
+        if word == row.len() {
+            return run;
+        }
+        let ones = (!(row[word] >> (bit % 64))).trailing_zeros() as usize;
+        run += ones;
+        if bit % 64 + ones < 64 {
+            return run;
+        }
+    }
+}
+
+fn transpose_sweeps(bits: &mut [Vec<u64>; 3], padded: [usize; 3], words: [usize; 3]) {
+    let [x_sweep, y_sweep, z_sweep] = bits;
+    for x in 0..padded[0] {
+        transpose_plane(
+            y_sweep,
+            |y| (y * padded[0] + x) * words[1],
+            padded[1],
+            words[1],
+            x_sweep,
+            |z| (x * padded[2] + z) * words[0],
+            padded[2],
+        );
+    }
+    for y in 0..padded[1] {
+        transpose_plane(
+            y_sweep,
+            |x| (y * padded[0] + x) * words[1],
+            padded[0],
+            words[1],
+            z_sweep,
+            |z| (z * padded[1] + y) * words[2],
+            padded[2],
+        );
+    }
+}
+
+fn transpose_plane(
+    src: &[u64],
+    src_row: impl Fn(usize) -> usize,
+    src_rows: usize,
+    src_words: usize,
+    dst: &mut [u64],
+    dst_row: impl Fn(usize) -> usize,
+    dst_rows: usize,
+) {
+    let mut block = [0u64; 64];
+    for row_block in 0..src_rows.div_ceil(64) {
+        for word in 0..src_words {
+            let mut any = 0;
+            for (offset, bits) in block.iter_mut().enumerate() {
+                let row = row_block * 64 + offset;
+                *bits = if row < src_rows {
+                    src[src_row(row) + word]
+                } else {
+                    0
+                };
+                any |= *bits;
+            }
+            if any == 0 {
+                continue;
+            }
+            transpose_bits(&mut block);
+            for (row, &bits) in (word * 64..dst_rows).zip(&block) {
+                dst[dst_row(row) + row_block] = bits;
+            }
+        }
+    }
+}
+
+#[inline(always)]
+fn transpose_bytes(mut rows: u64) -> u64 {
+    let swap = (rows ^ (rows >> 7)) & 0x00AA_00AA_00AA_00AA;
+    rows ^= swap ^ (swap << 7);
+    let swap = (rows ^ (rows >> 14)) & 0x0000_CCCC_0000_CCCC;
+    rows ^= swap ^ (swap << 14);
+    let swap = (rows ^ (rows >> 28)) & 0x0000_0000_F0F0_F0F0;
+    rows ^ swap ^ (swap << 28)
+}
+
+fn transpose_bits(block: &mut [u64; 64]) {
+    let mut width = 32;
+    let mut mask = 0x0000_0000_FFFF_FFFFu64;
+    while width != 0 {
+        for base in (0..64).step_by(width * 2) {
+            for row in base..base + width {
+                let swap = ((block[row] >> width) ^ block[row + width]) & mask;
+                block[row] ^= swap << width;
+                block[row + width] ^= swap;
+            }
+        }
+        width >>= 1;
+        mask ^= mask << width;
+    }
+}
+
+fn padded_range(origin: usize, size: usize, data_size: usize) -> (usize, usize) {
+    let start = if origin == 0 { 1 } else { 0 };
+    let end = (size + 2).min(data_size + 1 - origin);
+    (start, end)
+}
+
+#[inline(always)]
+fn window(bits: &[u64], row_start: usize, start: usize) -> u64 {
+    let word = row_start + start / 64;
+    let shift = start % 64;
+    bits[word] >> shift | bits[word + 1] << 1 << (63 - shift)
+}
+
+#[inline(always)]
+fn low_bits(count: usize) -> u64 {
+    if count >= 64 {
+        u64::MAX
+    } else {
+        (1 << count) - 1
+    }
+}
+
+fn row_spans(words: usize, start: usize, width: usize) -> impl Iterator<Item = (usize, u64)> {
+    let end = start + width;
+    (start / 64..end.div_ceil(64).min(words)).map(move |word| {
+        let low = start.max(word * 64) - word * 64;
+        let high = end.min(word * 64 + 64) - word * 64;
+        let span = if high - low == 64 {
+            u64::MAX
+        } else {
+            ((1u64 << (high - low)) - 1) << low
+        };
+        (word, span)
+    })
+}
+
+fn reset_bits(bits: &mut Vec<u64>, len: usize) {
+    bits.clear();
+    bits.resize(len, 0);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::color_palette::ColorPalette;
+    use crate::find_exterior_faces::ExteriorFacesFinder;
+    use crate::mesh_arrays::MeshArrays;
+    use crate::test_support::assert_same_mesh;
+    use crate::texture_coords::expand_block_atlas_mapping;
+
+    fn pseudo_random_volume(len: usize, seed: u32, fill_percent: u32, block_types: u32) -> Vec<u8> {
+        let mut state = seed;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                let roll = (state >> 16) % 100;
+                if roll < fill_percent {
+                    (1 + (state >> 8) % block_types) as u8 | ((state & 0x80) as u8)
+                } else {
+                    0
+                }
+            })
+            .collect()
+    }
+
+    fn mesh(
+        backend: MeshingBackend,
+        voxel_data: &[u8],
+        dims: [usize; 3],
+        halo: bool,
+        block_classes: &[u8],
+        selection_data: &[u8],
+        palette: Option<ColorPalette>,
+    ) -> MeshArrays {
+        let mut finder = ExteriorFacesFinder::new(4);
+        finder.set_backend(backend);
+        finder.set_block_materials(block_classes).unwrap();
+        finder.set_color_palette(palette);
+        let mapping = expand_block_atlas_mapping(&[0, 1, 2, 3]);
+        let mut mesh_arrays = MeshArrays::new(0, 0);
+        let selection_empty = selection_data.is_empty();
+        if halo {
+            finder.find_exterior_faces_with_halo(
+                voxel_data,
+                4,
+                &mapping,
+                dims[0],
+                dims[1],
+                dims[2],
+                &mut mesh_arrays,
+                selection_data,
+                dims[0],
+                dims[1],
+                dims[2],
+                selection_empty,
+            );
+        } else {
+            finder.find_exterior_faces(
+                voxel_data,
+                4,
+                &mapping,
+                dims[0],
+                dims[1],
+                dims[2],
+                &mut mesh_arrays,
+                selection_data,
+                dims[0],
+                dims[1],
+                dims[2],
+                selection_empty,
+            );
+        }
+        mesh_arrays
+    }
+
+    fn assert_backends_match(
+        voxel_data: &[u8],
+        dims: [usize; 3],
+        halo: bool,
+        block_classes: &[u8],
+        selection_data: &[u8],
+    ) {
+        assert_backends_match_with_palette(
+            voxel_data,
+            dims,
+            halo,
+            block_classes,
+            selection_data,
+            None,
+        );
+    }
+
+    fn assert_backends_match_with_palette(
+        voxel_data: &[u8],
+        dims: [usize; 3],
+        halo: bool,
+        block_classes: &[u8],
+        selection_data: &[u8],
+        palette: Option<ColorPalette>,
+    ) {
+        let [scalar, bitmask] = [MeshingBackend::Scalar, MeshingBackend::Bitmask].map(|backend| {
+            mesh(
+                backend,
+                voxel_data,
+                dims,
+                halo,
+                block_classes,
+                selection_data,
+                palette.clone(),
+            )
+        });
+        assert!(scalar.vertex_count > 0);
+        assert_same_mesh(&bitmask, &scalar);
+    }
+
+    #[test]
+    fn matches_scalar_on_random_volumes() {
+        for (seed, fill_percent) in [(1, 10), (2, 50), (3, 90), (4, 100)] {
+            let dims = [9, 7, 5];
+            let data = pseudo_random_volume(dims[0] * dims[1] * dims[2], seed, fill_percent, 3);
+            assert_backends_match(&data, dims, false, &[], &[]);
+        }
+    }
+
+    #[test]
+    fn matches_scalar_across_word_boundaries() {
+        let dims = [3, 70, 130];
+        let data = pseudo_random_volume(dims[0] * dims[1] * dims[2], 7, 60, 2);
+        assert_backends_match(&data, dims, false, &[], &[]);
+    }
+
+    #[test]
+    fn matches_scalar_with_materials_and_selection() {
+        let dims = [8, 6, 10];
+        let len = dims[0] * dims[1] * dims[2];
+        let data = pseudo_random_volume(len, 11, 55, 4);
+        let selection = pseudo_random_volume(len, 12, 20, 4);
+        assert_backends_match(&data, dims, false, &[0, 1, 2, 2], &selection);
+    }
+
+    #[test]
+    fn matches_scalar_with_halo() {
+        let dims = [6, 5, 7];
+        let padded_len = (dims[0] + 2) * (dims[1] + 2) * (dims[2] + 2);
+        let data = pseudo_random_volume(padded_len, 21, 70, 3);
+        let selection = pseudo_random_volume(dims[0] * dims[1] * dims[2], 22, 15, 1);
+        assert_backends_match(&data, dims, true, &[0, 2, 1], &[]);
+        assert_backends_match(&data, dims, true, &[0, 2, 1], &selection);
+    }
+
+    #[test]
+    fn matches_scalar_when_every_axis_spans_words() {
+        let dims = [65, 66, 67];
+        let padded_len = (dims[0] + 2) * (dims[1] + 2) * (dims[2] + 2);
+        let data = pseudo_random_volume(padded_len, 31, 40, 3);
+        assert_backends_match(&data, dims, true, &[0, 1, 2], &[]);
+    }
+
+    #[test]
+    fn merges_runs_longer_than_a_word() {
+        let dims = [3, 70, 130];
+        let mut data = vec![0u8; dims[0] * dims[1] * dims[2]];
+        for x in 0..dims[0] {
+            for y in 0..dims[1] {
+                for z in 0..dims[2] {
+                    if (x + y * 3 + z * 7) % 97 != 0 {
+                        data[(x * dims[1] + y) * dims[2] + z] = 1 + ((y / 40 + z / 70) % 2) as u8;
+                    }
+                }
+            }
+        }
+        assert_backends_match(&data, dims, false, &[], &[]);
+        let palette = ColorPalette::from_hex_rgb(&[0xff0000, 0x00ff00]);
+        assert_backends_match_with_palette(&data, dims, false, &[], &[], Some(palette));
+    }
+}
@@ -0,0 +1,90 @@
+use crate::validation::MeshingError;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+#[repr(u8)]
+pub enum MaterialClass {
+    Opaque = 0,
+    Cutout = 1,
+    Translucent = 2,
+}
+
+impl TryFrom<u8> for MaterialClass {
+    type Error = MeshingError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(MaterialClass::Opaque),
+            1 => Ok(MaterialClass::Cutout),
+            2 => Ok(MaterialClass::Translucent),
+            _ => Err(MeshingError::InvalidMaterialClass { class: value }),
+        }
+    }
+}
+
+pub const BLOCK_TYPE_COUNT: usize = 128;
+
+#[derive(Clone)]
+pub struct BlockMaterials {
+    classes: [MaterialClass; BLOCK_TYPE_COUNT],
+    casts_ao: [bool; BLOCK_TYPE_COUNT],
+    has_translucent: bool,
+}
+
+impl Default for BlockMaterials {
+    fn default() -> Self {
+        let mut casts_ao = [true; BLOCK_TYPE_COUNT];
+        casts_ao[0] = false;
+        Self {
+            classes: [MaterialClass::Opaque; BLOCK_TYPE_COUNT],
+            casts_ao,
+            has_translucent: false,
+        }
+    }
+}
+
+impl BlockMaterials {
+    pub fn from_classes(block_classes: &[u8]) -> Result<Self, MeshingError> {
+        let mut materials = Self::default();
+
+        for (i, &class) in block_classes.iter().enumerate().take(BLOCK_TYPE_COUNT - 1) {
+            let block_type = i + 1;
+            let class = MaterialClass::try_from(class)?;
+            materials.classes[block_type] = class;
+            materials.casts_ao[block_type] = class != MaterialClass::Translucent;
+            materials.has_translucent |= class == MaterialClass::Translucent;
+        }
+
+        Ok(materials)
+    }
+
+    #[inline(always)]
+    pub fn class(&self, block_type: u8) -> MaterialClass {
+        self.classes[(block_type & 0x7F) as usize]
+    }
+
+    #[inline(always)]
+    pub fn is_translucent(&self, block_type: u8) -> bool {
+        self.class(block_type) == MaterialClass::Translucent
+    }
+
+    #[inline(always)]
+    pub fn is_opaque(&self, block_value: u8) -> bool {
+        block_value & 0x7F != 0 && self.class(block_value) == MaterialClass::Opaque
+    }
+
+    #[inline(always)]
+    pub fn casts_ao(&self, block_value: u8) -> bool {
+        self.casts_ao[(block_value & 0x7F) as usize]
+    }
+
+    #[inline(always)]
+    pub fn has_translucent(&self) -> bool {
+        self.has_translucent
+    }
+
+    #[inline(always)]
+    pub fn is_face_visible(&self, block_type: u8, neighbor_type: u8) -> bool {
+        neighbor_type == 0
+            || (self.class(neighbor_type) != MaterialClass::Opaque && neighbor_type != block_type)
+    }
+}
@@ -0,0 +1,71 @@
+use crate::block_materials::BLOCK_TYPE_COUNT;
+
+const WHITE: [f32; 4] = [1.0, 1.0, 1.0, 1.0];
+
+#[derive(Clone, Debug, PartialEq)]
+pub struct ColorPalette {
+    colors: [[f32; 4]; BLOCK_TYPE_COUNT],
+    premultiply_ao: bool,
+}
+
+impl ColorPalette {
+    pub fn from_rgba(rgba: &[f32]) -> Self {
+        let mut colors = [WHITE; BLOCK_TYPE_COUNT];
+        for (i, color) in rgba.chunks_exact(4).enumerate().take(BLOCK_TYPE_COUNT - 1) {
+            colors[i + 1] = [color[0], color[1], color[2], color[3]];
+        }
+        Self {
+            colors,
+            premultiply_ao: false,
+        }
+    }
+
+    pub fn from_hex_rgb(hex_colors: &[u32]) -> Self {
+        let rgba: Vec<f32> = hex_colors
+            .iter()
+            .flat_map(|&hex| {
+                let channel = |shift: u32| ((hex >> shift) & 0xFF) as f32 / 255.0;
+                [channel(16), channel(8), channel(0), 1.0]
+            })
+            .collect();
+        Self::from_rgba(&rgba)
+    }
+
+    pub fn set_premultiply_ao(&mut self, premultiply_ao: bool) {
+        self.premultiply_ao = premultiply_ao;
+    }
+
+    pub fn premultiply_ao(&self) -> bool {
+        self.premultiply_ao
+    }
+
+    #[inline(always)]
+    pub fn color(&self, block_type: u8, ao_level: f32) -> [f32; 4] {
+        let [r, g, b, a] = self.colors[(block_type & 0x7F) as usize];
+        if self.premultiply_ao {
+            [r * ao_level, g * ao_level, b * ao_level, a]
+        } else {
+            [r, g, b, a]
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn hex_colors_map_to_block_types() {
+        let palette = ColorPalette::from_hex_rgb(&[0xFF0000, 0x3366CC]);
+        assert_eq!(palette.color(1, 0.5), [1.0, 0.0, 0.0, 1.0]);
+        assert_eq!(palette.color(2, 1.0), [0.2, 0.4, 0.8, 1.0]);
+        assert_eq!(palette.color(3, 1.0), WHITE);
+    }
+
+    #[test]
+    fn premultiplied_ao_scales_rgb_only() {
+        let mut palette = ColorPalette::from_rgba(&[0.5, 1.0, 0.25, 0.75]);
+        palette.set_premultiply_ao(true);
+        assert_eq!(palette.color(1, 0.5), [0.25, 0.5, 0.125, 0.75]);
+    }
+}
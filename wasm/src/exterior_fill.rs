@@ -0,0 +1,269 @@
+use crate::block_materials::BlockMaterials;
+
+const UNVISITED: u8 = 0;
+const OUTSIDE: u8 = 1;
+const CAVITY: u8 = 2;
+const SOLID: u8 = 3;
+
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct CavityReport {
+    pub cavity_count: usize,
+    pub cavity_volume: usize,
+}
+
+#[derive(Default)]
+pub(crate) struct ExteriorFill {
+    states: Vec<u8>,
+    stack: Vec<usize>,
+    dims: [usize; 3],
+}
+
+impl ExteriorFill {
+    pub fn compute(
+        &mut self,
+        voxel_data: &[u8],
+        data_dims: [usize; 3],
+        origin: [usize; 3],
+        dims: [usize; 3],
+        materials: &BlockMaterials,
+    ) -> CavityReport {
+        self.dims = dims;
+        self.states.clear();
+        self.states.reserve(dims[0] * dims[1] * dims[2]);
+        for x in 0..dims[0] {
+            for y in 0..dims[1] {
+                let row = (origin[0] + x) * data_dims[1] * data_dims[2]
+                    + (origin[1] + y) * data_dims[2]
+                    + origin[2];
+                self.states
+                    .extend(voxel_data[row..row + dims[2]].iter().map(|&value| {
+                        if materials.is_opaque(value) {
+                            SOLID
+                        } else {
+                            UNVISITED
+                        }
+                    }));
+            }
+        }
+
+        for x in 0..dims[0] {
+            for y in 0..dims[1] {
+                for z in 0..dims[2] {
+                    let on_boundary = x == 0
+                        || y == 0
+                        || z == 0
+                        || x == dims[0] - 1
+                        || y == dims[1] - 1
+                        || z == dims[2] - 1;
+                    if on_boundary {
+                        self.flood(self.index(x, y, z), OUTSIDE);
+                    }
+                }
+            }
+        }
+
+        let mut report = CavityReport::default();
+        for idx in 0..self.states.len() {
+            if self.states[idx] == UNVISITED {
+                report.cavity_count += 1;
+                report.cavity_volume += self.flood(idx, CAVITY);
+            }
+        }
+        report
+    }
+
+    #[inline(always)]
+    pub fn is_outside(&self, x: usize, y: usize, z: usize) -> bool {
+        self.states[self.index(x, y, z)] == OUTSIDE
+    }
+
+    #[inline(always)]
+    fn index(&self, x: usize, y: usize, z: usize) -> usize {
+        x * self.dims[1] * self.dims[2] + y * self.dims[2] + z
+    }
+
+    fn flood(&mut self, start: usize, state: u8) -> usize {
+        if self.states[start] != UNVISITED {
+            return 0;
+        }
+        let [dim_x, dim_y, dim_z] = self.dims;
+        let stride_x = dim_y * dim_z;
+        self.states[start] = state;
+        self.stack.push(start);
+        let mut filled = 0;
+
+        while let Some(idx) = self.stack.pop() {
+            filled += 1;
+            let x = idx / stride_x;
+            let y = idx / dim_z % dim_y;
+            let z = idx % dim_z;
+            let neighbors = [
+                (x > 0).then(|| idx - stride_x),
+                (x + 1 < dim_x).then(|| idx + stride_x),
+                (y > 0).then(|| idx - dim_z),
+                (y + 1 < dim_y).then(|| idx + dim_z),
+                (z > 0).then(|| idx - 1),
+                (z + 1 < dim_z).then(|| idx + 1),
+            ];
+            for neighbor in neighbors.into_iter().flatten() {
+                if self.states[neighbor] == UNVISITED {
+                    self.states[neighbor] = state;
+                    self.stack.push(neighbor);
+                }
+            }
+        }
+        filled
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitmask_backend::MeshingBackend;
+    use crate::find_exterior_faces::ExteriorFacesFinder;
+    use crate::mesh_arrays::MeshArrays;
+    use crate::texture_coords::expand_block_atlas_mapping;
+
+    fn hollow_box(dim: usize, cavities: &[([usize; 3], [usize; 3])]) -> Vec<u8> {
+        let mut data = vec![1u8; dim * dim * dim];
+        for &(min, max) in cavities {
+            for x in min[0]..=max[0] {
+                for y in min[1]..=max[1] {
+                    for z in min[2]..=max[2] {
+                        data[x * dim * dim + y * dim + z] = 0;
+                    }
+                }
+            }
+        }
+        data
+    }
+
+    fn mesh(
+        data: &[u8],
+        dim: usize,
+        exterior_only: bool,
+        backend: MeshingBackend,
+    ) -> (MeshArrays, CavityReport) {
+        mesh_with_classes(data, dim, exterior_only, backend, &[])
+    }
+
+    fn mesh_with_classes(
+        data: &[u8],
+        dim: usize,
+        exterior_only: bool,
+        backend: MeshingBackend,
+        block_classes: &[u8],
+    ) -> (MeshArrays, CavityReport) {
+        let mut finder = ExteriorFacesFinder::new(dim);
+        finder.set_backend(backend);
+        finder.set_exterior_only(exterior_only);
+        finder.set_block_materials(block_classes).unwrap();
+        let mapping = expand_block_atlas_mapping(&[0, 0]);
+        let mut mesh_arrays = MeshArrays::new(0, 0);
+        finder.find_exterior_faces(
+            data,
+            4,
+            &mapping,
+            dim,
+            dim,
+            dim,
+            &mut mesh_arrays,
+            &[],
+            0,
+            0,
+            0,
+            true,
+        );
+        (mesh_arrays, finder.cavity_report())
+    }
+
+    #[test]
+    fn sealed_cavity_walls_are_skipped() {
+        let data = hollow_box(5, &[([1, 1, 1], [3, 3, 3])]);
+        let (all_faces, _) = mesh(&data, 5, false, MeshingBackend::Scalar);
+        let (exterior, report) = mesh(&data, 5, true, MeshingBackend::Scalar);
+
+        let (solid, _) = mesh(&hollow_box(5, &[]), 5, false, MeshingBackend::Scalar);
+
+        assert!(all_faces.index_count > solid.index_count);
+        assert_eq!(exterior.index_count, solid.index_count);
+        assert_eq!(
+            exterior.vertices[..exterior.vertex_count * 3],
+            solid.vertices[..solid.vertex_count * 3]
+        );
+        assert_eq!(
+            report,
+            CavityReport {
+                cavity_count: 1,
+                cavity_volume: 27
+            }
+        );
+    }
+
+    #[test]
+    fn counts_separate_cavities() {
+        let data = hollow_box(7, &[([1, 1, 1], [1, 1, 1]), ([3, 3, 3], [5, 4, 5])]);
+        let (_, report) = mesh(&data, 7, true, MeshingBackend::Scalar);
+        assert_eq!(
+            report,
+            CavityReport {
+                cavity_count: 2,
+                cavity_volume: 1 + 3 * 2 * 3
+            }
+        );
+    }
+
+    #[test]
+    fn open_cavities_stay_visible() {
+        let mut data = hollow_box(5, &[([1, 1, 1], [3, 3, 3])]);
+        data[2 * 25 + 2 * 5 + 4] = 0;
+        let (all_faces, _) = mesh(&data, 5, false, MeshingBackend::Scalar);
+        let (exterior, report) = mesh(&data, 5, true, MeshingBackend::Scalar);
+        assert_eq!(exterior.index_count, all_faces.index_count);
+        assert_eq!(report, CavityReport::default());
+    }
+
+    #[test]
+    fn glass_walls_do_not_seal_cavities() {
+        let mut data = hollow_box(5, &[([1, 1, 1], [3, 3, 3])]);
+        for y in 1..4 {
+            for z in 1..4 {
+                data[4 * 25 + y * 5 + z] = 2;
+            }
+        }
+        let classes = [0, 2];
+        for backend in [MeshingBackend::Scalar, MeshingBackend::Bitmask] {
+            let (all_faces, _) = mesh_with_classes(&data, 5, false, backend, &classes);
+            let (exterior, report) = mesh_with_classes(&data, 5, true, backend, &classes);
+            assert_eq!(report, CavityReport::default());
+            assert_eq!(exterior.index_count, all_faces.index_count);
+        }
+
+        let mut sealed = hollow_box(5, &[([1, 1, 1], [3, 3, 3])]);
+        sealed[2 * 25 + 2 * 5 + 2] = 2;
+        let (exterior, report) =
+            mesh_with_classes(&sealed, 5, true, MeshingBackend::Scalar, &classes);
+        let (solid, _) = mesh(&hollow_box(5, &[]), 5, false, MeshingBackend::Scalar);
+        assert_eq!(
+            report,
+            CavityReport {
+                cavity_count: 1,
+                cavity_volume: 27
+            }
+        );
+        assert_eq!(exterior.index_count, solid.index_count);
+    }
+
+    #[test]
+    fn bitmask_backend_matches_exterior_only_output() {
+        let data = hollow_box(7, &[([1, 1, 1], [2, 5, 2]), ([4, 4, 4], [5, 5, 5])]);
+        let (scalar, _) = mesh(&data, 7, true, MeshingBackend::Scalar);
+        let (bitmask, report) = mesh(&data, 7, true, MeshingBackend::Bitmask);
+        assert_eq!(report.cavity_count, 2);
+        assert_eq!(bitmask.index_count, scalar.index_count);
+        assert_eq!(
+            bitmask.vertices[..bitmask.vertex_count * 3],
+            scalar.vertices[..scalar.vertex_count * 3]
+        );
+    }
+}
@@ -0,0 +1,515 @@
+use std::fmt;
+
+use crate::raycast::RAYCASTABLE_BIT;
+use crate::texture_coords::FACES_PER_BLOCK;
+use crate::validation::{validate_voxel_data, MeshingError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FillShapeError {
+    InvalidVolume(MeshingError),
+    InvalidShape { value: u8 },
+    InvalidMode { value: u8 },
+    InvalidUpDirection { face_dir: usize },
+}
+
+impl fmt::Display for FillShapeError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FillShapeError::InvalidVolume(error) => error.fmt(f),
+            FillShapeError::InvalidShape { value } => {
+                write!(f, "{value} is not a valid fill shape")
+            }
+            FillShapeError::InvalidMode { value } => write!(f, "{value} is not a valid fill mode"),
+            FillShapeError::InvalidUpDirection { face_dir } => {
+                write!(f, "up direction must be below {FACES_PER_BLOCK}, got {face_dir}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FillShapeError {}
+
+impl From<MeshingError> for FillShapeError {
+    fn from(error: MeshingError) -> Self {
+        FillShapeError::InvalidVolume(error)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum FillShape {
+    #[default]
+    Box,
+    Ellipsoid,
+    Cylinder,
+    Cone,
+    Pyramid,
+}
+
+impl TryFrom<u8> for FillShape {
+    type Error = FillShapeError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(FillShape::Box),
+            1 => Ok(FillShape::Ellipsoid),
+            2 => Ok(FillShape::Cylinder),
+            3 => Ok(FillShape::Cone),
+            4 => Ok(FillShape::Pyramid),
+            _ => Err(FillShapeError::InvalidShape { value }),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum FillMode {
+    #[default]
+    Attach,
+    Erase,
+    Paint,
+}
+
+impl TryFrom<u8> for FillMode {
+    type Error = FillShapeError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(FillMode::Attach),
+            1 => Ok(FillMode::Erase),
+            2 => Ok(FillMode::Paint),
+            _ => Err(FillShapeError::InvalidMode { value }),
+        }
+    }
+}
+
+impl FillMode {
+    #[inline(always)]
+    pub fn apply(self, current: u8, block_type: u8) -> u8 {
+        match self {
+            FillMode::Attach => block_type,
+            FillMode::Erase => 0,
+            FillMode::Paint if current & 0x7F != 0 => block_type,
+            FillMode::Paint => current,
+        }
+    }
+
+    #[inline(always)]
+    pub fn preview_value(self, block_type: u8) -> u8 {
+        match self {
+            FillMode::Attach => block_type,
+            FillMode::Erase => RAYCASTABLE_BIT,
+            FillMode::Paint => block_type | RAYCASTABLE_BIT,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct ShapeParams {
+    center: [f64; 3],
+    radius: [f64; 3],
+    inv_radius: [f64; 3],
+    height: f64,
+    inv_height: f64,
+    max_y: f64,
+}
+
+impl ShapeParams {
+    fn new(min: [i64; 3], max: [i64; 3]) -> Option<Self> {
+        if (0..3).any(|a| max[a] < min[a]) {
+            return None;
+        }
+        let radius: [f64; 3] = std::array::from_fn(|a| (max[a] - min[a] + 1) as f64 / 2.0);
+        let height = (max[1] - min[1] + 1) as f64;
+        Some(Self {
+            center: std::array::from_fn(|a| (min[a] + max[a] + 1) as f64 / 2.0),
+            radius,
+            inv_radius: radius.map(|r| 1.0 / r),
+            height,
+            inv_height: 1.0 / height,
+            max_y: max[1] as f64,
+        })
+    }
+
+    fn contains(&self, shape: FillShape, p: [i64; 3]) -> bool {
+        let offset: [f64; 3] = std::array::from_fn(|a| p[a] as f64 + 0.5 - self.center[a]);
+        let scaled: [f64; 3] = std::array::from_fn(|a| offset[a] * self.inv_radius[a]);
+        let in_slab = (0..3).all(|a| offset[a].abs() <= self.radius[a]);
+        if !in_slab {
+            return false;
+        }
+        match shape {
+            FillShape::Box => true,
+            FillShape::Ellipsoid => scaled.iter().map(|s| s * s).sum::<f64>() <= 1.0,
+            FillShape::Cylinder => scaled[0] * scaled[0] + scaled[2] * scaled[2] <= 1.0,
+            FillShape::Cone => {
+                let row = self.max_y - p[1] as f64;
+                let fraction = (row + 0.5) * self.inv_height;
+                let (dx, dz) = (scaled[0] / fraction, scaled[2] / fraction);
+                dx * dx + dz * dz <= 1.0
+            }
+            FillShape::Pyramid => {
+                let row = self.max_y - p[1] as f64;
+                let top_fraction = if self.height <= 1.0 {
+                    1.0
+                } else {
+                    1.0 - row / (self.height - 1.0)
+                };
+                let min_diameter = (self.radius[0] * 2.0).min(self.radius[2] * 2.0);
+                let max_inset = ((min_diameter - 1.0) / 2.0).floor();
+                let inset = if max_inset > 0.0 {
+                    (top_fraction * max_inset).floor()
+                } else {
+                    0.0
+                };
+                offset[0].abs() <= (self.radius[0] - inset).max(0.0)
+                    && offset[2].abs() <= (self.radius[2] - inset).max(0.0)
+            }
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ShapeRaster {
+    shape: FillShape,
+    up_face_dir: usize,
+    outer: Option<ShapeParams>,
+    inner: Option<ShapeParams>,
+    shape_min: [i64; 3],
+    shape_max: [i64; 3],
+}
+
+impl ShapeRaster {
+    pub fn new(
+        shape: FillShape,
+        min: [i64; 3],
+        max: [i64; 3],
+        up_face_dir: usize,
+        wall_thickness: usize,
+    ) -> Self {
+        let up_axis = up_face_dir / 2;
+        let mut shape_min = min;
+        let mut shape_max = max;
+        shape_min.swap(1, up_axis);
+        shape_max.swap(1, up_axis);
+        let outer = ShapeParams::new(shape_min, shape_max);
+        let inner = match wall_thickness {
+            0 => None,
+            t => {
+                let t = t as i64;
+                ShapeParams::new(shape_min.map(|c| c + t), shape_max.map(|c| c - t))
+            }
+        };
+        Self {
+            shape,
+            up_face_dir,
+            outer,
+            inner,
+            shape_min,
+            shape_max,
+        }
+    }
+
+    fn shape_space_position(&self, p: [i64; 3]) -> [i64; 3] {
+        let up_axis = self.up_face_dir / 2;
+        let mut s = p;
+        s.swap(1, up_axis);
+        if self.up_face_dir % 2 == 1 {
+            s[1] = self.shape_min[1] + self.shape_max[1] - s[1];
+        }
+        s
+    }
+
+    pub fn contains(&self, p: [i64; 3]) -> bool {
+        let Some(outer) = &self.outer else {
+            return false;
+        };
+        let s = self.shape_space_position(p);
+        outer.contains(self.shape, s)
+            && !self
+                .inner
+                .is_some_and(|inner| inner.contains(self.shape, s))
+    }
+}
+
+pub fn fill_shape(
+    voxel_data: &mut [u8],
+    dims: [usize; 3],
+    raster: &ShapeRaster,
+    min: [i64; 3],
+    max: [i64; 3],
+    write: impl Fn(u8) -> u8,
+) -> usize {
+    let lo: [usize; 3] = std::array::from_fn(|a| min[a].max(0) as usize);
+    let hi: [usize; 3] = std::array::from_fn(|a| (max[a] + 1).clamp(0, dims[a] as i64) as usize);
+    let mut written = 0;
+    for x in lo[0]..hi[0] {
+        for y in lo[1]..hi[1] {
+            let row = x * dims[1] * dims[2] + y * dims[2];
+            for z in lo[2]..hi[2] {
+                if raster.contains([x as i64, y as i64, z as i64]) {
+                    let value = &mut voxel_data[row + z];
+                    let next = write(*value);
+                    written += (next != *value) as usize;
+                    *value = next;
+                }
+            }
+        }
+    }
+    written
+}
+
+fn validate_fill_input(
+    voxel_data: &[u8],
+    dims: [usize; 3],
+    up_face_dir: usize,
+) -> Result<(), FillShapeError> {
+    validate_voxel_data(voxel_data, dims)?;
+    if up_face_dir >= FACES_PER_BLOCK {
+        return Err(FillShapeError::InvalidUpDirection {
+            face_dir: up_face_dir,
+        });
+    }
+    Ok(())
+}
+
+pub fn apply_fill_shape(
+    voxel_data: &mut [u8],
+    dims: [usize; 3],
+    shape: FillShape,
+    min: [i64; 3],
+    max: [i64; 3],
+    up_face_dir: usize,
+    wall_thickness: usize,
+    mode: FillMode,
+    block_type: u8,
+) -> Result<usize, FillShapeError> {
+    validate_fill_input(voxel_data, dims, up_face_dir)?;
+    let raster = ShapeRaster::new(shape, min, max, up_face_dir, wall_thickness);
+    Ok(fill_shape(voxel_data, dims, &raster, min, max, |current| {
+        mode.apply(current, block_type)
+    }))
+}
+
+pub fn write_fill_shape_preview(
+    preview_buffer: &mut [u8],
+    dims: [usize; 3],
+    shape: FillShape,
+    min: [i64; 3],
+    max: [i64; 3],
+    up_face_dir: usize,
+    wall_thickness: usize,
+    mode: FillMode,
+    block_type: u8,
+) -> Result<usize, FillShapeError> {
+    validate_fill_input(preview_buffer, dims, up_face_dir)?;
+    let raster = ShapeRaster::new(shape, min, max, up_face_dir, wall_thickness);
+    let preview_value = mode.preview_value(block_type);
+    Ok(fill_shape(preview_buffer, dims, &raster, min, max, |_| {
+        preview_value
+    }))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const UP: usize = 2;
+
+    fn count(shape: FillShape, size: [i64; 3], up_face_dir: usize, wall: usize) -> usize {
+        let raster = ShapeRaster::new(shape, [0; 3], size.map(|s| s - 1), up_face_dir, wall);
+        let mut total = 0;
+        for x in 0..size[0] {
+            for y in 0..size[1] {
+                for z in 0..size[2] {
+                    total += raster.contains([x, y, z]) as usize;
+                }
+            }
+        }
+        total
+    }
+
+    fn layer_counts(shape: FillShape, size: i64, up_face_dir: usize) -> Vec<usize> {
+        let raster = ShapeRaster::new(shape, [0; 3], [size - 1; 3], up_face_dir, 0);
+        let axis = up_face_dir / 2;
+        (0..size)
+            .map(|layer| {
+                let mut total = 0;
+                for a in 0..size {
+                    for b in 0..size {
+                        let mut p = [a, b, a];
+                        p[axis] = layer;
+                        p[(axis + 1) % 3] = a;
+                        p[(axis + 2) % 3] = b;
+                        total += raster.contains(p) as usize;
+                    }
+                }
+                total
+            })
+            .collect()
+    }
+
+    #[test]
+    fn solid_shape_volumes() {
+        assert_eq!(count(FillShape::Box, [3, 4, 5], UP, 0), 60);
+        assert_eq!(count(FillShape::Ellipsoid, [1, 1, 1], UP, 0), 1);
+        assert_eq!(count(FillShape::Ellipsoid, [3, 3, 3], UP, 0), 19);
+        assert_eq!(count(FillShape::Cylinder, [3, 4, 3], UP, 0), 36);
+        assert_eq!(count(FillShape::Cylinder, [5, 2, 5], UP, 0), 42);
+        assert_eq!(count(FillShape::Pyramid, [5, 3, 5], UP, 0), 25 + 9 + 1);
+    }
+
+    #[test]
+    fn cone_and_pyramid_narrow_towards_up_direction() {
+        for face_dir in 0..6 {
+            let cone = layer_counts(FillShape::Cone, 7, face_dir);
+            let pyramid = layer_counts(FillShape::Pyramid, 7, face_dir);
+            let (cone, pyramid) = if face_dir % 2 == 1 {
+                (
+                    cone.into_iter().rev().collect(),
+                    pyramid.into_iter().rev().collect(),
+                )
+            } else {
+                (cone, pyramid)
+            };
+            assert!(cone.windows(2).all(|w: &[usize]| w[0] >= w[1]), "{cone:?}");
+            assert!(
+                pyramid.windows(2).all(|w: &[usize]| w[0] >= w[1]),
+                "{pyramid:?}"
+            );
+            assert!(cone[0] > cone[6]);
+            assert_eq!(pyramid, vec![49, 49, 25, 25, 9, 9, 1]);
+        }
+    }
+
+    #[test]
+    fn hollow_shapes_keep_only_the_wall() {
+        assert_eq!(count(FillShape::Box, [5, 5, 5], UP, 1), 125 - 27);
+        assert_eq!(count(FillShape::Box, [5, 5, 5], UP, 2), 124);
+        assert_eq!(count(FillShape::Box, [2, 2, 2], UP, 1), 8);
+        let solid = count(FillShape::Ellipsoid, [9, 9, 9], UP, 0);
+        let hollow = count(FillShape::Ellipsoid, [9, 9, 9], UP, 1);
+        let inner = count(FillShape::Ellipsoid, [7, 7, 7], UP, 0);
+        assert_eq!(hollow, solid - inner);
+    }
+
+    #[test]
+    fn modes_attach_erase_and_paint() {
+        let dims = [3, 1, 1];
+        let mut data = vec![0, 2, 0x83];
+        let written = apply_fill_shape(
+            &mut data,
+            dims,
+            FillShape::Box,
+            [0; 3],
+            [2, 0, 0],
+            UP,
+            0,
+            FillMode::Paint,
+            5,
+        )
+        .unwrap();
+        assert_eq!((written, data.clone()), (2, vec![0, 5, 5]));
+
+        apply_fill_shape(
+            &mut data,
+            dims,
+            FillShape::Box,
+            [1, 0, 0],
+            [1, 0, 0],
+            UP,
+            0,
+            FillMode::Erase,
+            5,
+        )
+        .unwrap();
+        assert_eq!(data, vec![0, 0, 5]);
+
+        apply_fill_shape(
+            &mut data,
+            dims,
+            FillShape::Box,
+            [-4, -1, 0],
+            [1, 3, 0],
+            UP,
+            0,
+            FillMode::Attach,
+            7,
+        )
+        .unwrap();
+        assert_eq!(data, vec![7, 7, 5]);
+    }
+
+    #[test]
+    fn preview_values_carry_raycastable_bit() {
+        let dims = [2, 2, 2];
+        for (mode, expected) in [
+            (FillMode::Attach, 3),
+            (FillMode::Erase, RAYCASTABLE_BIT),
+            (FillMode::Paint, 3 | RAYCASTABLE_BIT),
+        ] {
+            let mut preview = vec![0; 8];
+            let written = write_fill_shape_preview(
+                &mut preview,
+                dims,
+                FillShape::Box,
+                [0; 3],
+                [1, 1, 0],
+                UP,
+                0,
+                mode,
+                3,
+            )
+            .unwrap();
+            assert_eq!(written, 4);
+            assert_eq!(
+                preview,
+                vec![expected, 0, expected, 0, expected, 0, expected, 0]
+            );
+        }
+        assert!(
+            write_fill_shape_preview(
+                &mut [0; 3],
+                dims,
+                FillShape::Box,
+                [0; 3],
+                [1; 3],
+                UP,
+                0,
+                FillMode::Attach,
+                1
+            )
+            .is_err()
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_shape_and_mode_values() {
+        assert_eq!(FillShape::try_from(4), Ok(FillShape::Pyramid));
+        assert_eq!(
+            FillShape::try_from(5),
+            Err(FillShapeError::InvalidShape { value: 5 })
+        );
+        assert_eq!(FillMode::try_from(2), Ok(FillMode::Paint));
+        assert_eq!(
+            FillMode::try_from(3),
+            Err(FillShapeError::InvalidMode { value: 3 })
+        );
+    }
+
+    #[test]
+    fn rejects_invalid_up_direction() {
+        assert_eq!(
+            apply_fill_shape(
+                &mut [0; 1],
+                [1, 1, 1],
+                FillShape::Cone,
+                [0; 3],
+                [0; 3],
+                6,
+                0,
+                FillMode::Attach,
+                1
+            ),
+            Err(FillShapeError::InvalidUpDirection { face_dir: 6 })
+        );
+    }
+}
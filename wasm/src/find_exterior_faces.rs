@@ -1,14 +1,179 @@
-use crate::ambient_occlusion::{calculate_ambient_occlusion, precompute_ao_offsets, OCCLUSION_LEVELS};
+use crate::ambient_occlusion::{calculate_ambient_occlusion, precompute_ao_offsets, AoConfig};
+use crate::bitmask_backend::{BitmaskColumns, MeshingBackend};
+use crate::block_materials::{BlockMaterials, BLOCK_TYPE_COUNT};
+use crate::color_palette::ColorPalette;
+use crate::exterior_fill::{CavityReport, ExteriorFill};
+use crate::lod::{downsample_voxels, LOD_FACTORS};
 use crate::mesh_arrays::MeshArrays;
-use crate::texture_coords::get_texture_coordinates;
+use crate::packed_vertex::pack_vertex;
+use crate::picking::PickQuad;
+use crate::texture_coords::{
+    get_atlas_index, get_texture_coordinates, get_tile_origin, get_tiled_uv, UvMode,
+};
+use crate::validation::{
+    validate_atlas_mapping, validate_packed_output, validate_volume_input, MeshingError,
+};
 use crate::voxel_constants::FACES;
 
+const QUAD_INDICES: [u32; 6] = [0, 1, 2, 0, 2, 3];
+const FLIPPED_QUAD_INDICES: [u32; 6] = [1, 2, 3, 1, 3, 0];
+
+#[derive(Clone, Copy)]
+pub(crate) struct SliceRange {
+    pub face_dir: usize,
+    pub depth: usize,
+    pub vertex_start: usize,
+    pub index_start: usize,
+    pub translucent_vertex_start: usize,
+    pub translucent_index_start: usize,
+}
+
+struct SweepDirection {
+    axis: usize,
+    u: usize,
+    v: usize,
+    dir: i32,
+    face_dir: usize,
+    dx: i32,
+    dy: i32,
+    dz: i32,
+    u_size: usize,
+    v_size: usize,
+    depth_size: usize,
+    origin: [usize; 3],
+    strides: [usize; 3],
+    u_stride: usize,
+    v_stride: usize,
+    neighbor_max: i32,
+    normal_flat_offset: i32,
+    ao_off: [i32; 8],
+    ao_dim_n: i32,
+    ao_dim_u: i32,
+    ao_dim_v: i32,
+    ao_u_is_loop_u: bool,
+    ao_v_is_loop_u: bool,
+    ao_u_origin: i32,
+    ao_v_origin: i32,
+}
+
+struct SweepSlice {
+    depth: usize,
+    depth_base_idx: usize,
+    neighbor_coord: i32,
+    neighbor_in_bounds: bool,
+    neighbor_in_region: bool,
+}
+
+impl SweepDirection {
+    fn new(
+        axis: usize,
+        dir_idx: usize,
+        data_dims: [usize; 3],
+        origin: [usize; 3],
+        dims: [usize; 3],
+    ) -> Self {
+        let u = (axis + 1) % 3;
+        let v = (axis + 2) % 3;
+        let dir: i32 = if dir_idx == 0 { -1 } else { 1 };
+        let face_dir = axis * 2 + if dir > 0 { 0 } else { 1 };
+        let dx: i32 = if axis == 0 { dir } else { 0 };
+        let dy: i32 = if axis == 1 { dir } else { 0 };
+        let dz: i32 = if axis == 2 { dir } else { 0 };
+
+        let data_dim_z = data_dims[2];
+        let stride_x = data_dims[1] * data_dim_z;
+        let ao_offsets = precompute_ao_offsets(face_dir, stride_x as i32, data_dim_z as i32);
+        let normal_flat_offset: i32 = dx * stride_x as i32 + dy * data_dim_z as i32 + dz;
+        let dims_i32 = [data_dims[0] as i32, data_dims[1] as i32, data_dims[2] as i32];
+        let strides = [stride_x, data_dim_z, 1];
+
+        Self {
+            axis,
+            u,
+            v,
+            dir,
+            face_dir,
+            dx,
+            dy,
+            dz,
+            u_size: dims[u],
+            v_size: dims[v],
+            depth_size: dims[axis],
+            origin,
+            strides,
+            u_stride: strides[u],
+            v_stride: strides[v],
+            neighbor_max: data_dims[axis] as i32,
+            normal_flat_offset,
+            ao_off: ao_offsets.offsets,
+            ao_dim_n: dims_i32[ao_offsets.n_axis],
+            ao_dim_u: dims_i32[ao_offsets.u_axis],
+            ao_dim_v: dims_i32[ao_offsets.v_axis],
+            ao_u_is_loop_u: ao_offsets.u_axis == u,
+            ao_v_is_loop_u: ao_offsets.v_axis == u,
+            ao_u_origin: origin[ao_offsets.u_axis] as i32,
+            ao_v_origin: origin[ao_offsets.v_axis] as i32,
+        }
+    }
+
+    #[inline(always)]
+    fn slice(&self, d: usize) -> SweepSlice {
+        let depth_base_idx = (self.origin[self.axis] + d) * self.strides[self.axis]
+            + self.origin[self.u] * self.u_stride
+            + self.origin[self.v] * self.v_stride;
+        let neighbor_coord = (self.origin[self.axis] + d) as i32 + self.dir;
+        let neighbor_in_bounds = if self.dir > 0 {
+            neighbor_coord < self.neighbor_max
+        } else {
+            neighbor_coord >= 0
+        };
+        let neighbor_in_region = if self.dir > 0 {
+            d + 1 < self.depth_size
+        } else {
+            d > 0
+        };
+        SweepSlice {
+            depth: d,
+            depth_base_idx,
+            neighbor_coord,
+            neighbor_in_bounds,
+            neighbor_in_region,
+        }
+    }
+
+    #[inline(always)]
+    fn local_coords(&self, d: usize, iu: usize, iv: usize) -> (usize, usize, usize) {
+        let mut coords = [0; 3];
+        coords[self.axis] = d;
+        coords[self.u] = iu;
+        coords[self.v] = iv;
+        (coords[0], coords[1], coords[2])
+    }
+}
+
 pub struct ExteriorFacesFinder {
     mask: Vec<i16>,
     ao_mask: Vec<u8>,
     is_selected_mask: Vec<u8>,
+    translucent_mask: Vec<u8>,
+    block_type_mask: Vec<u8>,
     mask_size: usize,
     max_dim: usize,
+    materials: BlockMaterials,
+    translucent_arrays: MeshArrays,
+    record_slices: bool,
+    slice_ranges: Vec<SliceRange>,
+    backend: MeshingBackend,
+    columns: BitmaskColumns,
+    face_key_bases: [u64; BLOCK_TYPE_COUNT],
+    ao: AoConfig,
+    lod_voxels: Vec<u8>,
+    exterior_only: bool,
+    exterior_fill: ExteriorFill,
+    cavity_report: CavityReport,
+    uv_mode: UvMode,
+    palette: Option<ColorPalette>,
+    picking: bool,
 }
 
 impl ExteriorFacesFinder {
@@ -18,11 +183,87 @@ impl ExteriorFacesFinder {
             mask: vec![-1; mask_size],
             ao_mask: vec![0; mask_size],
             is_selected_mask: vec![0; mask_size],
+            translucent_mask: vec![0; mask_size],
+            block_type_mask: vec![0; mask_size],
             mask_size,
             max_dim: max_dimension,
+            materials: BlockMaterials::default(),
+            translucent_arrays: MeshArrays::new(0, 0),
+            record_slices: false,
+            slice_ranges: Vec::new(),
+            backend: MeshingBackend::default(),
+            columns: BitmaskColumns::default(),
+            face_key_bases: [u64::MAX; BLOCK_TYPE_COUNT],
+            ao: AoConfig::default(),
+            lod_voxels: Vec::new(),
+            exterior_only: false,
+            exterior_fill: ExteriorFill::default(),
+            cavity_report: CavityReport::default(),
+            uv_mode: UvMode::default(),
+            palette: None,
+            picking: false,
         }
     }
 
+    pub fn set_block_materials(&mut self, block_classes: &[u8]) -> Result<(), MeshingError> {
+        self.materials = BlockMaterials::from_classes(block_classes)?;
+        Ok(())
+    }
+
+    pub fn set_backend(&mut self, backend: MeshingBackend) {
+        self.backend = backend;
+    }
+
+    pub fn set_uv_mode(&mut self, uv_mode: UvMode) {
+        self.uv_mode = uv_mode;
+    }
+
+    pub fn uv_mode(&self) -> UvMode {
+        self.uv_mode
+    }
+
+    pub fn set_color_palette(&mut self, palette: Option<ColorPalette>) {
+        self.palette = palette;
+    }
+
+    pub fn color_palette(&self) -> Option<&ColorPalette> {
+        self.palette.as_ref()
+    }
+
+    pub fn set_picking(&mut self, picking: bool) {
+        self.picking = picking;
+    }
+
+    pub fn picking(&self) -> bool {
+        self.picking
+    }
+
+    pub(crate) fn set_ao_config(&mut self, ao: AoConfig) {
+        self.ao = ao;
+    }
+
+    pub fn try_set_ao_config(&mut self, ao: AoConfig) -> Result<(), MeshingError> {
+        ao.validate()?;
+        self.ao = ao;
+        Ok(())
+    }
+
+    pub fn ao_config(&self) -> AoConfig {
+        self.ao
+    }
+
+    pub fn set_exterior_only(&mut self, exterior_only: bool) {
+        self.exterior_only = exterior_only;
+    }
+
+    pub fn exterior_only(&self) -> bool {
+        self.exterior_only
+    }
+
+    pub fn cavity_report(&self) -> CavityReport {
+        self.cavity_report
+    }
+
     pub fn find_exterior_faces(
         &mut self,
         voxel_data: &[u8],
@@ -37,10 +278,310 @@ impl ExteriorFacesFinder {
         selection_dim_y: usize,
         selection_dim_z: usize,
         selection_empty: bool,
+    ) {
+        self.find_faces_in_region(
+            voxel_data,
+            texture_width,
+            block_atlas_mapping,
+            [dim_x, dim_y, dim_z],
+            [0, 0, 0],
+            [dim_x, dim_y, dim_z],
+            [(0, dim_x), (0, dim_y), (0, dim_z)],
+            mesh_arrays,
+            selection_data,
+            selection_dim_x,
+            selection_dim_y,
+            selection_dim_z,
+            selection_empty,
+        );
+    }
+
+    pub fn try_find_exterior_faces(
+        &mut self,
+        voxel_data: &[u8],
+        texture_width: i32,
+        block_atlas_mapping: &[i32],
+        dim_x: usize,
+        dim_y: usize,
+        dim_z: usize,
+        mesh_arrays: &mut MeshArrays,
+        selection_data: &[u8],
+        selection_dim_x: usize,
+        selection_dim_y: usize,
+        selection_dim_z: usize,
+        selection_empty: bool,
+    ) -> Result<(), MeshingError> {
+        let texture_width = self.validate_input(
+            voxel_data,
+            texture_width,
+            block_atlas_mapping,
+            [dim_x, dim_y, dim_z],
+            [dim_x, dim_y, dim_z],
+            mesh_arrays,
+            selection_data,
+            selection_dim_x,
+            selection_dim_y,
+            selection_dim_z,
+            selection_empty,
+        )?;
+        self.find_exterior_faces(
+            voxel_data,
+            texture_width,
+            block_atlas_mapping,
+            dim_x,
+            dim_y,
+            dim_z,
+            mesh_arrays,
+            selection_data,
+            selection_dim_x,
+            selection_dim_y,
+            selection_dim_z,
+            selection_empty,
+        );
+        Ok(())
+    }
+
+    pub fn try_find_exterior_faces_with_halo(
+        &mut self,
+        voxel_data: &[u8],
+        texture_width: i32,
+        block_atlas_mapping: &[i32],
+        dim_x: usize,
+        dim_y: usize,
+        dim_z: usize,
+        mesh_arrays: &mut MeshArrays,
+        selection_data: &[u8],
+        selection_dim_x: usize,
+        selection_dim_y: usize,
+        selection_dim_z: usize,
+        selection_empty: bool,
+    ) -> Result<(), MeshingError> {
+        let dims = [dim_x, dim_y, dim_z];
+        let padded = [dim_x.checked_add(2), dim_y.checked_add(2), dim_z.checked_add(2)];
+        let [Some(padded_x), Some(padded_y), Some(padded_z)] = padded else {
+            return Err(MeshingError::DimensionOverflow { dims });
+        };
+        let texture_width = self.validate_input(
+            voxel_data,
+            texture_width,
+            block_atlas_mapping,
+            [padded_x, padded_y, padded_z],
+            dims,
+            mesh_arrays,
+            selection_data,
+            selection_dim_x,
+            selection_dim_y,
+            selection_dim_z,
+            selection_empty,
+        )?;
+        self.find_exterior_faces_with_halo(
+            voxel_data,
+            texture_width,
+            block_atlas_mapping,
+            dim_x,
+            dim_y,
+            dim_z,
+            mesh_arrays,
+            selection_data,
+            selection_dim_x,
+            selection_dim_y,
+            selection_dim_z,
+            selection_empty,
+        );
+        Ok(())
+    }
+
+    pub fn find_exterior_faces_with_halo(
+        &mut self,
+        voxel_data: &[u8],
+        texture_width: i32,
+        block_atlas_mapping: &[i32],
+        dim_x: usize,
+        dim_y: usize,
+        dim_z: usize,
+        mesh_arrays: &mut MeshArrays,
+        selection_data: &[u8],
+        selection_dim_x: usize,
+        selection_dim_y: usize,
+        selection_dim_z: usize,
+        selection_empty: bool,
+    ) {
+        self.find_faces_in_region(
+            voxel_data,
+            texture_width,
+            block_atlas_mapping,
+            [dim_x + 2, dim_y + 2, dim_z + 2],
+            [1, 1, 1],
+            [dim_x, dim_y, dim_z],
+            [(0, dim_x), (0, dim_y), (0, dim_z)],
+            mesh_arrays,
+            selection_data,
+            selection_dim_x,
+            selection_dim_y,
+            selection_dim_z,
+            selection_empty,
+        );
+    }
+
+    pub fn find_exterior_faces_lod(
+        &mut self,
+        voxel_data: &[u8],
+        texture_width: i32,
+        block_atlas_mapping: &[i32],
+        dim_x: usize,
+        dim_y: usize,
+        dim_z: usize,
+        lod_factor: usize,
+        mesh_arrays: &mut MeshArrays,
+    ) {
+        let mut lod_voxels = std::mem::take(&mut self.lod_voxels);
+        let [lod_x, lod_y, lod_z] =
+            downsample_voxels(voxel_data, [dim_x, dim_y, dim_z], lod_factor, &mut lod_voxels);
+        self.find_exterior_faces(
+            &lod_voxels,
+            texture_width,
+            block_atlas_mapping,
+            lod_x,
+            lod_y,
+            lod_z,
+            mesh_arrays,
+            &[],
+            0,
+            0,
+            0,
+            true,
+        );
+        let bounds = [dim_x, dim_y, dim_z].map(|dim| dim as u32);
+        mesh_arrays.scale_positions(lod_factor as u32, bounds);
+        self.lod_voxels = lod_voxels;
+    }
+
+    pub fn try_find_exterior_faces_lod(
+        &mut self,
+        voxel_data: &[u8],
+        texture_width: i32,
+        block_atlas_mapping: &[i32],
+        dim_x: usize,
+        dim_y: usize,
+        dim_z: usize,
+        lod_factor: usize,
+        mesh_arrays: &mut MeshArrays,
+    ) -> Result<(), MeshingError> {
+        if !LOD_FACTORS.contains(&lod_factor) {
+            return Err(MeshingError::InvalidLodFactor { factor: lod_factor });
+        }
+        if lod_factor > 1 && self.uv_mode == UvMode::Tiled {
+            return Err(MeshingError::UnsupportedLodOption {
+                option: "tiled UVs",
+            });
+        }
+        let texture_width = self.validate_input(
+            voxel_data,
+            texture_width,
+            block_atlas_mapping,
+            [dim_x, dim_y, dim_z],
+            [dim_x, dim_y, dim_z],
+            mesh_arrays,
+            &[],
+            0,
+            0,
+            0,
+            true,
+        )?;
+        self.find_exterior_faces_lod(
+            voxel_data,
+            texture_width,
+            block_atlas_mapping,
+            dim_x,
+            dim_y,
+            dim_z,
+            lod_factor,
+            mesh_arrays,
+        );
+        Ok(())
+    }
+
+    pub(crate) fn validate_input(
+        &self,
+        voxel_data: &[u8],
+        texture_width: i32,
+        block_atlas_mapping: &[i32],
+        data_dims: [usize; 3],
+        dims: [usize; 3],
+        mesh_arrays: &MeshArrays,
+        selection_data: &[u8],
+        selection_dim_x: usize,
+        selection_dim_y: usize,
+        selection_dim_z: usize,
+        selection_empty: bool,
+    ) -> Result<i32, MeshingError> {
+        let palette_only = self.palette.is_some() && block_atlas_mapping.is_empty();
+        let texture_width = if palette_only { 1 } else { texture_width };
+        let max_type = validate_volume_input(
+            voxel_data,
+            texture_width,
+            data_dims,
+            selection_data,
+            selection_dim_x,
+            selection_dim_y,
+            selection_dim_z,
+            selection_empty,
+        )?;
+        let atlas_len = if palette_only {
+            0
+        } else {
+            validate_atlas_mapping(block_atlas_mapping, max_type)?
+        };
+
+        if mesh_arrays.is_packed() {
+            if self.palette.is_some() {
+                return Err(MeshingError::UnsupportedPackedOption {
+                    option: "color palette",
+                });
+            }
+            if self.uv_mode == UvMode::Tiled {
+                return Err(MeshingError::UnsupportedPackedOption {
+                    option: "tiled UVs",
+                });
+            }
+            if self.ao.enabled && self.ao != AoConfig::default() {
+                return Err(MeshingError::UnsupportedPackedOption {
+                    option: "custom AO curve",
+                });
+            }
+            validate_packed_output(dims, &block_atlas_mapping[..atlas_len])?;
+        }
+        Ok(texture_width)
+    }
+
+    pub(crate) fn set_record_slices(&mut self, record_slices: bool) {
+        self.record_slices = record_slices;
+    }
+
+    pub(crate) fn slice_ranges(&self) -> &[SliceRange] {
+        &self.slice_ranges
+    }
+
+    pub(crate) fn find_faces_in_region(
+        &mut self,
+        voxel_data: &[u8],
+        texture_width: i32,
+        block_atlas_mapping: &[i32],
+        data_dims: [usize; 3],
+        origin: [usize; 3],
+        dims: [usize; 3],
+        depth_ranges: [(usize, usize); 3],
+        mesh_arrays: &mut MeshArrays,
+        selection_data: &[u8],
+        selection_dim_x: usize,
+        selection_dim_y: usize,
+        selection_dim_z: usize,
+        selection_empty: bool,
     ) {
         mesh_arrays.reset();
+        self.slice_ranges.clear();
 
-        let max_dimension = dim_x.max(dim_y).max(dim_z);
+        let max_dimension = dims[0].max(dims[1]).max(dims[2]);
         let current_mask_size = max_dimension * max_dimension;
 
         if current_mask_size > self.mask_size {
@@ -49,53 +590,70 @@ impl ExteriorFacesFinder {
             self.mask = vec![-1; current_mask_size];
             self.ao_mask = vec![0; current_mask_size];
             self.is_selected_mask = vec![0; current_mask_size];
+            self.translucent_mask = vec![0; current_mask_size];
+            self.block_type_mask = vec![0; current_mask_size];
         } else if selection_empty {
             self.is_selected_mask[..current_mask_size].fill(0);
         }
 
-        let stride_x = dim_y * dim_z;
-        let max_dim = self.max_dim;
-        let dims = [dim_x, dim_y, dim_z];
+        let has_translucent = self.materials.has_translucent();
+        if has_translucent {
+            if self.translucent_arrays.format != mesh_arrays.format {
+                self.translucent_arrays = MeshArrays::with_format(mesh_arrays.format, 0, 0);
+            }
+            self.translucent_arrays.reset();
+        }
 
-        for axis in 0..3usize {
-            let u = (axis + 1) % 3;
-            let v = (axis + 2) % 3;
+        self.cavity_report = if self.exterior_only {
+            self.exterior_fill
+                .compute(voxel_data, data_dims, origin, dims, &self.materials)
+        } else {
+            CavityReport::default()
+        };
 
-            let axis_size = dims[axis];
-            let u_size = dims[u];
-            let v_size = dims[v];
+        let use_bitmask = self.backend == MeshingBackend::Bitmask;
+        if use_bitmask {
+            self.columns.build(
+                voxel_data,
+                data_dims,
+                origin,
+                dims,
+                &self.materials,
+                self.exterior_only.then_some(&self.exterior_fill),
+                selection_data,
+                selection_dim_x,
+                selection_dim_y,
+                selection_dim_z,
+                selection_empty,
+            );
+        }
+
+        let max_dim = self.max_dim;
+
+        for (axis, &(depth_start, depth_end)) in depth_ranges.iter().enumerate() {
 
             for dir_idx in 0..2usize {
-                let dir: i32 = if dir_idx == 0 { -1 } else { 1 };
-                let face_dir = axis * 2 + if dir > 0 { 0 } else { 1 };
-                let dx: i32 = if axis == 0 { dir } else { 0 };
-                let dy: i32 = if axis == 1 { dir } else { 0 };
-                let dz: i32 = if axis == 2 { dir } else { 0 };
-                let neighbor_max = dims[axis] as i32;
-                let ao_offsets = precompute_ao_offsets(face_dir, stride_x as i32, dim_z as i32);
-                let normal_flat_offset: i32 = dx * stride_x as i32 + dy * dim_z as i32 + dz;
-
-                let dims_i32 = [dim_x as i32, dim_y as i32, dim_z as i32];
-                let ao_dim_n = dims_i32[ao_offsets.n_axis];
-                let ao_dim_u = dims_i32[ao_offsets.u_axis];
-                let ao_dim_v = dims_i32[ao_offsets.v_axis];
-                let ao_off = ao_offsets.offsets;
-
-                let ao_u_is_loop_u = ao_offsets.u_axis == u;
-                let ao_v_is_loop_u = ao_offsets.v_axis == u;
-
-                let x_is_depth = axis == 0;
-                let y_is_depth = axis == 1;
-                let z_is_depth = axis == 2;
-                let x_is_u_axis = !x_is_depth && u == 0;
-                let y_is_u_axis = !y_is_depth && u == 1;
-                let z_is_u_axis = !z_is_depth && u == 2;
-
-                let strides = [stride_x, dim_z, 1];
-                let u_stride = strides[u];
-                let v_stride = strides[v];
-
-                for d in 0..axis_size {
+                let sweep = SweepDirection::new(axis, dir_idx, data_dims, origin, dims);
+                if use_bitmask {
+                    self.face_key_bases.fill(u64::MAX);
+                }
+                let u_size = sweep.u_size;
+                let v_size = sweep.v_size;
+                let index_start = mesh_arrays.index_count;
+                let translucent_index_start = self.translucent_arrays.index_count;
+
+                for d in depth_start..depth_end {
+                    if self.record_slices {
+                        self.slice_ranges.push(SliceRange {
+                            face_dir: sweep.face_dir,
+                            depth: d,
+                            vertex_start: mesh_arrays.vertex_count,
+                            index_start: mesh_arrays.index_count,
+                            translucent_vertex_start: self.translucent_arrays.vertex_count,
+                            translucent_index_start: self.translucent_arrays.index_count,
+                        });
+                    }
+
                     if !selection_empty {
                         for iv in 0..v_size {
                             let row_offset = iv * max_dim;
@@ -103,150 +661,398 @@ impl ExteriorFacesFinder {
                         }
                     }
 
+                    let slice = sweep.slice(d);
                     let mut has_faces = false;
 
-                    let depth_base_idx = d * strides[axis];
-                    let neighbor_coord = d as i32 + dir;
-                    let neighbor_in_bounds = if dir > 0 {
-                        neighbor_coord < neighbor_max
+                    if use_bitmask {
+                        has_faces = self.fill_face_words(
+                            &sweep,
+                            &slice,
+                            voxel_data,
+                            block_atlas_mapping,
+                            selection_data,
+                            selection_dim_x,
+                            selection_dim_y,
+                            selection_dim_z,
+                        );
                     } else {
-                        neighbor_coord >= 0
-                    };
-
-                    for iu in 0..u_size {
-                        let u_base_idx = depth_base_idx + iu * u_stride;
-                        for iv in 0..v_size {
-                            let block_idx = u_base_idx + iv * v_stride;
-                            let block_value = unsafe { *voxel_data.get_unchecked(block_idx) };
-                            let block_type = block_value & 0x7F;
-                            let block_visible = block_type != 0;
-
-                            let block_is_selected = if selection_empty {
-                                false
-                            } else {
-                                let x = if x_is_depth { d } else if x_is_u_axis { iu } else { iv };
-                                let y = if y_is_depth { d } else if y_is_u_axis { iu } else { iv };
-                                let z = if z_is_depth { d } else if z_is_u_axis { iu } else { iv };
-                                is_selection_set(
+                        for iu in 0..u_size {
+                            for iv in 0..v_size {
+                                has_faces |= self.fill_face_cell(
+                                    &sweep,
+                                    &slice,
+                                    iu,
+                                    iv,
+                                    voxel_data,
+                                    block_atlas_mapping,
                                     selection_data,
-                                    x,
-                                    y,
-                                    z,
                                     selection_dim_x,
                                     selection_dim_y,
                                     selection_dim_z,
-                                )
-                            };
-
-                            if !block_visible && !block_is_selected {
-                                continue;
-                            }
-
-                            let neighbor_idx = block_idx as i32 + normal_flat_offset;
-
-                            let mask_idx = iv * max_dim + iu;
-
-                            let ao_nn = neighbor_coord;
-                            let ao_nu = if ao_u_is_loop_u { iu as i32 } else { iv as i32 };
-                            let ao_nv = if ao_v_is_loop_u { iu as i32 } else { iv as i32 };
-
-                            if block_is_selected && !block_visible {
-                                let x = if x_is_depth { d } else if x_is_u_axis { iu } else { iv };
-                                let y = if y_is_depth { d } else if y_is_u_axis { iu } else { iv };
-                                let z = if z_is_depth { d } else if z_is_u_axis { iu } else { iv };
-                                let nx = x as i32 + dx;
-                                let ny = y as i32 + dy;
-                                let nz = z as i32 + dz;
-
-                                let neighbor_is_selected = neighbor_in_bounds
-                                    && is_selection_set(
-                                        selection_data,
-                                        nx as usize,
-                                        ny as usize,
-                                        nz as usize,
-                                        selection_dim_x,
-                                        selection_dim_y,
-                                        selection_dim_z,
-                                    );
-
-                                if !neighbor_is_selected {
-                                    let selection_block_type = get_selection_value(
-                                        selection_data,
-                                        x,
-                                        y,
-                                        z,
-                                        selection_dim_x,
-                                        selection_dim_y,
-                                        selection_dim_z,
-                                    ) & 0x7F;
-                                    let texture_index = block_atlas_mapping
-                                        [(selection_block_type.max(1) - 1) as usize];
-
-                                    self.ao_mask[mask_idx] = calculate_ambient_occlusion(
-                                        ao_nn,
-                                        ao_nu,
-                                        ao_nv,
-                                        ao_dim_n,
-                                        ao_dim_u,
-                                        ao_dim_v,
-                                        voxel_data,
-                                        neighbor_idx,
-                                        &ao_off,
-                                    );
-
-                                    self.mask[mask_idx] = texture_index as i16;
-                                    self.is_selected_mask[mask_idx] = 1;
-                                    has_faces = true;
-                                }
-                            } else if block_visible {
-                                let neighbor_visible = neighbor_in_bounds
-                                    && (unsafe { *voxel_data.get_unchecked(neighbor_idx as usize) }
-                                        & 0x7F)
-                                        != 0;
-
-                                if !neighbor_visible {
-                                    let texture_index =
-                                        block_atlas_mapping[(block_type - 1) as usize];
-
-                                    self.ao_mask[mask_idx] = calculate_ambient_occlusion(
-                                        ao_nn,
-                                        ao_nu,
-                                        ao_nv,
-                                        ao_dim_n,
-                                        ao_dim_u,
-                                        ao_dim_v,
-                                        voxel_data,
-                                        neighbor_idx,
-                                        &ao_off,
-                                    );
-
-                                    self.mask[mask_idx] = texture_index as i16;
-                                    if block_is_selected {
-                                        self.is_selected_mask[mask_idx] = 1;
-                                    }
-                                    has_faces = true;
-                                }
+                                    selection_empty,
+                                );
                             }
                         }
                     }
 
                     if has_faces {
-                        self.generate_greedy_mesh(
-                            u_size,
-                            v_size,
-                            d,
-                            axis,
-                            u,
-                            v,
-                            dir,
-                            face_dir,
-                            texture_width,
-                            mesh_arrays,
-                        );
+                        if use_bitmask {
+                            self.generate_greedy_mesh_from_bits(
+                                &sweep,
+                                d,
+                                texture_width,
+                                mesh_arrays,
+                            );
+                        } else {
+                            self.generate_greedy_mesh(
+                                u_size,
+                                v_size,
+                                d,
+                                axis,
+                                sweep.u,
+                                sweep.v,
+                                sweep.dir,
+                                sweep.face_dir,
+                                texture_width,
+                                mesh_arrays,
+                            );
+                        }
+                    }
+                }
+
+                mesh_arrays.face_index_ranges[sweep.face_dir] =
+                    (index_start, mesh_arrays.index_count);
+                mesh_arrays.translucent_face_index_ranges[sweep.face_dir] =
+                    (translucent_index_start, self.translucent_arrays.index_count);
+            }
+        }
+
+        mesh_arrays.translucent_vertex_start = mesh_arrays.vertex_count;
+        mesh_arrays.translucent_index_start = mesh_arrays.index_count;
+        for range in &mut mesh_arrays.translucent_face_index_ranges {
+            range.0 += mesh_arrays.translucent_index_start;
+            range.1 += mesh_arrays.translucent_index_start;
+        }
+        if has_translucent {
+            mesh_arrays.append(&self.translucent_arrays);
+        }
+    }
+
+    #[inline(always)]
+    fn fill_face_cell(
+        &mut self,
+        sweep: &SweepDirection,
+        slice: &SweepSlice,
+        iu: usize,
+        iv: usize,
+        voxel_data: &[u8],
+        block_atlas_mapping: &[i32],
+        selection_data: &[u8],
+        selection_dim_x: usize,
+        selection_dim_y: usize,
+        selection_dim_z: usize,
+        selection_empty: bool,
+    ) -> bool {
+        let d = slice.depth;
+        let block_idx = slice.depth_base_idx + iu * sweep.u_stride + iv * sweep.v_stride;
+        let block_value = unsafe { *voxel_data.get_unchecked(block_idx) };
+        let block_type = block_value & 0x7F;
+        let block_visible = block_type != 0;
+
+        let block_is_selected = if selection_empty {
+            false
+        } else {
+            let (x, y, z) = sweep.local_coords(d, iu, iv);
+            is_selection_set(
+                selection_data,
+                x,
+                y,
+                z,
+                selection_dim_x,
+                selection_dim_y,
+                selection_dim_z,
+            )
+        };
+
+        if !block_visible && !block_is_selected {
+            return false;
+        }
+
+        let neighbor_idx = block_idx as i32 + sweep.normal_flat_offset;
+        let mask_idx = iv * self.max_dim + iu;
+
+        if block_is_selected && !block_visible {
+            let (x, y, z) = sweep.local_coords(d, iu, iv);
+            let nx = x as i32 + sweep.dx;
+            let ny = y as i32 + sweep.dy;
+            let nz = z as i32 + sweep.dz;
+
+            let neighbor_is_selected = slice.neighbor_in_bounds
+                && is_selection_set(
+                    selection_data,
+                    nx as usize,
+                    ny as usize,
+                    nz as usize,
+                    selection_dim_x,
+                    selection_dim_y,
+                    selection_dim_z,
+                );
+
+            if neighbor_is_selected {
+                return false;
+            }
+
+            let selection_block_type = get_selection_value(
+                selection_data,
+                x,
+                y,
+                z,
+                selection_dim_x,
+                selection_dim_y,
+                selection_dim_z,
+            ) & 0x7F;
+            let texture_index =
+                texture_index(block_atlas_mapping, selection_block_type.max(1), sweep.face_dir);
+
+            self.ao_mask[mask_idx] =
+                self.face_occlusion(sweep, slice, iu, iv, voxel_data, neighbor_idx);
+
+            self.mask[mask_idx] = texture_index as i16;
+            self.block_type_mask[mask_idx] = selection_block_type.max(1);
+            self.is_selected_mask[mask_idx] = 1;
+            self.translucent_mask[mask_idx] = 0;
+            return true;
+        }
+
+        let neighbor_type = if slice.neighbor_in_bounds {
+            (unsafe { *voxel_data.get_unchecked(neighbor_idx as usize) }) & 0x7F
+        } else {
+            0
+        };
+
+        if !self.materials.is_face_visible(block_type, neighbor_type)
+            || (neighbor_type == 0 && self.faces_sealed_cavity(sweep, slice, iu, iv))
+        {
+            return false;
+        }
+
+        let texture_index = texture_index(block_atlas_mapping, block_type, sweep.face_dir);
+
+        self.ao_mask[mask_idx] = self.face_occlusion(sweep, slice, iu, iv, voxel_data, neighbor_idx);
+
+        self.mask[mask_idx] = texture_index as i16;
+        self.block_type_mask[mask_idx] = block_type;
+        self.translucent_mask[mask_idx] = self.materials.is_translucent(block_type) as u8;
+        if block_is_selected {
+            self.is_selected_mask[mask_idx] = 1;
+        }
+        true
+    }
+
+    fn fill_face_words(
+        &mut self,
+        sweep: &SweepDirection,
+        slice: &SweepSlice,
+        voxel_data: &[u8],
+        block_atlas_mapping: &[i32],
+        selection_data: &[u8],
+        selection_dim_x: usize,
+        selection_dim_y: usize,
+        selection_dim_z: usize,
+    ) -> bool {
+        let d = slice.depth;
+        let mut has_faces = false;
+        self.columns.begin_slice(sweep.u_size, sweep.v_size);
+        for iv in 0..sweep.v_size {
+            let row_idx = slice.depth_base_idx + iv * sweep.v_stride;
+            for word in 0..self.columns.face_words_per_row() {
+                let face_word =
+                    self.columns
+                        .face_word(sweep.axis, sweep.dir, d, sweep.u_size, iv, word);
+                let mut faces = face_word.visible;
+                let mut ambiguous = face_word.ambiguous;
+                while ambiguous != 0 {
+                    let bit = ambiguous.trailing_zeros() as usize;
+                    ambiguous &= ambiguous - 1;
+                    let block_idx = row_idx + (word * 64 + bit) * sweep.u_stride;
+                    let neighbor_idx = (block_idx as i32 + sweep.normal_flat_offset) as usize;
+                    if (voxel_data[block_idx] ^ voxel_data[neighbor_idx]) & 0x7F != 0 {
+                        faces |= 1 << bit;
                     }
                 }
+                if faces == 0 {
+                    continue;
+                }
+
+                let occlusion = if self.ao.enabled {
+                    self.columns
+                        .occlusion(sweep.axis, sweep.dir, d, iv, word, faces)
+                } else {
+                    [0; 64]
+                };
+                let mut bits = faces;
+                while bits != 0 {
+                    let bit = bits.trailing_zeros() as usize;
+                    bits &= bits - 1;
+                    let iu = word * 64 + bit;
+                    let block_type = voxel_data[row_idx + iu * sweep.u_stride] & 0x7F;
+                    let base_key = if block_type == 0 {
+                        let (x, y, z) = sweep.local_coords(d, iu, iv);
+                        let selection_block_type = (get_selection_value(
+                            selection_data,
+                            x,
+                            y,
+                            z,
+                            selection_dim_x,
+                            selection_dim_y,
+                            selection_dim_z,
+                        ) & 0x7F)
+                            .max(1);
+                        self.face_key_base(
+                            block_atlas_mapping,
+                            selection_block_type,
+                            sweep.face_dir,
+                        ) & !face_key(0, 0, 0, 1, 0)
+                    } else {
+                        self.face_key_base(block_atlas_mapping, block_type, sweep.face_dir)
+                    };
+                    let is_selected = ((face_word.selected >> bit) & 1) as u8;
+                    let key = base_key | face_key(0, occlusion[bit], is_selected, 0, 0);
+                    self.columns.set_face_key(iu, iv, key);
+                }
+                self.columns.set_face_word(iv, word, faces);
+                has_faces = true;
             }
         }
+        has_faces
+    }
+
+    #[inline(always)]
+    fn face_key_base(
+        &mut self,
+        block_atlas_mapping: &[i32],
+        block_type: u8,
+        face_dir: usize,
+    ) -> u64 {
+        let cached = self.face_key_bases[block_type as usize];
+        if cached != u64::MAX {
+            return cached;
+        }
+        let key = face_key(
+            texture_index(block_atlas_mapping, block_type, face_dir) as i16,
+            0,
+            0,
+            self.materials.is_translucent(block_type) as u8,
+            if self.palette.is_some() {
+                block_type
+            } else {
+                0
+            },
+        );
+        self.face_key_bases[block_type as usize] = key;
+        key
+    }
+
+    #[inline(always)]
+    fn faces_sealed_cavity(
+        &self,
+        sweep: &SweepDirection,
+        slice: &SweepSlice,
+        iu: usize,
+        iv: usize,
+    ) -> bool {
+        if !self.exterior_only || !slice.neighbor_in_region {
+            return false;
+        }
+        let (x, y, z) = sweep.local_coords(slice.depth, iu, iv);
+        !self.exterior_fill.is_outside(
+            (x as i32 + sweep.dx) as usize,
+            (y as i32 + sweep.dy) as usize,
+            (z as i32 + sweep.dz) as usize,
+        )
+    }
+
+    #[inline(always)]
+    fn face_occlusion(
+        &self,
+        sweep: &SweepDirection,
+        slice: &SweepSlice,
+        iu: usize,
+        iv: usize,
+        voxel_data: &[u8],
+        neighbor_idx: i32,
+    ) -> u8 {
+        if !self.ao.enabled {
+            return 0;
+        }
+        let ao_nu = sweep.ao_u_origin + if sweep.ao_u_is_loop_u { iu as i32 } else { iv as i32 };
+        let ao_nv = sweep.ao_v_origin + if sweep.ao_v_is_loop_u { iu as i32 } else { iv as i32 };
+        calculate_ambient_occlusion(
+            slice.neighbor_coord,
+            ao_nu,
+            ao_nv,
+            sweep.ao_dim_n,
+            sweep.ao_dim_u,
+            sweep.ao_dim_v,
+            voxel_data,
+            neighbor_idx,
+            &sweep.ao_off,
+            &self.materials,
+        )
+    }
+
+    #[inline(always)]
+    fn merge_quad(&mut self, i: usize, j: usize, width: usize, height: usize) -> (usize, usize) {
+        let stride = self.max_dim;
+        let ji = j * stride + i;
+        let texture_index = self.mask[ji];
+        let is_selected = self.is_selected_mask[ji];
+        let ao_val = self.ao_mask[ji];
+        let is_translucent = self.translucent_mask[ji];
+        let block_type = self.block_type_mask[ji];
+        let split_block_types = self.palette.is_some();
+        let mut quad_width = 1usize;
+
+        while i + quad_width < width {
+            let idx = ji + quad_width;
+            if self.mask[idx] != texture_index
+                || self.ao_mask[idx] != ao_val
+                || self.is_selected_mask[idx] != is_selected
+                || self.translucent_mask[idx] != is_translucent
+                || (split_block_types && self.block_type_mask[idx] != block_type)
+            {
+                break;
+            }
+            quad_width += 1;
+        }
+
+        let mut quad_height = 1usize;
+        'outer: while j + quad_height < height {
+            let row_off = (j + quad_height) * stride;
+            for w in 0..quad_width {
+                let idx = row_off + i + w;
+                if self.mask[idx] != texture_index
+                    || self.ao_mask[idx] != ao_val
+                    || self.is_selected_mask[idx] != is_selected
+                    || self.translucent_mask[idx] != is_translucent
+                    || (split_block_types && self.block_type_mask[idx] != block_type)
+                {
+                    break 'outer;
+                }
+            }
+            quad_height += 1;
+        }
+
+        let end_i = i + quad_width;
+        let end_j = j + quad_height;
+        for jj in j..end_j {
+            let row_off = jj * stride;
+            for ii in i..end_i {
+                self.mask[row_off + ii] = -1;
+            }
+        }
+
+        (quad_width, quad_height)
     }
 
     fn generate_greedy_mesh(
@@ -263,8 +1069,6 @@ impl ExteriorFacesFinder {
         mesh_arrays: &mut MeshArrays,
     ) {
         let stride = self.max_dim;
-        let normal = FACES[face_dir].normal;
-        let face_offset: f32 = if dir > 0 { 1.0 } else { 0.0 };
 
         let mut j = 0;
         while j < height {
@@ -280,238 +1084,364 @@ impl ExteriorFacesFinder {
                 let texture_index = self.mask[ji];
                 let is_selected = self.is_selected_mask[ji];
                 let ao_val = self.ao_mask[ji];
-                let mut quad_width = 1usize;
-
-                while i + quad_width < width {
-                    let idx = j_offset + i + quad_width;
-                    if self.mask[idx] != texture_index
-                        || self.ao_mask[idx] != ao_val
-                        || self.is_selected_mask[idx] != is_selected
-                    {
+                let is_translucent = self.translucent_mask[ji];
+                let block_type = self.block_type_mask[ji];
+                let (quad_width, quad_height) = self.merge_quad(i, j, width, height);
+
+                self.emit_quad(
+                    i,
+                    j,
+                    quad_width,
+                    quad_height,
+                    depth,
+                    axis,
+                    u,
+                    v,
+                    dir,
+                    face_dir,
+                    texture_index,
+                    block_type,
+                    is_selected,
+                    ao_val,
+                    is_translucent,
+                    texture_width,
+                    mesh_arrays,
+                );
+
+                i += quad_width;
+            }
+            j += 1;
+        }
+    }
+
+    fn generate_greedy_mesh_from_bits(
+        &mut self,
+        sweep: &SweepDirection,
+        depth: usize,
+        texture_width: i32,
+        mesh_arrays: &mut MeshArrays,
+    ) {
+        for j in 0..sweep.v_size {
+            for word in 0..self.columns.face_words_per_row() {
+                loop {
+                    let bits = self.columns.face_bits(j, word);
+                    if bits == 0 {
                         break;
                     }
-                    quad_width += 1;
-                }
+                    let i = word * 64 + bits.trailing_zeros() as usize;
+                    let key = self.columns.face_key(i, j);
+                    let (quad_width, quad_height) = self.columns.take_quad(i, j);
 
-                let mut quad_height = 1usize;
-                'outer: while j + quad_height < height {
-                    let row_off = (j + quad_height) * stride;
-                    for w in 0..quad_width {
-                        let idx = row_off + i + w;
-                        if self.mask[idx] != texture_index
-                            || self.ao_mask[idx] != ao_val
-                            || self.is_selected_mask[idx] != is_selected
-                        {
-                            break 'outer;
-                        }
-                    }
-                    quad_height += 1;
+                    self.emit_quad(
+                        i,
+                        j,
+                        quad_width,
+                        quad_height,
+                        depth,
+                        sweep.axis,
+                        sweep.u,
+                        sweep.v,
+                        sweep.dir,
+                        sweep.face_dir,
+                        key as u16 as i16,
+                        (key >> 40) as u8,
+                        (key >> 24) as u8,
+                        (key >> 16) as u8,
+                        (key >> 32) as u8,
+                        texture_width,
+                        mesh_arrays,
+                    );
                 }
+            }
+        }
+    }
 
-                let end_i = i + quad_width;
-                let end_j = j + quad_height;
-                for jj in j..end_j {
-                    let row_off = jj * stride;
-                    for ii in i..end_i {
-                        self.mask[row_off + ii] = -1;
-                    }
-                }
+    #[inline(always)]
+    fn emit_quad(
+        &mut self,
+        i: usize,
+        j: usize,
+        quad_width: usize,
+        quad_height: usize,
+        depth: usize,
+        axis: usize,
+        u: usize,
+        v: usize,
+        dir: i32,
+        face_dir: usize,
+        texture_index: i16,
+        block_type: u8,
+        is_selected: u8,
+        ao_val: u8,
+        is_translucent: u8,
+        texture_width: i32,
+        mesh_arrays: &mut MeshArrays,
+    ) {
+        let normal = FACES[face_dir].normal;
+        let face_offset: f32 = if dir > 0 { 1.0 } else { 0.0 };
 
-                let x = if axis == 0 {
-                    depth
-                } else if u == 0 {
-                    i
-                } else if v == 0 {
-                    j
-                } else {
-                    0
-                };
-                let y = if axis == 1 {
-                    depth
-                } else if u == 1 {
-                    i
-                } else if v == 1 {
-                    j
-                } else {
-                    0
-                };
-                let z = if axis == 2 {
-                    depth
-                } else if u == 2 {
-                    i
-                } else if v == 2 {
-                    j
-                } else {
-                    0
-                };
+        let x = if axis == 0 {
+            depth
+        } else if u == 0 {
+            i
+        } else if v == 0 {
+            j
+        } else {
+            0
+        };
+        let y = if axis == 1 {
+            depth
+        } else if u == 1 {
+            i
+        } else if v == 1 {
+            j
+        } else {
+            0
+        };
+        let z = if axis == 2 {
+            depth
+        } else if u == 2 {
+            i
+        } else if v == 2 {
+            j
+        } else {
+            0
+        };
 
-                let texture_coords =
-                    get_texture_coordinates(texture_index as i32, texture_width);
-                let tex_u = texture_coords[0];
-                let tex_v = texture_coords[1];
+        let texture_coords = get_texture_coordinates(texture_index as i32, texture_width);
+        let tex_u = texture_coords[0];
+        let tex_v = texture_coords[1];
+        let tiled = self.uv_mode == UvMode::Tiled;
+        let tile_origin = get_tile_origin(texture_index as i32, texture_width);
+        let mut extent = [0.0f32; 3];
+        extent[u] = quad_width as f32;
+        extent[v] = quad_height as f32;
 
-                let start_vertex_index = mesh_arrays.vertex_count as u32;
+        let mesh_arrays: &mut MeshArrays = if is_translucent != 0 {
+            &mut self.translucent_arrays
+        } else {
+            &mut *mesh_arrays
+        };
+        let start_vertex_index = mesh_arrays.vertex_count as u32;
+        let mut corner_occlusion = [0u8; 4];
+        let quad_id = mesh_arrays.pick_quads.len() as u32;
+        if self.picking {
+            mesh_arrays.pick_quads.push(PickQuad::new(
+                [x, y, z],
+                axis,
+                u,
+                v,
+                quad_width,
+                quad_height,
+                face_dir,
+            ));
+        }
 
-                for vi in 0..4u32 {
-                    let actual_vi = if dir < 0 && (vi == 1 || vi == 3) {
-                        if vi == 1 { 3 } else { 1 }
-                    } else {
-                        vi
-                    };
+        for vi in 0..4u32 {
+            let actual_vi = if dir < 0 && (vi == 1 || vi == 3) {
+                if vi == 1 { 3 } else { 1 }
+            } else {
+                vi
+            };
 
-                    let (vx, vy, vz): (f32, f32, f32);
+            let (vx, vy, vz): (f32, f32, f32);
 
-                    if axis == 0 {
-                        vx = x as f32 + face_offset;
-                        match actual_vi {
-                            0 => {
-                                vy = y as f32;
-                                vz = z as f32;
-                            }
-                            1 => {
-                                vy = y as f32
-                                    + if u == 1 { quad_width as f32 } else { 0.0 };
-                                vz = z as f32
-                                    + if u == 2 { quad_width as f32 } else { 0.0 };
-                            }
-                            2 => {
-                                vy = y as f32
-                                    + if u == 1 {
-                                        quad_width as f32
-                                    } else if v == 1 {
-                                        quad_height as f32
-                                    } else {
-                                        0.0
-                                    };
-                                vz = z as f32
-                                    + if u == 2 {
-                                        quad_width as f32
-                                    } else if v == 2 {
-                                        quad_height as f32
-                                    } else {
-                                        0.0
-                                    };
-                            }
-                            _ => {
-                                vy = y as f32
-                                    + if v == 1 { quad_height as f32 } else { 0.0 };
-                                vz = z as f32
-                                    + if v == 2 { quad_height as f32 } else { 0.0 };
-                            }
-                        }
-                    } else if axis == 1 {
-                        vy = y as f32 + face_offset;
-                        match actual_vi {
-                            0 => {
-                                vx = x as f32;
-                                vz = z as f32;
-                            }
-                            1 => {
-                                vx = x as f32
-                                    + if u == 0 { quad_width as f32 } else { 0.0 };
-                                vz = z as f32
-                                    + if u == 2 { quad_width as f32 } else { 0.0 };
-                            }
-                            2 => {
-                                vx = x as f32
-                                    + if u == 0 {
-                                        quad_width as f32
-                                    } else if v == 0 {
-                                        quad_height as f32
-                                    } else {
-                                        0.0
-                                    };
-                                vz = z as f32
-                                    + if u == 2 {
-                                        quad_width as f32
-                                    } else if v == 2 {
-                                        quad_height as f32
-                                    } else {
-                                        0.0
-                                    };
-                            }
-                            _ => {
-                                vx = x as f32
-                                    + if v == 0 { quad_height as f32 } else { 0.0 };
-                                vz = z as f32
-                                    + if v == 2 { quad_height as f32 } else { 0.0 };
-                            }
-                        }
-                    } else {
-                        vz = z as f32 + face_offset;
-                        match actual_vi {
-                            0 => {
-                                vx = x as f32;
-                                vy = y as f32;
-                            }
-                            1 => {
-                                vx = x as f32
-                                    + if u == 0 { quad_width as f32 } else { 0.0 };
-                                vy = y as f32
-                                    + if u == 1 { quad_width as f32 } else { 0.0 };
-                            }
-                            2 => {
-                                vx = x as f32
-                                    + if u == 0 {
-                                        quad_width as f32
-                                    } else if v == 0 {
-                                        quad_height as f32
-                                    } else {
-                                        0.0
-                                    };
-                                vy = y as f32
-                                    + if u == 1 {
-                                        quad_width as f32
-                                    } else if v == 1 {
-                                        quad_height as f32
-                                    } else {
-                                        0.0
-                                    };
-                            }
-                            _ => {
-                                vx = x as f32
-                                    + if v == 0 { quad_height as f32 } else { 0.0 };
-                                vy = y as f32
-                                    + if v == 1 { quad_height as f32 } else { 0.0 };
-                            }
-                        }
+            if axis == 0 {
+                vx = x as f32 + face_offset;
+                match actual_vi {
+                    0 => {
+                        vy = y as f32;
+                        vz = z as f32;
                     }
+                    1 => {
+                        vy = y as f32 + if u == 1 { quad_width as f32 } else { 0.0 };
+                        vz = z as f32 + if u == 2 { quad_width as f32 } else { 0.0 };
+                    }
+                    2 => {
+                        vy = y as f32
+                            + if u == 1 {
+                                quad_width as f32
+                            } else if v == 1 {
+                                quad_height as f32
+                            } else {
+                                0.0
+                            };
+                        vz = z as f32
+                            + if u == 2 {
+                                quad_width as f32
+                            } else if v == 2 {
+                                quad_height as f32
+                            } else {
+                                0.0
+                            };
+                    }
+                    _ => {
+                        vy = y as f32 + if v == 1 { quad_height as f32 } else { 0.0 };
+                        vz = z as f32 + if v == 2 { quad_height as f32 } else { 0.0 };
+                    }
+                }
+            } else if axis == 1 {
+                vy = y as f32 + face_offset;
+                match actual_vi {
+                    0 => {
+                        vx = x as f32;
+                        vz = z as f32;
+                    }
+                    1 => {
+                        vx = x as f32 + if u == 0 { quad_width as f32 } else { 0.0 };
+                        vz = z as f32 + if u == 2 { quad_width as f32 } else { 0.0 };
+                    }
+                    2 => {
+                        vx = x as f32
+                            + if u == 0 {
+                                quad_width as f32
+                            } else if v == 0 {
+                                quad_height as f32
+                            } else {
+                                0.0
+                            };
+                        vz = z as f32
+                            + if u == 2 {
+                                quad_width as f32
+                            } else if v == 2 {
+                                quad_height as f32
+                            } else {
+                                0.0
+                            };
+                    }
+                    _ => {
+                        vx = x as f32 + if v == 0 { quad_height as f32 } else { 0.0 };
+                        vz = z as f32 + if v == 2 { quad_height as f32 } else { 0.0 };
+                    }
+                }
+            } else {
+                vz = z as f32 + face_offset;
+                match actual_vi {
+                    0 => {
+                        vx = x as f32;
+                        vy = y as f32;
+                    }
+                    1 => {
+                        vx = x as f32 + if u == 0 { quad_width as f32 } else { 0.0 };
+                        vy = y as f32 + if u == 1 { quad_width as f32 } else { 0.0 };
+                    }
+                    2 => {
+                        vx = x as f32
+                            + if u == 0 {
+                                quad_width as f32
+                            } else if v == 0 {
+                                quad_height as f32
+                            } else {
+                                0.0
+                            };
+                        vy = y as f32
+                            + if u == 1 {
+                                quad_width as f32
+                            } else if v == 1 {
+                                quad_height as f32
+                            } else {
+                                0.0
+                            };
+                    }
+                    _ => {
+                        vx = x as f32 + if v == 0 { quad_height as f32 } else { 0.0 };
+                        vy = y as f32 + if v == 1 { quad_height as f32 } else { 0.0 };
+                    }
+                }
+            }
 
-                    mesh_arrays.push_vertex(vx, vy, vz);
-                    mesh_arrays.push_normal(normal[0], normal[1], normal[2]);
-                    mesh_arrays.push_uv(tex_u, tex_v);
-
-                    let ao_corner_index =
-                        if face_dir == 1 || face_dir == 2 || face_dir == 5 {
-                            if vi == 1 { 3 } else if vi == 3 { 1 } else { vi }
-                        } else {
-                            vi
-                        };
-
-                    let occlusion_count =
-                        (ao_val >> (ao_corner_index * 2)) & 0x03;
-                    let ao_factor = OCCLUSION_LEVELS[occlusion_count as usize];
-                    mesh_arrays.push_ao(ao_factor);
-                    mesh_arrays.push_is_selected(is_selected);
-                    mesh_arrays.increment_vertex();
+            let ao_corner_index = if face_dir == 1 || face_dir == 2 || face_dir == 5 {
+                if vi == 1 {
+                    3
+                } else if vi == 3 {
+                    1
+                } else {
+                    vi
                 }
+            } else {
+                vi
+            };
 
-                mesh_arrays.push_index(start_vertex_index);
-                mesh_arrays.push_index(start_vertex_index + 1);
-                mesh_arrays.push_index(start_vertex_index + 2);
-                mesh_arrays.push_index(start_vertex_index);
-                mesh_arrays.push_index(start_vertex_index + 2);
-                mesh_arrays.push_index(start_vertex_index + 3);
+            let occlusion_count = (ao_val >> (ao_corner_index * 2)) & 0x03;
+            corner_occlusion[vi as usize] = occlusion_count;
 
-                i += quad_width;
+            if mesh_arrays.is_packed() {
+                mesh_arrays.push_packed(pack_vertex(
+                    vx as u32,
+                    vy as u32,
+                    vz as u32,
+                    face_dir,
+                    texture_index as i32,
+                    occlusion_count,
+                    is_selected,
+                ));
+            } else {
+                mesh_arrays.push_vertex(vx, vy, vz);
+                mesh_arrays.push_normal(normal[0], normal[1], normal[2]);
+                if tiled {
+                    let local = [vx - x as f32, vy - y as f32, vz - z as f32];
+                    let [tile_u, tile_v] = get_tiled_uv(face_dir, local, extent);
+                    mesh_arrays.push_uv(tile_u, tile_v);
+                    mesh_arrays.push_tile_origin(tile_origin[0], tile_origin[1]);
+                } else {
+                    mesh_arrays.push_uv(tex_u, tex_v);
+                }
+                let ao_level = self.ao.level(occlusion_count);
+                if let Some(palette) = &self.palette {
+                    mesh_arrays.push_color(palette.color(block_type, ao_level));
+                }
+                mesh_arrays.push_ao(ao_level);
+                mesh_arrays.push_is_selected(is_selected);
             }
-            j += 1;
+            if self.picking {
+                mesh_arrays.push_quad_id(quad_id);
+            }
+            mesh_arrays.increment_vertex();
+        }
+
+        let quad_indices = if flip_quad_diagonal(&self.ao, corner_occlusion) {
+            &FLIPPED_QUAD_INDICES
+        } else {
+            &QUAD_INDICES
+        };
+        for &index in quad_indices {
+            mesh_arrays.push_index(start_vertex_index + index);
         }
     }
 }
 
 #[inline(always)]
-fn is_selection_set(
+fn texture_index(block_atlas_mapping: &[i32], block_type: u8, face_dir: usize) -> i32 {
+    if block_atlas_mapping.is_empty() {
+        0
+    } else {
+        get_atlas_index(block_atlas_mapping, block_type, face_dir)
+    }
+}
+
+#[inline(always)]
+fn face_key(
+    texture_index: i16,
+    ao: u8,
+    is_selected: u8,
+    is_translucent: u8,
+    block_type: u8,
+) -> u64 {
+    (texture_index as u16 as u64)
+        | (ao as u64) << 16
+        | (is_selected as u64) << 24
+        | (is_translucent as u64) << 32
+        | (block_type as u64) << 40
+}
+
+#[inline(always)]
+fn flip_quad_diagonal(ao: &AoConfig, corner_occlusion: [u8; 4]) -> bool {
+    let level = |vertex: usize| ao.level(corner_occlusion[vertex]);
+    level(0) + level(2) < level(1) + level(3)
+}
+
+#[inline(always)]
+pub(crate) fn is_selection_set(
     selection_data: &[u8],
     x: usize,
     y: usize,
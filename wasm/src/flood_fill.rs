@@ -0,0 +1,400 @@
+use std::collections::VecDeque;
+use std::fmt;
+
+use crate::validation::{validate_selection_data, validate_voxel_data, MeshingError};
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum FloodFillError {
+    InvalidVolume(MeshingError),
+    InvalidConnectivity { value: u8 },
+    InvalidMatchMode { value: u8 },
+}
+
+impl fmt::Display for FloodFillError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            FloodFillError::InvalidVolume(error) => error.fmt(f),
+            FloodFillError::InvalidConnectivity { value } => {
+                write!(f, "flood fill connectivity must be 6 or 26, got {value}")
+            }
+            FloodFillError::InvalidMatchMode { value } => {
+                write!(f, "{value} is not a valid flood fill match mode")
+            }
+        }
+    }
+}
+
+impl std::error::Error for FloodFillError {}
+
+impl From<MeshingError> for FloodFillError {
+    fn from(error: MeshingError) -> Self {
+        FloodFillError::InvalidVolume(error)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum Connectivity {
+    #[default]
+    Faces,
+    Full,
+}
+
+impl TryFrom<u8> for Connectivity {
+    type Error = FloodFillError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            6 => Ok(Connectivity::Faces),
+            26 => Ok(Connectivity::Full),
+            _ => Err(FloodFillError::InvalidConnectivity { value }),
+        }
+    }
+}
+
+impl Connectivity {
+    fn offsets(self) -> Vec<[i64; 3]> {
+        let mut offsets = Vec::with_capacity(26);
+        for dx in -1..=1i64 {
+            for dy in -1..=1i64 {
+                for dz in -1..=1i64 {
+                    let manhattan = dx.abs() + dy.abs() + dz.abs();
+                    let keep = match self {
+                        Connectivity::Faces => manhattan == 1,
+                        Connectivity::Full => manhattan > 0,
+                    };
+                    if keep {
+                        offsets.push([dx, dy, dz]);
+                    }
+                }
+            }
+        }
+        offsets
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum FloodMatch {
+    #[default]
+    SameBlockType,
+    AnySolid,
+    EmptySpace,
+}
+
+impl TryFrom<u8> for FloodMatch {
+    type Error = FloodFillError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(FloodMatch::SameBlockType),
+            1 => Ok(FloodMatch::AnySolid),
+            2 => Ok(FloodMatch::EmptySpace),
+            _ => Err(FloodFillError::InvalidMatchMode { value }),
+        }
+    }
+}
+
+impl FloodMatch {
+    #[inline(always)]
+    fn matches(self, seed_type: u8, block_value: u8) -> bool {
+        let block_type = block_value & 0x7F;
+        match self {
+            FloodMatch::SameBlockType => block_type == seed_type,
+            FloodMatch::AnySolid => block_type != 0,
+            FloodMatch::EmptySpace => block_type == 0,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub struct FloodFillResult {
+    pub filled: usize,
+    pub truncated: bool,
+}
+
+pub fn try_flood_fill(
+    voxel_data: &[u8],
+    dims: [usize; 3],
+    seed: [usize; 3],
+    connectivity: Connectivity,
+    match_mode: FloodMatch,
+    max_voxels: usize,
+    selection_data: &mut [u8],
+) -> Result<FloodFillResult, FloodFillError> {
+    validate_voxel_data(voxel_data, dims)?;
+    validate_selection_data(selection_data, dims)?;
+    Ok(flood_fill(
+        voxel_data,
+        dims,
+        seed,
+        connectivity,
+        match_mode,
+        max_voxels,
+        selection_data,
+    ))
+}
+
+pub fn flood_fill(
+    voxel_data: &[u8],
+    dims: [usize; 3],
+    seed: [usize; 3],
+    connectivity: Connectivity,
+    match_mode: FloodMatch,
+    max_voxels: usize,
+    selection_data: &mut [u8],
+) -> FloodFillResult {
+    let [dim_x, dim_y, dim_z] = dims;
+    let total = dim_x * dim_y * dim_z;
+    selection_data[..total].fill(0);
+
+    let mut result = FloodFillResult::default();
+    if seed[0] >= dim_x || seed[1] >= dim_y || seed[2] >= dim_z {
+        return result;
+    }
+    let index = |p: [usize; 3]| p[0] * dim_y * dim_z + p[1] * dim_z + p[2];
+    let seed_type = voxel_data[index(seed)] & 0x7F;
+    if !match_mode.matches(seed_type, voxel_data[index(seed)]) || max_voxels == 0 {
+        return result;
+    }
+
+    let offsets = connectivity.offsets();
+    let mut queue = VecDeque::new();
+    selection_data[index(seed)] = 1;
+    result.filled = 1;
+    queue.push_back(seed);
+
+    while let Some(cell) = queue.pop_front() {
+        for offset in &offsets {
+            let neighbor: [i64; 3] = std::array::from_fn(|a| cell[a] as i64 + offset[a]);
+            if (0..3).any(|a| neighbor[a] < 0 || neighbor[a] >= dims[a] as i64) {
+                continue;
+            }
+            let neighbor = neighbor.map(|c| c as usize);
+            let idx = index(neighbor);
+            if selection_data[idx] != 0 || !match_mode.matches(seed_type, voxel_data[idx]) {
+                continue;
+            }
+            if result.filled == max_voxels {
+                result.truncated = true;
+                return result;
+            }
+            selection_data[idx] = 1;
+            result.filled += 1;
+            queue.push_back(neighbor);
+        }
+    }
+
+    result
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::find_exterior_faces::is_selection_set;
+
+    const DIMS: [usize; 3] = [4, 4, 4];
+
+    fn grid(cells: &[([usize; 3], u8)]) -> Vec<u8> {
+        let mut data = vec![0u8; DIMS[0] * DIMS[1] * DIMS[2]];
+        for &(cell, value) in cells {
+            data[cell[0] * DIMS[1] * DIMS[2] + cell[1] * DIMS[2] + cell[2]] = value;
+        }
+        data
+    }
+
+    fn fill(
+        data: &[u8],
+        seed: [usize; 3],
+        connectivity: Connectivity,
+        match_mode: FloodMatch,
+        max_voxels: usize,
+    ) -> (FloodFillResult, Vec<u8>) {
+        let mut selection = vec![7u8; data.len()];
+        let result = flood_fill(
+            data,
+            DIMS,
+            seed,
+            connectivity,
+            match_mode,
+            max_voxels,
+            &mut selection,
+        );
+        (result, selection)
+    }
+
+    fn selected(selection: &[u8], p: [usize; 3]) -> bool {
+        is_selection_set(selection, p[0], p[1], p[2], DIMS[0], DIMS[1], DIMS[2])
+    }
+
+    #[test]
+    fn diagonal_neighbors_join_only_with_full_connectivity() {
+        let data = grid(&[
+            ([0, 0, 0], 2),
+            ([1, 1, 0], 2),
+            ([2, 2, 2], 2),
+            ([1, 0, 0], 3),
+        ]);
+        let (result, selection) = fill(
+            &data,
+            [0, 0, 0],
+            Connectivity::Faces,
+            FloodMatch::SameBlockType,
+            64,
+        );
+        assert_eq!(
+            result,
+            FloodFillResult {
+                filled: 1,
+                truncated: false
+            }
+        );
+        assert!(selected(&selection, [0, 0, 0]));
+        assert!(!selected(&selection, [1, 1, 0]));
+
+        let (result, selection) = fill(
+            &data,
+            [0, 0, 0],
+            Connectivity::Full,
+            FloodMatch::SameBlockType,
+            64,
+        );
+        assert_eq!(result.filled, 2);
+        assert!(selected(&selection, [1, 1, 0]));
+        assert!(!selected(&selection, [2, 2, 2]));
+        assert!(!selected(&selection, [1, 0, 0]));
+
+        let (result, selection) = fill(
+            &data,
+            [0, 0, 0],
+            Connectivity::Faces,
+            FloodMatch::AnySolid,
+            64,
+        );
+        assert_eq!(result.filled, 3);
+        assert!(selected(&selection, [1, 0, 0]));
+    }
+
+    #[test]
+    fn block_type_ignores_raycastable_bit() {
+        let data = grid(&[([0, 0, 0], 0x82), ([0, 0, 1], 2), ([0, 0, 2], 0x83)]);
+        let (result, _) = fill(
+            &data,
+            [0, 0, 1],
+            Connectivity::Faces,
+            FloodMatch::SameBlockType,
+            64,
+        );
+        assert_eq!(result.filled, 2);
+    }
+
+    #[test]
+    fn empty_space_is_bounded_by_solids() {
+        let mut cells = Vec::new();
+        for x in 0..4 {
+            for z in 0..4 {
+                cells.push(([x, 1, z], 1));
+            }
+        }
+        let data = grid(&cells);
+        let (result, selection) = fill(
+            &data,
+            [2, 0, 3],
+            Connectivity::Full,
+            FloodMatch::EmptySpace,
+            64,
+        );
+        assert_eq!(result.filled, 16);
+        assert!(selected(&selection, [0, 0, 0]));
+        assert!(!selected(&selection, [0, 1, 0]));
+        assert!(!selected(&selection, [0, 2, 0]));
+
+        let (result, selection) = fill(
+            &data,
+            [0, 1, 0],
+            Connectivity::Faces,
+            FloodMatch::EmptySpace,
+            64,
+        );
+        assert_eq!(result.filled, 0);
+        assert!(selection.iter().all(|&s| s == 0));
+    }
+
+    #[test]
+    fn cap_stops_runaway_fills() {
+        let data = grid(&[]);
+        let (result, selection) = fill(
+            &data,
+            [1, 1, 1],
+            Connectivity::Faces,
+            FloodMatch::EmptySpace,
+            10,
+        );
+        assert_eq!(
+            result,
+            FloodFillResult {
+                filled: 10,
+                truncated: true
+            }
+        );
+        assert_eq!(selection.iter().filter(|&&s| s != 0).count(), 10);
+
+        let (result, _) = fill(
+            &data,
+            [1, 1, 1],
+            Connectivity::Faces,
+            FloodMatch::EmptySpace,
+            64,
+        );
+        assert_eq!(
+            result,
+            FloodFillResult {
+                filled: 64,
+                truncated: false
+            }
+        );
+
+        let (result, _) = fill(
+            &data,
+            [4, 0, 0],
+            Connectivity::Faces,
+            FloodMatch::EmptySpace,
+            64,
+        );
+        assert_eq!(result.filled, 0);
+    }
+
+    #[test]
+    fn rejects_unknown_connectivity_and_match_values() {
+        assert_eq!(Connectivity::try_from(26), Ok(Connectivity::Full));
+        assert_eq!(
+            Connectivity::try_from(18),
+            Err(FloodFillError::InvalidConnectivity { value: 18 })
+        );
+        assert_eq!(FloodMatch::try_from(2), Ok(FloodMatch::EmptySpace));
+        assert_eq!(
+            FloodMatch::try_from(3),
+            Err(FloodFillError::InvalidMatchMode { value: 3 })
+        );
+    }
+
+    #[test]
+    fn rejects_short_selection_buffer() {
+        let data = grid(&[]);
+        assert_eq!(
+            try_flood_fill(
+                &data,
+                DIMS,
+                [0; 3],
+                Connectivity::Faces,
+                FloodMatch::EmptySpace,
+                8,
+                &mut [0; 8]
+            ),
+            Err(FloodFillError::InvalidVolume(
+                MeshingError::SelectionDataLength {
+                    expected: 64,
+                    actual: 8
+                }
+            ))
+        );
+    }
+}
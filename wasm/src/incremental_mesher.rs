@@ -0,0 +1,515 @@
+use crate::find_exterior_faces::ExteriorFacesFinder;
+use crate::mesh_arrays::MeshArrays;
+use crate::validation::MeshingError;
+
+pub struct IncrementalMesher {
+    finder: ExteriorFacesFinder,
+    scratch: MeshArrays,
+    mesh_arrays: MeshArrays,
+    slices: Vec<MeshArrays>,
+    slice_offsets: Vec<(usize, usize)>,
+    slice_bases: [usize; 6],
+    dims: [usize; 3],
+    stale: bool,
+}
+
+impl IncrementalMesher {
+    pub fn new(max_dimension: usize) -> Self {
+        let mut finder = ExteriorFacesFinder::new(max_dimension);
+        finder.set_record_slices(true);
+        Self {
+            finder,
+            scratch: MeshArrays::new(0, 0),
+            mesh_arrays: MeshArrays::new(0, 0),
+            slices: Vec::new(),
+            slice_offsets: Vec::new(),
+            slice_bases: [0; 6],
+            dims: [0; 3],
+            stale: true,
+        }
+    }
+
+    pub fn finder_mut(&mut self) -> &mut ExteriorFacesFinder {
+        self.stale = true;
+        &mut self.finder
+    }
+
+    pub fn mesh_arrays(&self) -> &MeshArrays {
+        &self.mesh_arrays
+    }
+
+    pub fn remesh_full(
+        &mut self,
+        voxel_data: &[u8],
+        texture_width: i32,
+        block_atlas_mapping: &[i32],
+        dim_x: usize,
+        dim_y: usize,
+        dim_z: usize,
+        selection_data: &[u8],
+        selection_dim_x: usize,
+        selection_dim_y: usize,
+        selection_dim_z: usize,
+        selection_empty: bool,
+    ) {
+        self.stale = false;
+        self.dims = [dim_x, dim_y, dim_z];
+        let mut base = 0;
+        for (position, slice_base) in self.slice_bases.iter_mut().enumerate() {
+            *slice_base = base;
+            base += self.dims[position / 2];
+        }
+        self.slices.resize_with(base, || MeshArrays::new(0, 0));
+        self.slice_offsets.resize(base, (0, 0));
+
+        self.remesh_slices(
+            voxel_data,
+            texture_width,
+            block_atlas_mapping,
+            [(0, dim_x), (0, dim_y), (0, dim_z)],
+            selection_data,
+            selection_dim_x,
+            selection_dim_y,
+            selection_dim_z,
+            selection_empty,
+        );
+    }
+
+    pub fn try_remesh_full(
+        &mut self,
+        voxel_data: &[u8],
+        texture_width: i32,
+        block_atlas_mapping: &[i32],
+        dim_x: usize,
+        dim_y: usize,
+        dim_z: usize,
+        selection_data: &[u8],
+        selection_dim_x: usize,
+        selection_dim_y: usize,
+        selection_dim_z: usize,
+        selection_empty: bool,
+    ) -> Result<(), MeshingError> {
+        let dims = [dim_x, dim_y, dim_z];
+        let texture_width = self.finder.validate_input(
+            voxel_data,
+            texture_width,
+            block_atlas_mapping,
+            dims,
+            dims,
+            &self.mesh_arrays,
+            selection_data,
+            selection_dim_x,
+            selection_dim_y,
+            selection_dim_z,
+            selection_empty,
+        )?;
+        self.remesh_full(
+            voxel_data,
+            texture_width,
+            block_atlas_mapping,
+            dim_x,
+            dim_y,
+            dim_z,
+            selection_data,
+            selection_dim_x,
+            selection_dim_y,
+            selection_dim_z,
+            selection_empty,
+        );
+        Ok(())
+    }
+
+    pub fn remesh_region(
+        &mut self,
+        voxel_data: &[u8],
+        texture_width: i32,
+        block_atlas_mapping: &[i32],
+        dim_x: usize,
+        dim_y: usize,
+        dim_z: usize,
+        min: [usize; 3],
+        max: [usize; 3],
+        selection_data: &[u8],
+        selection_dim_x: usize,
+        selection_dim_y: usize,
+        selection_dim_z: usize,
+        selection_empty: bool,
+    ) {
+        if self.stale
+            || self.slices.is_empty()
+            || self.dims != [dim_x, dim_y, dim_z]
+            || self.finder.exterior_only()
+        {
+            self.remesh_full(
+                voxel_data,
+                texture_width,
+                block_atlas_mapping,
+                dim_x,
+                dim_y,
+                dim_z,
+                selection_data,
+                selection_dim_x,
+                selection_dim_y,
+                selection_dim_z,
+                selection_empty,
+            );
+            return;
+        }
+
+        let mut depth_ranges = [(0, 0); 3];
+        for axis in 0..3 {
+            let start = min[axis].saturating_sub(1).min(self.dims[axis]);
+            let end = max[axis].saturating_add(2).min(self.dims[axis]);
+            depth_ranges[axis] = (start, end.max(start));
+        }
+
+        self.remesh_slices(
+            voxel_data,
+            texture_width,
+            block_atlas_mapping,
+            depth_ranges,
+            selection_data,
+            selection_dim_x,
+            selection_dim_y,
+            selection_dim_z,
+            selection_empty,
+        );
+    }
+
+    pub fn try_remesh_region(
+        &mut self,
+        voxel_data: &[u8],
+        texture_width: i32,
+        block_atlas_mapping: &[i32],
+        dim_x: usize,
+        dim_y: usize,
+        dim_z: usize,
+        min: [usize; 3],
+        max: [usize; 3],
+        selection_data: &[u8],
+        selection_dim_x: usize,
+        selection_dim_y: usize,
+        selection_dim_z: usize,
+        selection_empty: bool,
+    ) -> Result<(), MeshingError> {
+        let dims = [dim_x, dim_y, dim_z];
+        let texture_width = self.finder.validate_input(
+            voxel_data,
+            texture_width,
+            block_atlas_mapping,
+            dims,
+            dims,
+            &self.mesh_arrays,
+            selection_data,
+            selection_dim_x,
+            selection_dim_y,
+            selection_dim_z,
+            selection_empty,
+        )?;
+        if (0..3).any(|axis| min[axis] > max[axis] || max[axis] >= dims[axis]) {
+            return Err(MeshingError::InvalidRegion { min, max });
+        }
+        self.remesh_region(
+            voxel_data,
+            texture_width,
+            block_atlas_mapping,
+            dim_x,
+            dim_y,
+            dim_z,
+            min,
+            max,
+            selection_data,
+            selection_dim_x,
+            selection_dim_y,
+            selection_dim_z,
+            selection_empty,
+        );
+        Ok(())
+    }
+
+    fn remesh_slices(
+        &mut self,
+        voxel_data: &[u8],
+        texture_width: i32,
+        block_atlas_mapping: &[i32],
+        depth_ranges: [(usize, usize); 3],
+        selection_data: &[u8],
+        selection_dim_x: usize,
+        selection_dim_y: usize,
+        selection_dim_z: usize,
+        selection_empty: bool,
+    ) {
+        let dims = self.dims;
+        self.finder.find_faces_in_region(
+            voxel_data,
+            texture_width,
+            block_atlas_mapping,
+            dims,
+            [0, 0, 0],
+            dims,
+            depth_ranges,
+            &mut self.scratch,
+            selection_data,
+            selection_dim_x,
+            selection_dim_y,
+            selection_dim_z,
+            selection_empty,
+        );
+
+        let scratch = &self.scratch;
+        let ranges = self.finder.slice_ranges();
+        let mut first_dirty = self.slices.len();
+        for (k, range) in ranges.iter().enumerate() {
+            let (vertex_end, index_end, translucent_vertex_end, translucent_index_end) =
+                match ranges.get(k + 1) {
+                    Some(next) => (
+                        next.vertex_start,
+                        next.index_start,
+                        next.translucent_vertex_start,
+                        next.translucent_index_start,
+                    ),
+                    None => (
+                        scratch.translucent_vertex_start,
+                        scratch.translucent_index_start,
+                        scratch.vertex_count - scratch.translucent_vertex_start,
+                        scratch.index_count - scratch.translucent_index_start,
+                    ),
+                };
+            let translucent_vertices = scratch.translucent_vertex_start + range.translucent_vertex_start
+                ..scratch.translucent_vertex_start + translucent_vertex_end;
+            let translucent_indices = scratch.translucent_index_start + range.translucent_index_start
+                ..scratch.translucent_index_start + translucent_index_end;
+
+            let slice_index = slice_index(&self.slice_bases, range.face_dir, range.depth);
+            first_dirty = first_dirty.min(slice_index);
+
+            let slice = &mut self.slices[slice_index];
+            slice.reset();
+            slice.append_range(
+                scratch,
+                range.vertex_start..vertex_end,
+                range.index_start..index_end,
+            );
+            slice.translucent_vertex_start = slice.vertex_count;
+            slice.translucent_index_start = slice.index_count;
+            slice.append_range(scratch, translucent_vertices, translucent_indices);
+        }
+
+        self.splice_from(first_dirty);
+    }
+
+    fn splice_from(&mut self, first_dirty: usize) {
+        let (vertex_count, index_count) = self.slice_offsets.get(first_dirty).copied().unwrap_or((
+            self.mesh_arrays.translucent_vertex_start,
+            self.mesh_arrays.translucent_index_start,
+        ));
+
+        self.mesh_arrays.truncate(vertex_count, index_count);
+        for (slice, offset) in self.slices[first_dirty..]
+            .iter()
+            .zip(&mut self.slice_offsets[first_dirty..])
+        {
+            *offset = (self.mesh_arrays.vertex_count, self.mesh_arrays.index_count);
+            self.mesh_arrays.append_range(
+                slice,
+                0..slice.translucent_vertex_start,
+                0..slice.translucent_index_start,
+            );
+        }
+
+        self.mesh_arrays.translucent_vertex_start = self.mesh_arrays.vertex_count;
+        self.mesh_arrays.translucent_index_start = self.mesh_arrays.index_count;
+        let opaque_end = self.mesh_arrays.index_count;
+        for (position, &base) in self.slice_bases.iter().enumerate() {
+            let face_dir = sweep_face_dir(position);
+            let end = base + self.dims[position / 2];
+            let index_offset = |slice: usize| self.slice_offsets.get(slice).map_or(opaque_end, |o| o.1);
+            self.mesh_arrays.face_index_ranges[face_dir] = (index_offset(base), index_offset(end));
+
+            let translucent_start = self.mesh_arrays.index_count;
+            for slice in &self.slices[base..end] {
+                self.mesh_arrays.append_range(
+                    slice,
+                    slice.translucent_vertex_start..slice.vertex_count,
+                    slice.translucent_index_start..slice.index_count,
+                );
+            }
+            self.mesh_arrays.translucent_face_index_ranges[face_dir] =
+                (translucent_start, self.mesh_arrays.index_count);
+        }
+    }
+}
+
+fn sweep_face_dir(sweep_position: usize) -> usize {
+    let axis = sweep_position / 2;
+    axis * 2 + if sweep_position % 2 == 1 { 0 } else { 1 }
+}
+
+fn slice_index(slice_bases: &[usize; 6], face_dir: usize, depth: usize) -> usize {
+    let axis = face_dir / 2;
+    let sweep_position = axis * 2 + if face_dir % 2 == 1 { 0 } else { 1 };
+    slice_bases[sweep_position] + depth
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::test_support::assert_same_mesh;
+    use crate::texture_coords::expand_block_atlas_mapping;
+
+    fn mesh_full(
+        voxel_data: &[u8],
+        dims: [usize; 3],
+        block_classes: &[u8],
+        mapping: &[i32],
+    ) -> MeshArrays {
+        let mut finder = ExteriorFacesFinder::new(dims[0].max(dims[1]).max(dims[2]));
+        finder.set_block_materials(block_classes).unwrap();
+        finder.set_picking(true);
+        let max_faces = dims[0] * dims[1] * dims[2] * 6;
+        let mut mesh_arrays = MeshArrays::new(max_faces * 4, max_faces * 6);
+        finder.find_exterior_faces(
+            voxel_data,
+            4,
+            mapping,
+            dims[0],
+            dims[1],
+            dims[2],
+            &mut mesh_arrays,
+            &[],
+            0,
+            0,
+            0,
+            true,
+        );
+        mesh_arrays
+    }
+
+    #[test]
+    fn region_remesh_matches_full_remesh() {
+        let dims = [6, 5, 4];
+        let block_classes = [0, 2];
+        let mapping = expand_block_atlas_mapping(&[0, 1]);
+        let mut data = vec![0u8; dims[0] * dims[1] * dims[2]];
+        for (i, value) in data.iter_mut().enumerate() {
+            if i % 3 != 0 {
+                *value = if i % 7 == 0 { 2 } else { 1 };
+            }
+        }
+
+        let mut mesher = IncrementalMesher::new(6);
+        mesher.finder_mut().set_block_materials(&block_classes).unwrap();
+        mesher.finder_mut().set_picking(true);
+        mesher.remesh_full(&data, 4, &mapping, dims[0], dims[1], dims[2], &[], 0, 0, 0, true);
+        assert_same_mesh(mesher.mesh_arrays(), &mesh_full(&data, dims, &block_classes, &mapping));
+
+        let edits = [([2, 2, 2], 0u8), ([0, 0, 0], 2), ([5, 4, 3], 1), ([3, 1, 0], 0)];
+        for (pos, value) in edits {
+            data[pos[0] * dims[1] * dims[2] + pos[1] * dims[2] + pos[2]] = value;
+            mesher.remesh_region(
+                &data, 4, &mapping, dims[0], dims[1], dims[2], pos, pos, &[], 0, 0, 0, true,
+            );
+            assert_same_mesh(
+                mesher.mesh_arrays(),
+                &mesh_full(&data, dims, &block_classes, &mapping),
+            );
+        }
+    }
+
+    #[test]
+    fn dimension_change_triggers_full_remesh() {
+        let mapping = expand_block_atlas_mapping(&[0]);
+        let mut mesher = IncrementalMesher::new(2);
+        mesher.finder_mut().set_picking(true);
+        mesher.remesh_full(&[1], 4, &mapping, 1, 1, 1, &[], 0, 0, 0, true);
+        assert_eq!(mesher.mesh_arrays().index_count, 36);
+
+        mesher.remesh_region(
+            &[1, 0],
+            4,
+            &mapping,
+            2,
+            1,
+            1,
+            [1, 0, 0],
+            [1, 0, 0],
+            &[],
+            0,
+            0,
+            0,
+            true,
+        );
+        assert_same_mesh(mesher.mesh_arrays(), &mesh_full(&[1, 0], [2, 1, 1], &[], &mapping));
+    }
+    #[test]
+    fn finder_changes_force_full_remesh() {
+        let dims = [3, 2, 2];
+        let mapping = expand_block_atlas_mapping(&[0]);
+        let data = vec![1u8; 12];
+        let mut mesher = IncrementalMesher::new(3);
+        mesher.finder_mut().set_picking(true);
+        mesher.remesh_full(&data, 4, &mapping, dims[0], dims[1], dims[2], &[], 0, 0, 0, true);
+
+        mesher.finder_mut().set_block_materials(&[2]).unwrap();
+        mesher.remesh_region(
+            &data, 4, &mapping, dims[0], dims[1], dims[2], [0; 3], [0; 3], &[], 0, 0, 0, true,
+        );
+        let expected = mesh_full(&data, dims, &[2], &mapping);
+        assert_eq!(expected.translucent_index_start, 0);
+        assert_same_mesh(mesher.mesh_arrays(), &expected);
+    }
+
+    #[test]
+    fn region_remesh_rejects_invalid_arguments() {
+        let mapping = expand_block_atlas_mapping(&[0]);
+        let data = vec![1u8; 8];
+        let mut mesher = IncrementalMesher::new(2);
+        let mut remesh = |data: &[u8], min: [usize; 3], max: [usize; 3], selection: &[u8]| {
+            mesher.try_remesh_region(
+                data,
+                4,
+                &mapping,
+                2,
+                2,
+                2,
+                min,
+                max,
+                selection,
+                2,
+                2,
+                2,
+                selection.is_empty(),
+            )
+        };
+
+        assert!(remesh(&data, [0; 3], [1; 3], &[]).is_ok());
+        assert_eq!(
+            remesh(&data, [1, 0, 0], [0, 1, 1], &[]),
+            Err(MeshingError::InvalidRegion {
+                min: [1, 0, 0],
+                max: [0, 1, 1]
+            })
+        );
+        assert_eq!(
+            remesh(&data, [0; 3], [usize::MAX, 0, 0], &[]),
+            Err(MeshingError::InvalidRegion {
+                min: [0; 3],
+                max: [usize::MAX, 0, 0]
+            })
+        );
+        assert_eq!(
+            remesh(&data[..7], [0; 3], [0; 3], &[]),
+            Err(MeshingError::VoxelDataLength {
+                expected: 8,
+                actual: 7
+            })
+        );
+        assert_eq!(
+            remesh(&data, [0; 3], [0; 3], &[0; 3]),
+            Err(MeshingError::SelectionDataLength {
+                expected: 8,
+                actual: 3
+            })
+        );
+    }
+}
@@ -1,17 +1,242 @@
+#![allow(clippy::too_many_arguments)]
+
 pub mod ambient_occlusion;
+pub mod bitmask_backend;
+pub mod block_materials;
+pub mod color_palette;
+pub mod exterior_fill;
+pub mod fill_shape;
 pub mod find_exterior_faces;
+pub mod flood_fill;
+pub mod incremental_mesher;
+pub mod lod;
 pub mod mesh_arrays;
+pub mod mesh_export;
+pub mod packed_vertex;
+#[cfg(feature = "parallel")]
+pub mod parallel_mesher;
+pub mod picking;
+pub mod raycast;
+pub mod selection_outline;
+pub mod surface_nets;
+#[cfg(test)]
+mod test_support;
 pub mod texture_coords;
+pub mod validation;
+pub mod voxel_codec;
 pub mod voxel_constants;
 
+use bitmask_backend::MeshingBackend;
+use color_palette::ColorPalette;
+use fill_shape::{FillMode, FillShape};
 use find_exterior_faces::ExteriorFacesFinder;
-use mesh_arrays::MeshArrays;
+use flood_fill::{Connectivity, FloodMatch};
+use incremental_mesher::IncrementalMesher;
+use mesh_arrays::{MeshArrays, VertexFormat};
+use mesh_export::{AxisConvention, ExportFormat, ExportOptions};
+use packed_vertex::PACKED_WORDS_PER_VERTEX;
+use selection_outline::SelectionOutline;
+use surface_nets::SurfaceNetsMesher;
+use texture_coords::{expand_block_atlas_mapping, UvMode, FACES_PER_BLOCK};
 use wasm_bindgen::prelude::*;
 
+#[wasm_bindgen(js_name = facesPerBlock)]
+pub fn faces_per_block() -> usize {
+    FACES_PER_BLOCK
+}
+
+#[wasm_bindgen(js_name = wasmMemory)]
+pub fn wasm_memory() -> JsValue {
+    wasm_bindgen::memory()
+}
+
+#[wasm_bindgen(js_name = expandBlockAtlasMapping)]
+pub fn expand_block_atlas_mapping_js(per_block_mapping: &[i32]) -> Vec<i32> {
+    expand_block_atlas_mapping(per_block_mapping)
+}
+
+#[wasm_bindgen(js_name = raycastVoxels)]
+pub fn raycast_voxels(
+    voxel_data: &[u8],
+    dim_x: usize,
+    dim_y: usize,
+    dim_z: usize,
+    origin_x: f32,
+    origin_y: f32,
+    origin_z: f32,
+    direction_x: f32,
+    direction_y: f32,
+    direction_z: f32,
+    max_distance: f32,
+) -> Result<Option<Vec<f32>>, JsError> {
+    let hit = raycast::try_raycast(
+        voxel_data,
+        [dim_x, dim_y, dim_z],
+        [origin_x, origin_y, origin_z],
+        [direction_x, direction_y, direction_z],
+        max_distance,
+    )?;
+    Ok(hit.map(|hit| {
+        let [x, y, z] = hit.grid_position.map(|c| c as f32);
+        let [nx, ny, nz] = hit.normal();
+        vec![x, y, z, nx, ny, nz, hit.block_value as f32, hit.distance]
+    }))
+}
+
+#[wasm_bindgen(js_name = fillShape)]
+pub fn fill_shape_js(
+    voxel_data: &mut [u8],
+    dim_x: usize,
+    dim_y: usize,
+    dim_z: usize,
+    shape: u8,
+    min_x: i32,
+    min_y: i32,
+    min_z: i32,
+    max_x: i32,
+    max_y: i32,
+    max_z: i32,
+    up_face_dir: usize,
+    wall_thickness: usize,
+    mode: u8,
+    block_type: u8,
+    preview: bool,
+) -> Result<usize, JsError> {
+    let dims = [dim_x, dim_y, dim_z];
+    let min = [min_x, min_y, min_z].map(i64::from);
+    let max = [max_x, max_y, max_z].map(i64::from);
+    let shape = FillShape::try_from(shape)?;
+    let mode = FillMode::try_from(mode)?;
+    let write = if preview {
+        fill_shape::write_fill_shape_preview
+    } else {
+        fill_shape::apply_fill_shape
+    };
+    Ok(write(
+        voxel_data,
+        dims,
+        shape,
+        min,
+        max,
+        up_face_dir,
+        wall_thickness,
+        mode,
+        block_type,
+    )?)
+}
+
+#[wasm_bindgen(js_name = floodFill)]
+pub fn flood_fill_js(
+    voxel_data: &[u8],
+    dim_x: usize,
+    dim_y: usize,
+    dim_z: usize,
+    seed_x: usize,
+    seed_y: usize,
+    seed_z: usize,
+    connectivity: u8,
+    match_mode: u8,
+    max_voxels: usize,
+    selection_data: &mut [u8],
+) -> Result<Vec<u32>, JsError> {
+    let result = flood_fill::try_flood_fill(
+        voxel_data,
+        [dim_x, dim_y, dim_z],
+        [seed_x, seed_y, seed_z],
+        Connectivity::try_from(connectivity)?,
+        FloodMatch::try_from(match_mode)?,
+        max_voxels,
+        selection_data,
+    )?;
+    Ok(vec![result.filled as u32, result.truncated as u32])
+}
+
+#[wasm_bindgen(js_name = compressVoxelData)]
+pub fn compress_voxel_data_js(voxel_data: &[u8]) -> Result<Vec<u8>, JsError> {
+    Ok(voxel_codec::compress_voxel_data(voxel_data)?)
+}
+
+#[wasm_bindgen(js_name = decompressVoxelData)]
+pub fn decompress_voxel_data_js(compressed_data: &[u8]) -> Result<Vec<u8>, JsError> {
+    Ok(voxel_codec::decompress_voxel_data(compressed_data)?)
+}
+
+#[wasm_bindgen(js_name = decompressVoxelDataInto)]
+pub fn decompress_voxel_data_into_js(
+    compressed_data: &[u8],
+    target: &mut [u8],
+) -> Result<usize, JsError> {
+    Ok(voxel_codec::decompress_into(compressed_data, target)?)
+}
+
+#[wasm_bindgen(js_name = exportVoxelVolume)]
+pub fn export_voxel_volume(
+    voxel_data: &[u8],
+    dim_x: usize,
+    dim_y: usize,
+    dim_z: usize,
+    format: u8,
+    scale: f32,
+    axis: u8,
+    palette_rgba: &[f32],
+) -> Result<Vec<u8>, JsError> {
+    if !palette_rgba.len().is_multiple_of(4) {
+        return Err(JsError::new("Color palette needs 4 components per block type"));
+    }
+    let options = ExportOptions {
+        scale,
+        axis: AxisConvention::try_from(axis)?,
+        vertex_colors: false,
+    };
+    Ok(mesh_export::export_volume(
+        voxel_data,
+        [dim_x, dim_y, dim_z],
+        (!palette_rgba.is_empty()).then(|| ColorPalette::from_rgba(palette_rgba)),
+        ExportFormat::try_from(format)?,
+        &options,
+    )?)
+}
+
+#[wasm_bindgen(js_name = buildSelectionOutline)]
+pub fn build_selection_outline(
+    selection_data: &[u8],
+    selection_dim_x: usize,
+    selection_dim_y: usize,
+    selection_dim_z: usize,
+) -> Result<Vec<f32>, JsError> {
+    let mut outline = SelectionOutline::new();
+    let segments = outline.try_build(
+        selection_data,
+        selection_dim_x,
+        selection_dim_y,
+        selection_dim_z,
+    )?;
+    Ok(segments.to_vec())
+}
+
 #[wasm_bindgen]
 pub struct WasmExteriorFacesFinder {
     finder: ExteriorFacesFinder,
     mesh_arrays: Option<MeshArrays>,
+    vertex_format: VertexFormat,
+    surface_nets: SurfaceNetsMesher,
+    smooth: bool,
+}
+
+impl WasmExteriorFacesFinder {
+    fn prepare_mesh_arrays(
+        mesh_arrays: &mut Option<MeshArrays>,
+        vertex_format: VertexFormat,
+    ) -> &mut MeshArrays {
+        let mesh_arrays = mesh_arrays
+            .get_or_insert_with(|| MeshArrays::with_format(vertex_format, 0, 0));
+
+        if mesh_arrays.format != vertex_format {
+            *mesh_arrays = MeshArrays::with_format(vertex_format, 0, 0);
+        }
+
+        mesh_arrays
+    }
 }
 
 #[wasm_bindgen]
@@ -21,6 +246,9 @@ impl WasmExteriorFacesFinder {
         Self {
             finder: ExteriorFacesFinder::new(max_dimension),
             mesh_arrays: None,
+            vertex_format: VertexFormat::Float,
+            surface_nets: SurfaceNetsMesher::new(),
+            smooth: false,
         }
     }
 
@@ -33,39 +261,255 @@ impl WasmExteriorFacesFinder {
         dim_x: usize,
         dim_y: usize,
         dim_z: usize,
-        max_vertices: usize,
-        max_indices: usize,
         selection_data: &[u8],
         selection_dim_x: usize,
         selection_dim_y: usize,
         selection_dim_z: usize,
         selection_empty: bool,
-    ) {
-        let mesh_arrays = self.mesh_arrays.get_or_insert_with(|| {
-            MeshArrays::new(max_vertices, max_indices)
-        });
+    ) -> Result<(), JsError> {
+        if self.smooth {
+            let mesh_arrays = Self::prepare_mesh_arrays(&mut self.mesh_arrays, VertexFormat::Float);
+            self.surface_nets.try_extract_surface(
+                voxel_data,
+                texture_width,
+                block_atlas_mapping,
+                dim_x,
+                dim_y,
+                dim_z,
+                mesh_arrays,
+                selection_data,
+                selection_dim_x,
+                selection_dim_y,
+                selection_dim_z,
+                selection_empty,
+            )?;
+            return Ok(());
+        }
+
+        let mesh_arrays = Self::prepare_mesh_arrays(&mut self.mesh_arrays, self.vertex_format);
 
-        let needs_resize = mesh_arrays.vertices.len() < max_vertices * 3
-            || mesh_arrays.indices.len() < max_indices;
+        self.finder.try_find_exterior_faces(
+            voxel_data,
+            texture_width,
+            block_atlas_mapping,
+            dim_x,
+            dim_y,
+            dim_z,
+            mesh_arrays,
+            selection_data,
+            selection_dim_x,
+            selection_dim_y,
+            selection_dim_z,
+            selection_empty,
+        )?;
+        Ok(())
+    }
 
-        if needs_resize {
-            *mesh_arrays = MeshArrays::new(max_vertices, max_indices);
+    #[wasm_bindgen(js_name = findExteriorFacesWithHalo)]
+    pub fn find_exterior_faces_with_halo(
+        &mut self,
+        voxel_data: &[u8],
+        texture_width: i32,
+        block_atlas_mapping: &[i32],
+        dim_x: usize,
+        dim_y: usize,
+        dim_z: usize,
+        selection_data: &[u8],
+        selection_dim_x: usize,
+        selection_dim_y: usize,
+        selection_dim_z: usize,
+        selection_empty: bool,
+    ) -> Result<(), JsError> {
+        if self.smooth {
+            let mesh_arrays = Self::prepare_mesh_arrays(&mut self.mesh_arrays, VertexFormat::Float);
+            self.surface_nets.try_extract_surface_with_halo(
+                voxel_data,
+                texture_width,
+                block_atlas_mapping,
+                dim_x,
+                dim_y,
+                dim_z,
+                mesh_arrays,
+                selection_data,
+                selection_dim_x,
+                selection_dim_y,
+                selection_dim_z,
+                selection_empty,
+            )?;
+            return Ok(());
         }
 
-        self.finder.find_exterior_faces(
+        let mesh_arrays = Self::prepare_mesh_arrays(&mut self.mesh_arrays, self.vertex_format);
+
+        self.finder.try_find_exterior_faces_with_halo(
             voxel_data,
             texture_width,
             block_atlas_mapping,
             dim_x,
             dim_y,
             dim_z,
-            self.mesh_arrays.as_mut().unwrap(),
+            mesh_arrays,
             selection_data,
             selection_dim_x,
             selection_dim_y,
             selection_dim_z,
             selection_empty,
-        );
+        )?;
+        Ok(())
+    }
+
+    #[wasm_bindgen(js_name = findExteriorFacesLod)]
+    pub fn find_exterior_faces_lod(
+        &mut self,
+        voxel_data: &[u8],
+        texture_width: i32,
+        block_atlas_mapping: &[i32],
+        dim_x: usize,
+        dim_y: usize,
+        dim_z: usize,
+        lod_factor: usize,
+    ) -> Result<(), JsError> {
+        let mesh_arrays = Self::prepare_mesh_arrays(&mut self.mesh_arrays, self.vertex_format);
+
+        self.finder.try_find_exterior_faces_lod(
+            voxel_data,
+            texture_width,
+            block_atlas_mapping,
+            dim_x,
+            dim_y,
+            dim_z,
+            lod_factor,
+            mesh_arrays,
+        )?;
+        Ok(())
+    }
+
+    #[wasm_bindgen(js_name = setBlockMaterials)]
+    pub fn set_block_materials(&mut self, block_classes: &[u8]) -> Result<(), JsError> {
+        Ok(self.finder.set_block_materials(block_classes)?)
+    }
+
+    #[wasm_bindgen(js_name = setBitmaskBackend)]
+    pub fn set_bitmask_backend(&mut self, enabled: bool) {
+        self.finder.set_backend(if enabled {
+            MeshingBackend::Bitmask
+        } else {
+            MeshingBackend::Scalar
+        });
+    }
+
+    #[wasm_bindgen(js_name = setTiledUvs)]
+    pub fn set_tiled_uvs(&mut self, tiled: bool) {
+        self.finder.set_uv_mode(if tiled { UvMode::Tiled } else { UvMode::Atlas });
+    }
+
+    #[wasm_bindgen(js_name = setColorPalette)]
+    pub fn set_color_palette(&mut self, rgba: &[f32], premultiply_ao: bool) -> Result<(), JsError> {
+        if !rgba.len().is_multiple_of(4) {
+            return Err(JsError::new("Color palette needs 4 components per block type"));
+        }
+        let mut palette = ColorPalette::from_rgba(rgba);
+        palette.set_premultiply_ao(premultiply_ao);
+        self.finder.set_color_palette(Some(palette));
+        Ok(())
+    }
+
+    #[wasm_bindgen(js_name = setColorPaletteHex)]
+    pub fn set_color_palette_hex(&mut self, colors: &[u32], premultiply_ao: bool) {
+        let mut palette = ColorPalette::from_hex_rgb(colors);
+        palette.set_premultiply_ao(premultiply_ao);
+        self.finder.set_color_palette(Some(palette));
+    }
+
+    #[wasm_bindgen(js_name = clearColorPalette)]
+    pub fn clear_color_palette(&mut self) {
+        self.finder.set_color_palette(None);
+    }
+
+    #[wasm_bindgen(js_name = setPickingEnabled)]
+    pub fn set_picking_enabled(&mut self, enabled: bool) {
+        self.finder.set_picking(enabled);
+    }
+
+    #[wasm_bindgen(js_name = pickVoxel)]
+    pub fn pick_voxel(&self, quad_id: u32, hit_x: f32, hit_y: f32, hit_z: f32) -> Option<Vec<u32>> {
+        let hit = self
+            .mesh_arrays
+            .as_ref()
+            .filter(|_| !self.smooth)?
+            .pick(quad_id, [hit_x, hit_y, hit_z])?;
+        Some(vec![hit.voxel[0], hit.voxel[1], hit.voxel[2], hit.face_dir as u32])
+    }
+
+    #[wasm_bindgen(js_name = exportMesh)]
+    pub fn export_mesh(&self, format: u8, scale: f32, axis: u8) -> Result<Vec<u8>, JsError> {
+        let format = ExportFormat::try_from(format)?;
+        let options = ExportOptions {
+            scale,
+            axis: AxisConvention::try_from(axis)?,
+            vertex_colors: !self.smooth && self.finder.color_palette().is_some(),
+        };
+        let empty = MeshArrays::new(0, 0);
+        let mesh = self.mesh_arrays.as_ref().unwrap_or(&empty);
+        Ok(mesh_export::export_mesh(mesh, format, &options)?)
+    }
+
+    #[wasm_bindgen(js_name = setAoEnabled)]
+    pub fn set_ao_enabled(&mut self, enabled: bool) {
+        let mut ao = self.finder.ao_config();
+        ao.enabled = enabled;
+        self.finder.set_ao_config(ao);
+    }
+
+    #[wasm_bindgen(js_name = setAoStrength)]
+    pub fn set_ao_strength(&mut self, strength: f32) -> Result<(), JsError> {
+        let mut ao = self.finder.ao_config();
+        ao.strength = strength;
+        Ok(self.finder.try_set_ao_config(ao)?)
+    }
+
+    #[wasm_bindgen(js_name = setAoCurve)]
+    pub fn set_ao_curve(&mut self, levels: &[f32]) -> Result<(), JsError> {
+        let levels: [f32; 4] = levels
+            .try_into()
+            .map_err(|_| JsError::new("AO curve needs exactly 4 levels"))?;
+        let mut ao = self.finder.ao_config();
+        ao.levels = levels;
+        Ok(self.finder.try_set_ao_config(ao)?)
+    }
+
+    #[wasm_bindgen(js_name = setExteriorOnly)]
+    pub fn set_exterior_only(&mut self, exterior_only: bool) {
+        self.finder.set_exterior_only(exterior_only);
+    }
+
+    #[wasm_bindgen(js_name = getCavityCount)]
+    pub fn get_cavity_count(&self) -> usize {
+        self.finder.cavity_report().cavity_count
+    }
+
+    #[wasm_bindgen(js_name = getCavityVolume)]
+    pub fn get_cavity_volume(&self) -> usize {
+        self.finder.cavity_report().cavity_volume
+    }
+
+    #[wasm_bindgen(js_name = setSmoothMode)]
+    pub fn set_smooth_mode(&mut self, smooth: bool) {
+        self.smooth = smooth;
+    }
+
+    #[wasm_bindgen(js_name = setPackedOutput)]
+    pub fn set_packed_output(&mut self, packed: bool) {
+        self.vertex_format = if packed {
+            VertexFormat::Packed
+        } else {
+            VertexFormat::Float
+        };
+    }
+
+    #[wasm_bindgen(js_name = getPackedWordsPerVertex)]
+    pub fn get_packed_words_per_vertex(&self) -> usize {
+        PACKED_WORDS_PER_VERTEX
     }
 
     #[wasm_bindgen(js_name = getVertexCount)]
@@ -82,98 +526,424 @@ impl WasmExteriorFacesFinder {
             .map_or(0, |m| m.index_count)
     }
 
-    #[wasm_bindgen(js_name = getVertices)]
-    pub fn get_vertices(&self) -> Vec<f32> {
+    #[wasm_bindgen(js_name = getTranslucentIndexStart)]
+    pub fn get_translucent_index_start(&self) -> usize {
+        self.mesh_arrays
+            .as_ref()
+            .map_or(0, |m| m.translucent_index_start)
+    }
+
+    #[wasm_bindgen(js_name = getFaceIndexRanges)]
+    pub fn get_face_index_ranges(&self) -> Vec<u32> {
+        self.mesh_arrays
+            .as_ref()
+            .map_or_else(Vec::new, |m| flatten_index_ranges(&m.face_index_ranges))
+    }
+
+    #[wasm_bindgen(js_name = getTranslucentFaceIndexRanges)]
+    pub fn get_translucent_face_index_ranges(&self) -> Vec<u32> {
         self.mesh_arrays.as_ref().map_or_else(Vec::new, |m| {
-            m.vertices[..m.vertex_count * 3].to_vec()
+            flatten_index_ranges(&m.translucent_face_index_ranges)
         })
     }
 
+    #[wasm_bindgen(js_name = getVertices)]
+    pub fn get_vertices(&self) -> Vec<f32> {
+        self.mesh_arrays
+            .as_ref()
+            .filter(|m| !m.is_packed())
+            .map_or_else(Vec::new, |m| m.vertices[..m.vertex_count * 3].to_vec())
+    }
+
     #[wasm_bindgen(js_name = getNormals)]
     pub fn get_normals(&self) -> Vec<f32> {
-        self.mesh_arrays.as_ref().map_or_else(Vec::new, |m| {
-            m.normals[..m.vertex_count * 3].to_vec()
-        })
+        self.mesh_arrays
+            .as_ref()
+            .filter(|m| !m.is_packed())
+            .map_or_else(Vec::new, |m| m.normals[..m.vertex_count * 3].to_vec())
     }
 
     #[wasm_bindgen(js_name = getUVs)]
     pub fn get_uvs(&self) -> Vec<f32> {
-        self.mesh_arrays.as_ref().map_or_else(Vec::new, |m| {
-            m.uvs[..m.vertex_count * 2].to_vec()
-        })
+        self.mesh_arrays
+            .as_ref()
+            .filter(|m| !m.is_packed())
+            .map_or_else(Vec::new, |m| m.uvs[..m.vertex_count * 2].to_vec())
+    }
+
+    #[wasm_bindgen(js_name = getTileOrigins)]
+    pub fn get_tile_origins(&self) -> Vec<f32> {
+        self.mesh_arrays
+            .as_ref()
+            .filter(|m| !m.is_packed() && !self.smooth && self.finder.uv_mode() == UvMode::Tiled)
+            .and_then(|m| m.tile_origins.get(..m.vertex_count * 2))
+            .map_or_else(Vec::new, <[f32]>::to_vec)
+    }
+
+    #[wasm_bindgen(js_name = getColors)]
+    pub fn get_colors(&self) -> Vec<f32> {
+        self.mesh_arrays
+            .as_ref()
+            .filter(|m| !m.is_packed() && !self.smooth && self.finder.color_palette().is_some())
+            .and_then(|m| m.colors.get(..m.vertex_count * 4))
+            .map_or_else(Vec::new, <[f32]>::to_vec)
+    }
+
+    #[wasm_bindgen(js_name = getQuadIds)]
+    pub fn get_quad_ids(&self) -> Vec<u32> {
+        self.mesh_arrays
+            .as_ref()
+            .filter(|_| !self.smooth && self.finder.picking())
+            .map_or_else(Vec::new, |m| m.quad_ids[..m.vertex_count].to_vec())
     }
 
     #[wasm_bindgen(js_name = getAO)]
     pub fn get_ao(&self) -> Vec<f32> {
-        self.mesh_arrays.as_ref().map_or_else(Vec::new, |m| {
-            m.ao[..m.vertex_count].to_vec()
-        })
+        self.mesh_arrays
+            .as_ref()
+            .filter(|m| !m.is_packed())
+            .map_or_else(Vec::new, |m| m.ao[..m.vertex_count].to_vec())
     }
 
     #[wasm_bindgen(js_name = getIsSelected)]
     pub fn get_is_selected(&self) -> Vec<f32> {
-        self.mesh_arrays.as_ref().map_or_else(Vec::new, |m| {
-            m.is_selected[..m.vertex_count].to_vec()
-        })
+        self.mesh_arrays
+            .as_ref()
+            .filter(|m| !m.is_packed())
+            .map_or_else(Vec::new, |m| m.is_selected[..m.vertex_count].to_vec())
+    }
+
+    #[wasm_bindgen(js_name = getBlockTypes)]
+    pub fn get_block_types(&self) -> Vec<f32> {
+        self.mesh_arrays
+            .as_ref()
+            .filter(|_| self.smooth)
+            .and_then(|m| m.block_types.get(..m.vertex_count))
+            .map_or_else(Vec::new, <[f32]>::to_vec)
+    }
+
+    #[wasm_bindgen(js_name = getPackedVertices)]
+    pub fn get_packed_vertices(&self) -> Vec<u32> {
+        self.mesh_arrays
+            .as_ref()
+            .filter(|m| m.is_packed())
+            .map_or_else(Vec::new, |m| {
+                m.packed[..m.vertex_count * PACKED_WORDS_PER_VERTEX].to_vec()
+            })
     }
 
     #[wasm_bindgen(js_name = getIndices)]
     pub fn get_indices(&self) -> Vec<u32> {
-        self.mesh_arrays.as_ref().map_or_else(Vec::new, |m| {
-            m.indices[..m.index_count].to_vec()
-        })
+        self.mesh_arrays.as_ref().map_or_else(Vec::new, |m| m.indices[..m.index_count].to_vec())
     }
-}
 
-#[cfg(test)]
-mod tests {
-    use super::*;
+    /// The `get*Ptr` accessors return byte offsets into `wasmMemory().buffer` so JS
+    /// can build `Float32Array`/`Uint32Array` views of length `getVertexCount() * n`
+    /// or `getIndexCount()` without copying. A view is invalidated by the next
+    /// `findExteriorFaces*` call, which may reallocate the buffers, and by any wasm
+    /// allocation that grows linear memory, which detaches the old `ArrayBuffer`.
+    /// Read or upload the views immediately and re-create them after every remesh.
+    #[wasm_bindgen(js_name = getVerticesPtr)]
+    pub fn get_vertices_ptr(&self) -> *const f32 {
+        self.mesh_arrays
+            .as_ref()
+            .map_or(std::ptr::null(), |m| m.vertices.as_ptr())
+    }
 
-    fn create_voxel_data(dim_x: usize, dim_y: usize, dim_z: usize) -> Vec<u8> {
-        vec![0u8; dim_x * dim_y * dim_z]
+    #[wasm_bindgen(js_name = getNormalsPtr)]
+    pub fn get_normals_ptr(&self) -> *const f32 {
+        self.mesh_arrays
+            .as_ref()
+            .map_or(std::ptr::null(), |m| m.normals.as_ptr())
     }
 
-    fn set_voxel(
-        data: &mut [u8],
-        x: usize,
-        y: usize,
-        z: usize,
-        block_type: u8,
-        dim_y: usize,
-        dim_z: usize,
-    ) {
-        data[x * dim_y * dim_z + y * dim_z + z] = block_type;
+    #[wasm_bindgen(js_name = getUVsPtr)]
+    pub fn get_uvs_ptr(&self) -> *const f32 {
+        self.mesh_arrays
+            .as_ref()
+            .map_or(std::ptr::null(), |m| m.uvs.as_ptr())
     }
 
-    fn create_block_atlas_mapping(num_blocks: usize) -> Vec<i32> {
-        (0..num_blocks as i32).collect()
+    #[wasm_bindgen(js_name = getTileOriginsPtr)]
+    pub fn get_tile_origins_ptr(&self) -> *const f32 {
+        self.mesh_arrays
+            .as_ref()
+            .map_or(std::ptr::null(), |m| m.tile_origins.as_ptr())
     }
 
-    fn run_finder(
+    #[wasm_bindgen(js_name = getColorsPtr)]
+    pub fn get_colors_ptr(&self) -> *const f32 {
+        self.mesh_arrays
+            .as_ref()
+            .map_or(std::ptr::null(), |m| m.colors.as_ptr())
+    }
+
+    #[wasm_bindgen(js_name = getQuadIdsPtr)]
+    pub fn get_quad_ids_ptr(&self) -> *const u32 {
+        self.mesh_arrays
+            .as_ref()
+            .map_or(std::ptr::null(), |m| m.quad_ids.as_ptr())
+    }
+
+    #[wasm_bindgen(js_name = getAOPtr)]
+    pub fn get_ao_ptr(&self) -> *const f32 {
+        self.mesh_arrays
+            .as_ref()
+            .map_or(std::ptr::null(), |m| m.ao.as_ptr())
+    }
+
+    #[wasm_bindgen(js_name = getIsSelectedPtr)]
+    pub fn get_is_selected_ptr(&self) -> *const f32 {
+        self.mesh_arrays
+            .as_ref()
+            .map_or(std::ptr::null(), |m| m.is_selected.as_ptr())
+    }
+
+    #[wasm_bindgen(js_name = getBlockTypesPtr)]
+    pub fn get_block_types_ptr(&self) -> *const f32 {
+        self.mesh_arrays
+            .as_ref()
+            .map_or(std::ptr::null(), |m| m.block_types.as_ptr())
+    }
+
+    #[wasm_bindgen(js_name = getPackedVerticesPtr)]
+    pub fn get_packed_vertices_ptr(&self) -> *const u32 {
+        self.mesh_arrays
+            .as_ref()
+            .map_or(std::ptr::null(), |m| m.packed.as_ptr())
+    }
+
+    #[wasm_bindgen(js_name = getIndicesPtr)]
+    pub fn get_indices_ptr(&self) -> *const u32 {
+        self.mesh_arrays
+            .as_ref()
+            .map_or(std::ptr::null(), |m| m.indices.as_ptr())
+    }
+}
+
+#[wasm_bindgen]
+pub struct WasmIncrementalMesher {
+    mesher: IncrementalMesher,
+}
+
+#[wasm_bindgen]
+impl WasmIncrementalMesher {
+    #[wasm_bindgen(constructor)]
+    pub fn new(max_dimension: usize) -> Self {
+        Self {
+            mesher: IncrementalMesher::new(max_dimension),
+        }
+    }
+
+    #[wasm_bindgen(js_name = remeshFull)]
+    pub fn remesh_full(
+        &mut self,
         voxel_data: &[u8],
+        texture_width: i32,
+        block_atlas_mapping: &[i32],
         dim_x: usize,
         dim_y: usize,
         dim_z: usize,
-        block_atlas_mapping: &[i32],
         selection_data: &[u8],
+        selection_dim_x: usize,
+        selection_dim_y: usize,
+        selection_dim_z: usize,
         selection_empty: bool,
-    ) -> (usize, usize) {
-        let max_dim = dim_x.max(dim_y).max(dim_z);
-        let mut finder = ExteriorFacesFinder::new(max_dim);
-        let total_voxels = dim_x * dim_y * dim_z;
-        let max_faces = total_voxels * 6;
-        let mut mesh_arrays = MeshArrays::new(max_faces * 4, max_faces * 6);
-
-        finder.find_exterior_faces(
+    ) -> Result<(), JsError> {
+        Ok(self.mesher.try_remesh_full(
             voxel_data,
-            4,
+            texture_width,
             block_atlas_mapping,
             dim_x,
             dim_y,
             dim_z,
-            &mut mesh_arrays,
             selection_data,
-            dim_x,
+            selection_dim_x,
+            selection_dim_y,
+            selection_dim_z,
+            selection_empty,
+        )?)
+    }
+
+    #[wasm_bindgen(js_name = remeshRegion)]
+    pub fn remesh_region(
+        &mut self,
+        voxel_data: &[u8],
+        texture_width: i32,
+        block_atlas_mapping: &[i32],
+        dim_x: usize,
+        dim_y: usize,
+        dim_z: usize,
+        min_x: usize,
+        min_y: usize,
+        min_z: usize,
+        max_x: usize,
+        max_y: usize,
+        max_z: usize,
+        selection_data: &[u8],
+        selection_dim_x: usize,
+        selection_dim_y: usize,
+        selection_dim_z: usize,
+        selection_empty: bool,
+    ) -> Result<(), JsError> {
+        Ok(self.mesher.try_remesh_region(
+            voxel_data,
+            texture_width,
+            block_atlas_mapping,
+            dim_x,
+            dim_y,
+            dim_z,
+            [min_x, min_y, min_z],
+            [max_x, max_y, max_z],
+            selection_data,
+            selection_dim_x,
+            selection_dim_y,
+            selection_dim_z,
+            selection_empty,
+        )?)
+    }
+
+    #[wasm_bindgen(js_name = setBlockMaterials)]
+    pub fn set_block_materials(&mut self, block_classes: &[u8]) -> Result<(), JsError> {
+        Ok(self.mesher.finder_mut().set_block_materials(block_classes)?)
+    }
+
+    #[wasm_bindgen(js_name = setPickingEnabled)]
+    pub fn set_picking_enabled(&mut self, enabled: bool) {
+        self.mesher.finder_mut().set_picking(enabled);
+    }
+
+    #[wasm_bindgen(js_name = pickVoxel)]
+    pub fn pick_voxel(&self, quad_id: u32, hit_x: f32, hit_y: f32, hit_z: f32) -> Option<Vec<u32>> {
+        let hit = self.mesher.mesh_arrays().pick(quad_id, [hit_x, hit_y, hit_z])?;
+        Some(vec![hit.voxel[0], hit.voxel[1], hit.voxel[2], hit.face_dir as u32])
+    }
+
+    #[wasm_bindgen(js_name = getVertexCount)]
+    pub fn get_vertex_count(&self) -> usize {
+        self.mesher.mesh_arrays().vertex_count
+    }
+
+    #[wasm_bindgen(js_name = getIndexCount)]
+    pub fn get_index_count(&self) -> usize {
+        self.mesher.mesh_arrays().index_count
+    }
+
+    #[wasm_bindgen(js_name = getTranslucentIndexStart)]
+    pub fn get_translucent_index_start(&self) -> usize {
+        self.mesher.mesh_arrays().translucent_index_start
+    }
+
+    #[wasm_bindgen(js_name = getFaceIndexRanges)]
+    pub fn get_face_index_ranges(&self) -> Vec<u32> {
+        flatten_index_ranges(&self.mesher.mesh_arrays().face_index_ranges)
+    }
+
+    #[wasm_bindgen(js_name = getTranslucentFaceIndexRanges)]
+    pub fn get_translucent_face_index_ranges(&self) -> Vec<u32> {
+        flatten_index_ranges(&self.mesher.mesh_arrays().translucent_face_index_ranges)
+    }
+
+    #[wasm_bindgen(js_name = getVertices)]
+    pub fn get_vertices(&self) -> Vec<f32> {
+        let m = self.mesher.mesh_arrays();
+        m.vertices[..m.vertex_count * 3].to_vec()
+    }
+
+    #[wasm_bindgen(js_name = getNormals)]
+    pub fn get_normals(&self) -> Vec<f32> {
+        let m = self.mesher.mesh_arrays();
+        m.normals[..m.vertex_count * 3].to_vec()
+    }
+
+    #[wasm_bindgen(js_name = getUVs)]
+    pub fn get_uvs(&self) -> Vec<f32> {
+        let m = self.mesher.mesh_arrays();
+        m.uvs[..m.vertex_count * 2].to_vec()
+    }
+
+    #[wasm_bindgen(js_name = getAO)]
+    pub fn get_ao(&self) -> Vec<f32> {
+        let m = self.mesher.mesh_arrays();
+        m.ao[..m.vertex_count].to_vec()
+    }
+
+    #[wasm_bindgen(js_name = getIsSelected)]
+    pub fn get_is_selected(&self) -> Vec<f32> {
+        let m = self.mesher.mesh_arrays();
+        m.is_selected[..m.vertex_count].to_vec()
+    }
+
+    #[wasm_bindgen(js_name = getQuadIds)]
+    pub fn get_quad_ids(&self) -> Vec<u32> {
+        let m = self.mesher.mesh_arrays();
+        m.quad_ids.get(..m.vertex_count).map_or_else(Vec::new, <[u32]>::to_vec)
+    }
+
+    #[wasm_bindgen(js_name = getIndices)]
+    pub fn get_indices(&self) -> Vec<u32> {
+        let m = self.mesher.mesh_arrays();
+        m.indices[..m.index_count].to_vec()
+    }
+}
+
+fn flatten_index_ranges(ranges: &[(usize, usize); FACES_PER_BLOCK]) -> Vec<u32> {
+    ranges
+        .iter()
+        .flat_map(|&(start, end)| [start as u32, end as u32])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use ambient_occlusion::{AoConfig, OCCLUSION_LEVELS};
+
+    fn create_voxel_data(dim_x: usize, dim_y: usize, dim_z: usize) -> Vec<u8> {
+        vec![0u8; dim_x * dim_y * dim_z]
+    }
+
+    fn set_voxel(
+        data: &mut [u8],
+        x: usize,
+        y: usize,
+        z: usize,
+        block_type: u8,
+        dim_y: usize,
+        dim_z: usize,
+    ) {
+        data[x * dim_y * dim_z + y * dim_z + z] = block_type;
+    }
+
+    fn create_block_atlas_mapping(num_blocks: usize) -> Vec<i32> {
+        expand_block_atlas_mapping(&(0..num_blocks as i32).collect::<Vec<_>>())
+    }
+
+    fn run_finder(
+        voxel_data: &[u8],
+        dim_x: usize,
+        dim_y: usize,
+        dim_z: usize,
+        block_atlas_mapping: &[i32],
+        selection_data: &[u8],
+        selection_empty: bool,
+    ) -> (usize, usize) {
+        let max_dim = dim_x.max(dim_y).max(dim_z);
+        let mut finder = ExteriorFacesFinder::new(max_dim);
+        let total_voxels = dim_x * dim_y * dim_z;
+        let max_faces = total_voxels * 6;
+        let mut mesh_arrays = MeshArrays::new(max_faces * 4, max_faces * 6);
+
+        finder.find_exterior_faces(
+            voxel_data,
+            4,
+            block_atlas_mapping,
+            dim_x,
+            dim_y,
+            dim_z,
+            &mut mesh_arrays,
+            selection_data,
+            dim_x,
             dim_y,
             dim_z,
             selection_empty,
@@ -422,4 +1192,1124 @@ mod tests {
         assert_eq!(ic, 36);
         assert_eq!(vc, 24);
     }
+
+    fn collect_face_uvs(mesh_arrays: &MeshArrays) -> Vec<([f32; 3], [f32; 2])> {
+        (0..mesh_arrays.vertex_count)
+            .step_by(4)
+            .map(|v| {
+                (
+                    [
+                        mesh_arrays.normals[v * 3],
+                        mesh_arrays.normals[v * 3 + 1],
+                        mesh_arrays.normals[v * 3 + 2],
+                    ],
+                    [mesh_arrays.uvs[v * 2], mesh_arrays.uvs[v * 2 + 1]],
+                )
+            })
+            .collect()
+    }
+
+    #[test]
+    fn per_face_texture_mapping() {
+        let (dx, dy, dz) = (1, 1, 1);
+        let mut data = create_voxel_data(dx, dy, dz);
+        set_voxel(&mut data, 0, 0, 0, 1, dy, dz);
+        let mapping = vec![0, 0, 1, 2, 0, 0];
+        let sel = vec![0u8; dx * dy * dz];
+
+        let mut finder = ExteriorFacesFinder::new(1);
+        let mut mesh_arrays = MeshArrays::new(24, 36);
+        finder.find_exterior_faces(
+            &data, 4, &mapping, dx, dy, dz, &mut mesh_arrays, &sel, dx, dy, dz, true,
+        );
+
+        let side_uv = texture_coords::get_texture_coordinates(0, 4);
+        let top_uv = texture_coords::get_texture_coordinates(1, 4);
+        let bottom_uv = texture_coords::get_texture_coordinates(2, 4);
+        for (normal, uv) in collect_face_uvs(&mesh_arrays) {
+            let expected = match normal {
+                [_, 1.0, _] => top_uv,
+                [_, -1.0, _] => bottom_uv,
+                _ => side_uv,
+            };
+            assert_eq!(uv, [expected[0], expected[1]]);
+        }
+    }
+
+    #[test]
+    fn faces_with_same_resolved_texture_merge() {
+        let (dx, dy, dz) = (2, 1, 1);
+        let mut data = create_voxel_data(dx, dy, dz);
+        set_voxel(&mut data, 0, 0, 0, 1, dy, dz);
+        set_voxel(&mut data, 1, 0, 0, 2, dy, dz);
+        let mapping = vec![0, 0, 1, 0, 0, 0, 0, 0, 2, 0, 0, 0];
+        let sel = vec![0u8; dx * dy * dz];
+
+        let (vc, ic) = run_finder(&data, dx, dy, dz, &mapping, &sel, true);
+        assert_eq!(ic, 42);
+        assert_eq!(vc, 28);
+    }
+
+    #[test]
+    fn expanded_mapping_repeats_per_face() {
+        assert_eq!(
+            expand_block_atlas_mapping(&[3, 7]),
+            vec![3, 3, 3, 3, 3, 3, 7, 7, 7, 7, 7, 7]
+        );
+    }
+
+    fn mesh_with_materials(
+        voxel_data: &[u8],
+        dim_x: usize,
+        dim_y: usize,
+        dim_z: usize,
+        block_classes: &[u8],
+    ) -> MeshArrays {
+        let mut finder = ExteriorFacesFinder::new(dim_x.max(dim_y).max(dim_z));
+        finder.set_block_materials(block_classes).unwrap();
+        let max_faces = dim_x * dim_y * dim_z * 6;
+        let mut mesh_arrays = MeshArrays::new(max_faces * 4, max_faces * 6);
+        let mapping = create_block_atlas_mapping(block_classes.len() + 1);
+        finder.find_exterior_faces(
+            voxel_data,
+            4,
+            &mapping,
+            dim_x,
+            dim_y,
+            dim_z,
+            &mut mesh_arrays,
+            &[],
+            0,
+            0,
+            0,
+            true,
+        );
+        mesh_arrays
+    }
+
+    #[test]
+    fn translucent_block_does_not_hide_opaque_faces() {
+        let (dx, dy, dz) = (2, 1, 1);
+        let mut data = create_voxel_data(dx, dy, dz);
+        set_voxel(&mut data, 0, 0, 0, 1, dy, dz);
+        set_voxel(&mut data, 1, 0, 0, 2, dy, dz);
+
+        let mesh_arrays = mesh_with_materials(&data, dx, dy, dz, &[0, 2]);
+        assert_eq!(mesh_arrays.translucent_index_start, 36);
+        assert_eq!(mesh_arrays.index_count, 66);
+        assert_eq!(mesh_arrays.vertex_count, 44);
+    }
+
+    #[test]
+    fn different_translucent_types_emit_shared_faces() {
+        let (dx, dy, dz) = (2, 1, 1);
+        let mut data = create_voxel_data(dx, dy, dz);
+        set_voxel(&mut data, 0, 0, 0, 1, dy, dz);
+        set_voxel(&mut data, 1, 0, 0, 2, dy, dz);
+
+        let mesh_arrays = mesh_with_materials(&data, dx, dy, dz, &[2, 2]);
+        assert_eq!(mesh_arrays.translucent_index_start, 0);
+        assert_eq!(mesh_arrays.index_count, 72);
+    }
+
+    #[test]
+    fn same_translucent_type_culls_shared_faces() {
+        let (dx, dy, dz) = (2, 1, 1);
+        let mut data = create_voxel_data(dx, dy, dz);
+        set_voxel(&mut data, 0, 0, 0, 1, dy, dz);
+        set_voxel(&mut data, 1, 0, 0, 1, dy, dz);
+
+        let mesh_arrays = mesh_with_materials(&data, dx, dy, dz, &[2]);
+        assert_eq!(mesh_arrays.translucent_index_start, 0);
+        assert_eq!(mesh_arrays.index_count, 36);
+    }
+
+    #[test]
+    fn cutout_blocks_show_opaque_neighbors() {
+        let (dx, dy, dz) = (2, 1, 1);
+        let mut data = create_voxel_data(dx, dy, dz);
+        set_voxel(&mut data, 0, 0, 0, 1, dy, dz);
+        set_voxel(&mut data, 1, 0, 0, 2, dy, dz);
+
+        let mesh_arrays = mesh_with_materials(&data, dx, dy, dz, &[0, 1]);
+        assert_eq!(mesh_arrays.translucent_index_start, 66);
+        assert_eq!(mesh_arrays.index_count, 66);
+    }
+
+    #[test]
+    fn translucent_blocks_do_not_cast_ao() {
+        let (dx, dy, dz) = (2, 2, 1);
+        let mut data = create_voxel_data(dx, dy, dz);
+        set_voxel(&mut data, 0, 0, 0, 1, dy, dz);
+        set_voxel(&mut data, 1, 1, 0, 2, dy, dz);
+
+        let opaque = mesh_with_materials(&data, dx, dy, dz, &[0, 0]);
+        assert!(opaque.ao[..opaque.vertex_count].iter().any(|&ao| ao < 1.0));
+
+        let translucent = mesh_with_materials(&data, dx, dy, dz, &[0, 2]);
+        let opaque_vertices = translucent.translucent_index_start / 6 * 4;
+        assert!(translucent.ao[..opaque_vertices].iter().all(|&ao| ao == 1.0));
+    }
+
+    fn copy_with_halo(
+        world: &[u8],
+        world_dims: [usize; 3],
+        min: [usize; 3],
+        size: [usize; 3],
+    ) -> Vec<u8> {
+        let padded = [size[0] + 2, size[1] + 2, size[2] + 2];
+        let mut data = vec![0u8; padded[0] * padded[1] * padded[2]];
+        for x in 0..padded[0] {
+            for y in 0..padded[1] {
+                for z in 0..padded[2] {
+                    let wx = (min[0] + x) as i32 - 1;
+                    let wy = (min[1] + y) as i32 - 1;
+                    let wz = (min[2] + z) as i32 - 1;
+                    if wx < 0
+                        || wy < 0
+                        || wz < 0
+                        || wx >= world_dims[0] as i32
+                        || wy >= world_dims[1] as i32
+                        || wz >= world_dims[2] as i32
+                    {
+                        continue;
+                    }
+                    data[x * padded[1] * padded[2] + y * padded[2] + z] = world[wx as usize
+                        * world_dims[1]
+                        * world_dims[2]
+                        + wy as usize * world_dims[2]
+                        + wz as usize];
+                }
+            }
+        }
+        data
+    }
+
+    fn mesh_chunk_with_halo(halo_data: &[u8], size: [usize; 3]) -> MeshArrays {
+        let mut finder = ExteriorFacesFinder::new(size[0].max(size[1]).max(size[2]));
+        let max_faces = size[0] * size[1] * size[2] * 6;
+        let mut mesh_arrays = MeshArrays::new(max_faces * 4, max_faces * 6);
+        let mapping = create_block_atlas_mapping(2);
+        finder.find_exterior_faces_with_halo(
+            halo_data,
+            4,
+            &mapping,
+            size[0],
+            size[1],
+            size[2],
+            &mut mesh_arrays,
+            &[],
+            0,
+            0,
+            0,
+            true,
+        );
+        mesh_arrays
+    }
+
+    #[test]
+    fn halo_culls_faces_on_chunk_border() {
+        let world_dims = [4, 1, 1];
+        let world = vec![1u8; 4];
+
+        let left = copy_with_halo(&world, world_dims, [0, 0, 0], [2, 1, 1]);
+        let mesh_arrays = mesh_chunk_with_halo(&left, [2, 1, 1]);
+        assert_eq!(mesh_arrays.index_count, 30);
+        assert_eq!(mesh_arrays.vertex_count, 20);
+        for v in 0..mesh_arrays.vertex_count {
+            assert!(mesh_arrays.vertices[v * 3] <= 2.0);
+            assert_ne!(mesh_arrays.normals[v * 3], 1.0);
+        }
+
+        let right = copy_with_halo(&world, world_dims, [2, 0, 0], [2, 1, 1]);
+        let mesh_arrays = mesh_chunk_with_halo(&right, [2, 1, 1]);
+        assert_eq!(mesh_arrays.index_count, 30);
+        for v in 0..mesh_arrays.vertex_count {
+            assert_ne!(mesh_arrays.normals[v * 3], -1.0);
+        }
+    }
+
+    #[test]
+    fn halo_occludes_across_chunk_border() {
+        let world_dims = [4, 2, 1];
+        let mut world = vec![0u8; 8];
+        set_voxel(&mut world, 1, 0, 0, 1, world_dims[1], world_dims[2]);
+        set_voxel(&mut world, 2, 1, 0, 1, world_dims[1], world_dims[2]);
+
+        let left = copy_with_halo(&world, world_dims, [0, 0, 0], [2, 2, 1]);
+        let mesh_arrays = mesh_chunk_with_halo(&left, [2, 2, 1]);
+        assert_eq!(mesh_arrays.index_count, 36);
+        assert!(mesh_arrays.ao[..mesh_arrays.vertex_count].iter().any(|&ao| ao < 1.0));
+
+        let mut isolated = vec![0u8; 4];
+        set_voxel(&mut isolated, 1, 0, 0, 1, 2, 1);
+        let without_halo = mesh_with_materials(&isolated, 2, 2, 1, &[0]);
+        assert!(without_halo.ao[..without_halo.vertex_count].iter().all(|&ao| ao == 1.0));
+    }
+
+    #[test]
+    fn buffer_pointers_track_mesh_storage() {
+        let mut wasm_finder = WasmExteriorFacesFinder::new(1);
+        assert!(wasm_finder.get_vertices_ptr().is_null());
+        assert!(wasm_finder.get_indices_ptr().is_null());
+
+        let mapping = create_block_atlas_mapping(2);
+        assert!(wasm_finder
+            .find_exterior_faces(&[1], 4, &mapping, 1, 1, 1, &[], 0, 0, 0, true)
+            .is_ok());
+
+        let mesh_arrays = wasm_finder.mesh_arrays.as_ref().unwrap();
+        assert_eq!(wasm_finder.get_vertices_ptr(), mesh_arrays.vertices.as_ptr());
+        assert_eq!(wasm_finder.get_normals_ptr(), mesh_arrays.normals.as_ptr());
+        assert_eq!(wasm_finder.get_uvs_ptr(), mesh_arrays.uvs.as_ptr());
+        assert_eq!(wasm_finder.get_ao_ptr(), mesh_arrays.ao.as_ptr());
+        assert_eq!(wasm_finder.get_is_selected_ptr(), mesh_arrays.is_selected.as_ptr());
+        assert_eq!(wasm_finder.get_indices_ptr(), mesh_arrays.indices.as_ptr());
+
+        let view = unsafe {
+            std::slice::from_raw_parts(
+                wasm_finder.get_vertices_ptr(),
+                wasm_finder.get_vertex_count() * 3,
+            )
+        };
+        assert_eq!(view, wasm_finder.get_vertices().as_slice());
+    }
+
+    #[test]
+    fn packed_output_selectable_from_wasm_finder() {
+        let mut wasm_finder = WasmExteriorFacesFinder::new(1);
+        let mapping = create_block_atlas_mapping(2);
+        assert!(wasm_finder
+            .find_exterior_faces(&[1], 4, &mapping, 1, 1, 1, &[], 0, 0, 0, true)
+            .is_ok());
+        let float_vertices = wasm_finder.get_vertices();
+        assert!(wasm_finder.get_packed_vertices().is_empty());
+
+        wasm_finder.set_packed_output(true);
+        assert!(wasm_finder
+            .find_exterior_faces(&[1], 4, &mapping, 1, 1, 1, &[], 0, 0, 0, true)
+            .is_ok());
+        let packed = wasm_finder.get_packed_vertices();
+        assert_eq!(packed.len(), 24 * PACKED_WORDS_PER_VERTEX);
+        assert!(wasm_finder.get_vertices().is_empty());
+
+        for (v, words) in packed.chunks_exact(PACKED_WORDS_PER_VERTEX).enumerate() {
+            let decoded = packed_vertex::decode_packed_vertex([words[0], words[1]], 4);
+            assert_eq!(decoded.position, float_vertices[v * 3..v * 3 + 3]);
+        }
+    }
+
+    #[test]
+    fn mesh_arrays_grow_without_preallocated_capacity() {
+        let (dx, dy, dz) = (8, 8, 8);
+        let data = create_voxel_data(dx, dy, dz)
+            .iter()
+            .enumerate()
+            .map(|(i, _)| ((i + i / dz + i / (dy * dz)) % 2) as u8)
+            .collect::<Vec<_>>();
+        let mapping = create_block_atlas_mapping(2);
+        let mut finder = ExteriorFacesFinder::new(8);
+        let mut mesh_arrays = MeshArrays::new(0, 0);
+        finder.find_exterior_faces(
+            &data,
+            4,
+            &mapping,
+            dx,
+            dy,
+            dz,
+            &mut mesh_arrays,
+            &[],
+            0,
+            0,
+            0,
+            true,
+        );
+
+        assert_eq!(mesh_arrays.vertex_count, 256 * 6 * 4);
+        assert_eq!(mesh_arrays.index_count, 256 * 6 * 6);
+        assert!(mesh_arrays.vertex_capacity() >= mesh_arrays.vertex_count);
+
+        let mut wasm_finder = WasmExteriorFacesFinder::new(1);
+        assert!(wasm_finder
+            .find_exterior_faces(&data, 4, &mapping, dx, dy, dz, &[], 0, 0, 0, true)
+            .is_ok());
+        assert_eq!(wasm_finder.get_vertex_count(), mesh_arrays.vertex_count);
+        assert_eq!(wasm_finder.get_indices(), mesh_arrays.indices[..mesh_arrays.index_count]);
+    }
+
+    fn quad_triangles(mesh_arrays: &MeshArrays, quad: usize) -> [[usize; 3]; 2] {
+        let base = quad * 4;
+        let indices = &mesh_arrays.indices[quad * 6..quad * 6 + 6];
+        let local = |k: usize| indices[k] as usize - base;
+        [[local(0), local(1), local(2)], [local(3), local(4), local(5)]]
+    }
+
+    fn triangle_faces_normal(mesh_arrays: &MeshArrays, quad: usize, triangle: [usize; 3]) -> bool {
+        let position = |k: usize| {
+            let v = (quad * 4 + triangle[k]) * 3;
+            [mesh_arrays.vertices[v], mesh_arrays.vertices[v + 1], mesh_arrays.vertices[v + 2]]
+        };
+        let (p0, p1, p2) = (position(0), position(1), position(2));
+        let e1 = [p1[0] - p0[0], p1[1] - p0[1], p1[2] - p0[2]];
+        let e2 = [p2[0] - p0[0], p2[1] - p0[1], p2[2] - p0[2]];
+        let cross = [
+            e1[1] * e2[2] - e1[2] * e2[1],
+            e1[2] * e2[0] - e1[0] * e2[2],
+            e1[0] * e2[1] - e1[1] * e2[0],
+        ];
+        let n = quad * 4 * 3;
+        let normal = &mesh_arrays.normals[n..n + 3];
+        cross[0] * normal[0] + cross[1] * normal[1] + cross[2] * normal[2] > 0.0
+    }
+
+    #[test]
+    fn uniform_ao_keeps_default_diagonal() {
+        let mut data = create_voxel_data(1, 1, 1);
+        set_voxel(&mut data, 0, 0, 0, 1, 1, 1);
+        let mesh_arrays = mesh_with_materials(&data, 1, 1, 1, &[]);
+        for quad in 0..6 {
+            assert_eq!(quad_triangles(&mesh_arrays, quad), [[0, 1, 2], [0, 2, 3]]);
+        }
+    }
+
+    #[test]
+    fn quad_diagonal_avoids_occluded_corner() {
+        let (dx, dy, dz) = (3, 2, 3);
+        for (ox, oz) in [(0, 0), (2, 0), (0, 2), (2, 2)] {
+            let mut data = create_voxel_data(dx, dy, dz);
+            set_voxel(&mut data, 1, 0, 1, 1, dy, dz);
+            set_voxel(&mut data, ox, 1, oz, 1, dy, dz);
+            let mesh_arrays = mesh_with_materials(&data, dx, dy, dz, &[]);
+
+            let top_quad = (0..mesh_arrays.vertex_count / 4)
+                .find(|&quad| {
+                    let v = quad * 4 * 3;
+                    mesh_arrays.normals[v + 1] == 1.0
+                        && (0..4).all(|k| {
+                            let p = &mesh_arrays.vertices[v + k * 3..v + k * 3 + 3];
+                            p[1] == 1.0 && (1.0..=2.0).contains(&p[0]) && (1.0..=2.0).contains(&p[2])
+                        })
+                })
+                .unwrap();
+
+            let dark: Vec<usize> = (0..4)
+                .filter(|&k| mesh_arrays.ao[top_quad * 4 + k] < 1.0)
+                .collect();
+            assert_eq!(dark.len(), 1);
+
+            let [first, second] = quad_triangles(&mesh_arrays, top_quad);
+            assert!(!(first.contains(&dark[0]) && second.contains(&dark[0])));
+            assert!(triangle_faces_normal(&mesh_arrays, top_quad, first));
+            assert!(triangle_faces_normal(&mesh_arrays, top_quad, second));
+        }
+    }
+
+    #[test]
+    fn flipped_diagonals_keep_front_facing_winding() {
+        let (dx, dy, dz) = (5, 5, 5);
+        let data: Vec<u8> = (0..dx * dy * dz).map(|i| ((i * 7 + i / 3) % 5 < 2) as u8).collect();
+        let mesh_arrays = mesh_with_materials(&data, dx, dy, dz, &[]);
+
+        let mut flipped = 0;
+        for quad in 0..mesh_arrays.vertex_count / 4 {
+            let triangles = quad_triangles(&mesh_arrays, quad);
+            if triangles[0] == [1, 2, 3] {
+                assert_eq!(triangles[1], [1, 3, 0]);
+                flipped += 1;
+            } else {
+                assert_eq!(triangles, [[0, 1, 2], [0, 2, 3]]);
+            }
+            let ao = &mesh_arrays.ao[quad * 4..quad * 4 + 4];
+            assert_eq!(triangles[0] == [1, 2, 3], ao[0] + ao[2] < ao[1] + ao[3]);
+            for triangle in triangles {
+                assert!(triangle_faces_normal(&mesh_arrays, quad, triangle));
+            }
+        }
+        assert!(flipped > 0);
+    }
+
+    #[test]
+    fn disabled_ao_merges_walls_into_fewer_quads() {
+        let (dx, dy, dz) = (6, 4, 6);
+        let mut data = create_voxel_data(dx, dy, dz);
+        for x in 0..dx {
+            for z in 0..dz {
+                set_voxel(&mut data, x, 0, z, 1, dy, dz);
+            }
+        }
+        set_voxel(&mut data, 2, 1, 2, 1, dy, dz);
+        set_voxel(&mut data, 4, 1, 3, 1, dy, dz);
+        let mapping = create_block_atlas_mapping(2);
+
+        let mut finder = ExteriorFacesFinder::new(dx.max(dy).max(dz));
+        let mut with_ao = MeshArrays::new(0, 0);
+        finder.find_exterior_faces(&data, 4, &mapping, dx, dy, dz, &mut with_ao, &[], 0, 0, 0, true);
+
+        finder.set_ao_config(AoConfig::disabled());
+        let mut without_ao = MeshArrays::new(0, 0);
+        finder.find_exterior_faces(&data, 4, &mapping, dx, dy, dz, &mut without_ao, &[], 0, 0, 0, true);
+
+        assert!(without_ao.vertex_count < with_ao.vertex_count);
+        assert!(without_ao.ao[..without_ao.vertex_count].iter().all(|&ao| ao == 1.0));
+    }
+
+    #[test]
+    fn ao_curve_and_strength_shape_vertex_ao() {
+        let (dx, dy, dz) = (2, 2, 1);
+        let mut data = create_voxel_data(dx, dy, dz);
+        set_voxel(&mut data, 0, 0, 0, 1, dy, dz);
+        set_voxel(&mut data, 1, 1, 0, 1, dy, dz);
+        let mapping = create_block_atlas_mapping(2);
+        let mesh = |ao: AoConfig| {
+            let mut finder = ExteriorFacesFinder::new(2);
+            finder.set_ao_config(ao);
+            let mut mesh_arrays = MeshArrays::new(0, 0);
+            finder.find_exterior_faces(&data, 4, &mapping, dx, dy, dz, &mut mesh_arrays, &[], 0, 0, 0, true);
+            mesh_arrays
+        };
+
+        let default = mesh(AoConfig::default());
+        let curve = mesh(AoConfig {
+            levels: [1.0, 0.5, 0.25, 0.0],
+            ..AoConfig::default()
+        });
+        let half = mesh(AoConfig {
+            strength: 0.5,
+            ..AoConfig::default()
+        });
+        let none = mesh(AoConfig {
+            strength: 0.0,
+            ..AoConfig::default()
+        });
+
+        assert_eq!(curve.vertex_count, default.vertex_count);
+        assert_eq!(none.vertex_count, default.vertex_count);
+        for v in 0..default.vertex_count {
+            let level = OCCLUSION_LEVELS
+                .iter()
+                .position(|&level| (level - default.ao[v]).abs() < 1e-6)
+                .unwrap();
+            assert_eq!(curve.ao[v], [1.0, 0.5, 0.25, 0.0][level]);
+            assert!((half.ao[v] - (1.0 - (1.0 - default.ao[v]) * 0.5)).abs() < 1e-6);
+            assert_eq!(none.ao[v], 1.0);
+        }
+        assert!(default.ao[..default.vertex_count].iter().any(|&ao| ao < 1.0));
+    }
+
+    #[test]
+    fn ao_config_rejects_out_of_range_values() {
+        let mut finder = ExteriorFacesFinder::new(1);
+        for strength in [-0.5, 1.5, f32::NAN, f32::INFINITY] {
+            let ao = AoConfig {
+                strength,
+                ..AoConfig::default()
+            };
+            assert_eq!(
+                finder.try_set_ao_config(ao),
+                Err(validation::MeshingError::InvalidAoStrength)
+            );
+        }
+        let ao = AoConfig {
+            levels: [1.0, 0.5, f32::NAN, 0.0],
+            ..AoConfig::default()
+        };
+        assert_eq!(
+            finder.try_set_ao_config(ao),
+            Err(validation::MeshingError::InvalidAoLevel { index: 2 })
+        );
+        assert_eq!(finder.ao_config(), AoConfig::default());
+
+        let unchecked = AoConfig {
+            levels: [1.0, 1.5, -0.5, 0.0],
+            strength: 1.0,
+            enabled: true,
+        };
+        assert_eq!(unchecked.level(1), 1.0);
+        assert_eq!(unchecked.level(2), 0.0);
+    }
+
+    #[test]
+    fn wasm_ao_setters_update_config() {
+        let mut wasm_finder = WasmExteriorFacesFinder::new(1);
+        assert!(wasm_finder.set_ao_curve(&[1.0, 0.8, 0.6, 0.4]).is_ok());
+        assert_eq!(wasm_finder.finder.ao_config().levels, [1.0, 0.8, 0.6, 0.4]);
+        assert!(wasm_finder.set_ao_strength(0.5).is_ok());
+        wasm_finder.set_ao_enabled(false);
+        let ao = wasm_finder.finder.ao_config();
+        assert_eq!(ao.strength, 0.5);
+        assert!(!ao.enabled);
+    }
+
+    #[test]
+    fn smooth_mode_selectable_from_wasm_finder() {
+        let (dx, dy, dz) = (3, 3, 3);
+        let mut data = create_voxel_data(dx, dy, dz);
+        set_voxel(&mut data, 1, 1, 1, 2, dy, dz);
+        let mapping = create_block_atlas_mapping(3);
+
+        let mut wasm_finder = WasmExteriorFacesFinder::new(3);
+        wasm_finder.set_packed_output(true);
+        wasm_finder.set_smooth_mode(true);
+        assert!(wasm_finder
+            .find_exterior_faces(&data, 4, &mapping, dx, dy, dz, &[], 0, 0, 0, true)
+            .is_ok());
+        assert_eq!(wasm_finder.get_vertex_count(), 8);
+        assert_eq!(wasm_finder.get_index_count(), 36);
+        assert_eq!(wasm_finder.get_block_types(), vec![2.0; 8]);
+        assert_eq!(wasm_finder.get_normals().len(), 24);
+
+        wasm_finder.set_smooth_mode(false);
+        assert!(wasm_finder
+            .find_exterior_faces(&data, 4, &mapping, dx, dy, dz, &[], 0, 0, 0, true)
+            .is_ok());
+        assert_eq!(wasm_finder.get_vertex_count(), 24);
+        assert!(wasm_finder.get_block_types().is_empty());
+    }
+
+    #[test]
+    fn face_index_ranges_group_quads_by_direction() {
+        let (dx, dy, dz) = (5, 4, 3);
+        let data: Vec<u8> = (0..dx * dy * dz).map(|i| ((i * 5 + i / 4) % 3) as u8).collect();
+        let mesh_arrays = mesh_with_materials(&data, dx, dy, dz, &[0, 2]);
+        assert!(mesh_arrays.translucent_index_start < mesh_arrays.index_count);
+
+        for (ranges, section) in [
+            (mesh_arrays.face_index_ranges, (0, mesh_arrays.translucent_index_start)),
+            (
+                mesh_arrays.translucent_face_index_ranges,
+                (mesh_arrays.translucent_index_start, mesh_arrays.index_count),
+            ),
+        ] {
+            let mut covered = 0;
+            for (face_dir, &(start, end)) in ranges.iter().enumerate() {
+                assert!(section.0 <= start && start <= end && end <= section.1);
+                covered += end - start;
+                for &index in &mesh_arrays.indices[start..end] {
+                    let n = index as usize * 3;
+                    assert_eq!(
+                        mesh_arrays.normals[n..n + 3],
+                        voxel_constants::FACES[face_dir].normal
+                    );
+                }
+            }
+            assert_eq!(covered, section.1 - section.0);
+        }
+
+        let mut wasm_finder = WasmExteriorFacesFinder::new(1);
+        assert!(wasm_finder.set_block_materials(&[0, 2]).is_ok());
+        let mapping = create_block_atlas_mapping(3);
+        assert!(wasm_finder
+            .find_exterior_faces(&data, 4, &mapping, dx, dy, dz, &[], 0, 0, 0, true)
+            .is_ok());
+        let ranges = wasm_finder.get_face_index_ranges();
+        assert_eq!(ranges.len(), 12);
+        let (start, end) = mesh_arrays.face_index_ranges[1];
+        assert_eq!(ranges[2..4], [start as u32, end as u32]);
+        assert_eq!(wasm_finder.get_translucent_face_index_ranges().len(), 12);
+    }
+
+    fn mesh_tiled_box(dims: [usize; 3], block_type: u8) -> MeshArrays {
+        let [dx, dy, dz] = dims;
+        let data = vec![block_type; dx * dy * dz];
+        let mut finder = ExteriorFacesFinder::new(dx.max(dy).max(dz));
+        finder.set_uv_mode(UvMode::Tiled);
+        let mut mesh_arrays = MeshArrays::new(0, 0);
+        finder.find_exterior_faces(
+            &data,
+            4,
+            &create_block_atlas_mapping(block_type as usize),
+            dx,
+            dy,
+            dz,
+            &mut mesh_arrays,
+            &[],
+            0,
+            0,
+            0,
+            true,
+        );
+        mesh_arrays
+    }
+
+    #[test]
+    fn tiled_uvs_span_quad_extent_on_every_face() {
+        let (dx, dy, dz) = (3, 2, 5);
+        let mesh_arrays = mesh_tiled_box([dx, dy, dz], 6);
+        assert_eq!(mesh_arrays.vertex_count, 24);
+        let expected_origin = texture_coords::get_tile_origin(5, 4);
+        assert_eq!(expected_origin, [0.25, 0.5]);
+
+        for quad in 0..6 {
+            let face_dir = mesh_arrays.face_index_ranges.iter().position(|&(start, end)| {
+                (start..end).contains(&(quad * 6))
+            });
+            let extent = match face_dir.unwrap() / 2 {
+                0 => [dz, dy],
+                1 => [dx, dz],
+                _ => [dx, dy],
+            };
+            let uvs = &mesh_arrays.uvs[quad * 8..quad * 8 + 8];
+            let max_u = uvs.iter().step_by(2).fold(0.0f32, |a, &b| a.max(b));
+            let max_v = uvs.iter().skip(1).step_by(2).fold(0.0f32, |a, &b| a.max(b));
+            assert_eq!(uvs.iter().fold(f32::MAX, |a, &b| a.min(b)), 0.0);
+            assert_eq!([max_u, max_v], [extent[0] as f32, extent[1] as f32]);
+            for vertex in quad * 4..quad * 4 + 4 {
+                assert_eq!(mesh_arrays.tile_origins[vertex * 2..vertex * 2 + 2], expected_origin);
+            }
+        }
+    }
+
+    #[test]
+    fn tiled_uv_orientation_is_consistent_across_faces() {
+        let mesh_arrays = mesh_tiled_box([2, 3, 4], 1);
+        let position = |v: usize| {
+            [
+                mesh_arrays.vertices[v * 3],
+                mesh_arrays.vertices[v * 3 + 1],
+                mesh_arrays.vertices[v * 3 + 2],
+            ]
+        };
+        let uv = |v: usize| [mesh_arrays.uvs[v * 2], mesh_arrays.uvs[v * 2 + 1]];
+
+        for quad in 0..6 {
+            let normal = &mesh_arrays.normals[quad * 12..quad * 12 + 3];
+            for triangle in quad_triangles(&mesh_arrays, quad) {
+                let [v0, v1, v2] = triangle.map(|k| quad * 4 + k);
+                let (p0, p1, p2) = (position(v0), position(v1), position(v2));
+                let (t0, t1, t2) = (uv(v0), uv(v1), uv(v2));
+                let e1 = [p1[0] - p0[0], p1[1] - p0[1], p1[2] - p0[2]];
+                let e2 = [p2[0] - p0[0], p2[1] - p0[1], p2[2] - p0[2]];
+                let (du1, dv1) = (t1[0] - t0[0], t1[1] - t0[1]);
+                let (du2, dv2) = (t2[0] - t0[0], t2[1] - t0[1]);
+                let r = 1.0 / (du1 * dv2 - du2 * dv1);
+                let tangent: [f32; 3] = std::array::from_fn(|a| (e1[a] * dv2 - e2[a] * dv1) * r);
+                let bitangent: [f32; 3] = std::array::from_fn(|a| (e2[a] * du1 - e1[a] * du2) * r);
+                let cross = [
+                    tangent[1] * bitangent[2] - tangent[2] * bitangent[1],
+                    tangent[2] * bitangent[0] - tangent[0] * bitangent[2],
+                    tangent[0] * bitangent[1] - tangent[1] * bitangent[0],
+                ];
+                assert_eq!(cross, [normal[0], normal[1], normal[2]]);
+                if normal[1] == 0.0 {
+                    assert_eq!(bitangent, [0.0, 1.0, 0.0]);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn tile_origins_exposed_only_in_tiled_mode() {
+        let data = vec![1u8; 8];
+        let mapping = create_block_atlas_mapping(1);
+        let mut wasm_finder = WasmExteriorFacesFinder::new(2);
+        assert!(wasm_finder
+            .find_exterior_faces(&data, 4, &mapping, 2, 2, 2, &[], 0, 0, 0, true)
+            .is_ok());
+        assert!(wasm_finder.get_tile_origins().is_empty());
+        let atlas_uvs = wasm_finder.get_uvs();
+        assert!(atlas_uvs.chunks(2).all(|uv| uv == &atlas_uvs[..2]));
+
+        wasm_finder.set_tiled_uvs(true);
+        assert!(wasm_finder
+            .find_exterior_faces(&data, 4, &mapping, 2, 2, 2, &[], 0, 0, 0, true)
+            .is_ok());
+        let origins = wasm_finder.get_tile_origins();
+        assert_eq!(origins.len(), wasm_finder.get_vertex_count() * 2);
+        assert!(origins.chunks(2).all(|origin| origin == [0.0, 0.75]));
+        assert!(wasm_finder.get_uvs().contains(&2.0));
+    }
+
+    #[test]
+    fn tiled_uvs_reject_packed_output() {
+        let data = vec![1u8; 8];
+        let mapping = create_block_atlas_mapping(1);
+        let mut finder = ExteriorFacesFinder::new(2);
+        finder.set_uv_mode(UvMode::Tiled);
+        let mut packed = MeshArrays::new_packed(0, 0);
+        assert_eq!(
+            finder.try_find_exterior_faces(
+                &data,
+                4,
+                &mapping,
+                2,
+                2,
+                2,
+                &mut packed,
+                &[],
+                0,
+                0,
+                0,
+                true
+            ),
+            Err(validation::MeshingError::UnsupportedPackedOption {
+                option: "tiled UVs"
+            })
+        );
+        assert_eq!(packed.vertex_count, 0);
+
+        finder.set_uv_mode(UvMode::Atlas);
+        assert!(
+            finder
+                .try_find_exterior_faces(
+                    &data,
+                    4,
+                    &mapping,
+                    2,
+                    2,
+                    2,
+                    &mut packed,
+                    &[],
+                    0,
+                    0,
+                    0,
+                    true
+                )
+                .is_ok()
+        );
+        assert_eq!(packed.vertex_count, 24);
+    }
+
+    #[test]
+    fn wasm_incremental_mesher_matches_finder() {
+        let (dx, dy, dz) = (3, 3, 2);
+        let mut data = create_voxel_data(dx, dy, dz);
+        set_voxel(&mut data, 0, 0, 0, 1, dy, dz);
+        set_voxel(&mut data, 1, 0, 0, 2, dy, dz);
+        let mapping = create_block_atlas_mapping(3);
+
+        let mut mesher = WasmIncrementalMesher::new(3);
+        assert!(mesher.set_block_materials(&[0, 2]).is_ok());
+        assert!(mesher
+            .remesh_full(&data, 4, &mapping, dx, dy, dz, &[], 0, 0, 0, true)
+            .is_ok());
+
+        set_voxel(&mut data, 2, 2, 1, 1, dy, dz);
+        assert!(mesher
+            .remesh_region(&data, 4, &mapping, dx, dy, dz, 2, 2, 1, 2, 2, 1, &[], 0, 0, 0, true)
+            .is_ok());
+
+        let mut finder = WasmExteriorFacesFinder::new(3);
+        assert!(finder.set_block_materials(&[0, 2]).is_ok());
+        assert!(finder
+            .find_exterior_faces(&data, 4, &mapping, dx, dy, dz, &[], 0, 0, 0, true)
+            .is_ok());
+        assert_eq!(mesher.get_vertex_count(), finder.get_vertex_count());
+        assert_eq!(mesher.get_indices(), finder.get_indices());
+        assert_eq!(mesher.get_vertices(), finder.get_vertices());
+        assert_eq!(mesher.get_face_index_ranges(), finder.get_face_index_ranges());
+        assert_eq!(
+            mesher.get_translucent_face_index_ranges(),
+            finder.get_translucent_face_index_ranges()
+        );
+    }
+
+    #[test]
+    fn optional_vertex_buffers_follow_their_mode() {
+        let data = vec![1u8; 8];
+        let mapping = create_block_atlas_mapping(1);
+        let mesh = |finder: &mut ExteriorFacesFinder| {
+            let mut mesh_arrays = MeshArrays::new(0, 0);
+            finder.find_exterior_faces(&data, 4, &mapping, 2, 2, 2, &mut mesh_arrays, &[], 0, 0, 0, true);
+            mesh_arrays
+        };
+        let buffer_lens =
+            |m: &MeshArrays| [m.colors.len(), m.tile_origins.len(), m.block_types.len()];
+
+        let mut finder = ExteriorFacesFinder::new(2);
+        let plain = mesh(&mut finder);
+        assert_eq!(plain.vertex_count, 24);
+        assert_eq!(buffer_lens(&plain), [0, 0, 0]);
+
+        finder.set_color_palette(Some(ColorPalette::from_hex_rgb(&[0xFF0000])));
+        let colored = mesh(&mut finder);
+        assert!(colored.colors.len() >= colored.vertex_count * 4);
+        assert_eq!(buffer_lens(&colored)[1..], [0, 0]);
+
+        finder.set_color_palette(None);
+        finder.set_uv_mode(UvMode::Tiled);
+        let tiled = mesh(&mut finder);
+        assert!(tiled.tile_origins.len() >= tiled.vertex_count * 2);
+        assert_eq!(buffer_lens(&tiled)[0], 0);
+        assert_eq!(buffer_lens(&tiled)[2], 0);
+
+        let mut merged = MeshArrays::new(0, 0);
+        merged.append(&plain);
+        assert_eq!(buffer_lens(&merged), [0, 0, 0]);
+        merged.append(&colored);
+        assert_eq!(
+            merged.colors[plain.vertex_count * 4..merged.vertex_count * 4],
+            colored.colors[..colored.vertex_count * 4]
+        );
+    }
+
+    #[test]
+    fn export_before_meshing_writes_an_empty_file() {
+        let wasm_finder = WasmExteriorFacesFinder::new(1);
+        let stl = wasm_finder.export_mesh(0, 1.0, 0).ok().unwrap();
+        assert_eq!(stl.len(), 84);
+        assert_eq!(stl[80..], [0; 4]);
+    }
+
+    #[test]
+    fn custom_ao_rejects_packed_output() {
+        let data = vec![1u8; 8];
+        let mapping = create_block_atlas_mapping(1);
+        let mesh = |ao: AoConfig| {
+            let mut finder = ExteriorFacesFinder::new(2);
+            finder.try_set_ao_config(ao).unwrap();
+            let mut packed = MeshArrays::new_packed(0, 0);
+            finder.try_find_exterior_faces(&data, 4, &mapping, 2, 2, 2, &mut packed, &[], 0, 0, 0, true)
+        };
+
+        let unsupported = Err(validation::MeshingError::UnsupportedPackedOption {
+            option: "custom AO curve",
+        });
+        assert_eq!(
+            mesh(AoConfig {
+                strength: 0.5,
+                ..AoConfig::default()
+            }),
+            unsupported
+        );
+        assert_eq!(
+            mesh(AoConfig {
+                levels: [1.0, 0.8, 0.6, 0.4],
+                ..AoConfig::default()
+            }),
+            unsupported
+        );
+        assert!(mesh(AoConfig::default()).is_ok());
+        assert!(
+            mesh(AoConfig {
+                levels: [1.0, 0.8, 0.6, 0.4],
+                ..AoConfig::disabled()
+            })
+            .is_ok()
+        );
+    }
+
+    #[test]
+    fn palette_colors_split_quads_by_block_type() {
+        let (dx, dy, dz) = (2, 1, 1);
+        let mut data = create_voxel_data(dx, dy, dz);
+        set_voxel(&mut data, 0, 0, 0, 1, dy, dz);
+        set_voxel(&mut data, 1, 0, 0, 2, dy, dz);
+        let shared_texture = expand_block_atlas_mapping(&[3, 3]);
+
+        let mut wasm_finder = WasmExteriorFacesFinder::new(2);
+        assert!(wasm_finder
+            .find_exterior_faces(&data, 4, &shared_texture, dx, dy, dz, &[], 0, 0, 0, true)
+            .is_ok());
+        assert_eq!(wasm_finder.get_vertex_count(), 24);
+        assert!(wasm_finder.get_colors().is_empty());
+
+        assert!(wasm_finder
+            .set_color_palette(&[1.0, 0.0, 0.0, 1.0, 0.0, 0.0, 1.0, 0.5], false)
+            .is_ok());
+        assert!(wasm_finder
+            .find_exterior_faces(&data, 4, &shared_texture, dx, dy, dz, &[], 0, 0, 0, true)
+            .is_ok());
+        assert_eq!(wasm_finder.get_vertex_count(), 40);
+
+        let vertices = wasm_finder.get_vertices();
+        let colors = wasm_finder.get_colors();
+        assert_eq!(colors.len(), 40 * 4);
+        for quad in 0..10 {
+            let quad_vertices = &vertices[quad * 12..quad * 12 + 12];
+            let center_x = quad_vertices.iter().step_by(3).sum::<f32>() / 4.0;
+            let expected = if center_x < 1.0 {
+                [1.0, 0.0, 0.0, 1.0]
+            } else {
+                [0.0, 0.0, 1.0, 0.5]
+            };
+            for vertex in quad * 4..quad * 4 + 4 {
+                assert_eq!(colors[vertex * 4..vertex * 4 + 4], expected);
+            }
+        }
+
+        wasm_finder.clear_color_palette();
+        assert!(wasm_finder
+            .find_exterior_faces(&data, 4, &shared_texture, dx, dy, dz, &[], 0, 0, 0, true)
+            .is_ok());
+        assert_eq!(wasm_finder.get_vertex_count(), 24);
+    }
+
+    #[test]
+    fn palette_colors_premultiply_vertex_ao() {
+        let (dx, dy, dz) = (3, 2, 3);
+        let mut data = create_voxel_data(dx, dy, dz);
+        for x in 0..dx {
+            for z in 0..dz {
+                set_voxel(&mut data, x, 0, z, 1, dy, dz);
+            }
+        }
+        set_voxel(&mut data, 1, 1, 1, 1, dy, dz);
+        let mapping = create_block_atlas_mapping(1);
+
+        let mut wasm_finder = WasmExteriorFacesFinder::new(3);
+        wasm_finder.set_color_palette_hex(&[0xFFFFFF], true);
+        assert!(wasm_finder
+            .find_exterior_faces(&data, 4, &mapping, dx, dy, dz, &[], 0, 0, 0, true)
+            .is_ok());
+        let ao = wasm_finder.get_ao();
+        let colors = wasm_finder.get_colors();
+        assert!(ao.iter().any(|&level| level < 1.0));
+        for (vertex, &level) in ao.iter().enumerate() {
+            assert_eq!(colors[vertex * 4..vertex * 4 + 4], [level, level, level, 1.0]);
+        }
+    }
+
+    #[test]
+    fn palette_meshes_need_no_atlas_and_reject_packed_output() {
+        let (dx, dy, dz) = (2, 1, 1);
+        let mut data = create_voxel_data(dx, dy, dz);
+        set_voxel(&mut data, 0, 0, 0, 1, dy, dz);
+        set_voxel(&mut data, 1, 0, 0, 2, dy, dz);
+
+        let mut finder = ExteriorFacesFinder::new(2);
+        let mut mesh_arrays = MeshArrays::new(0, 0);
+        assert!(matches!(
+            finder.try_find_exterior_faces(
+                &data,
+                0,
+                &[],
+                dx,
+                dy,
+                dz,
+                &mut mesh_arrays,
+                &[],
+                0,
+                0,
+                0,
+                true
+            ),
+            Err(validation::MeshingError::InvalidTextureWidth { .. })
+        ));
+
+        finder.set_color_palette(Some(ColorPalette::from_hex_rgb(&[0xFF0000, 0x0000FF])));
+        finder
+            .try_find_exterior_faces(
+                &data,
+                0,
+                &[],
+                dx,
+                dy,
+                dz,
+                &mut mesh_arrays,
+                &[],
+                0,
+                0,
+                0,
+                true,
+            )
+            .unwrap();
+        assert_eq!(mesh_arrays.vertex_count, 40);
+        let colors = &mesh_arrays.colors[..40 * 4];
+        assert!(
+            colors
+                .chunks_exact(4)
+                .any(|color| color == [1.0, 0.0, 0.0, 1.0])
+        );
+        assert!(
+            colors
+                .chunks_exact(4)
+                .any(|color| color == [0.0, 0.0, 1.0, 1.0])
+        );
+
+        let mut packed = MeshArrays::new_packed(0, 0);
+        let mapping = create_block_atlas_mapping(2);
+        assert_eq!(
+            finder.try_find_exterior_faces(
+                &data,
+                4,
+                &mapping,
+                dx,
+                dy,
+                dz,
+                &mut packed,
+                &[],
+                0,
+                0,
+                0,
+                true
+            ),
+            Err(validation::MeshingError::UnsupportedPackedOption {
+                option: "color palette"
+            })
+        );
+    }
+
+    #[test]
+    fn quad_ids_resolve_to_face_footprints() {
+        let (dx, dy, dz) = (5, 4, 3);
+        let data: Vec<u8> = (0..dx * dy * dz).map(|i| ((i * 7 + i / 3) % 3) as u8).collect();
+        let mapping = create_block_atlas_mapping(2);
+
+        for backend in [MeshingBackend::Scalar, MeshingBackend::Bitmask] {
+            let mut finder = ExteriorFacesFinder::new(5);
+            finder.set_backend(backend);
+            finder.set_block_materials(&[0, 2]).unwrap();
+            finder.set_picking(true);
+            let mut mesh_arrays = MeshArrays::new(0, 0);
+            finder.find_exterior_faces(
+                &data,
+                4,
+                &mapping,
+                dx,
+                dy,
+                dz,
+                &mut mesh_arrays,
+                &[],
+                0,
+                0,
+                0,
+                true,
+            );
+            assert!(mesh_arrays.translucent_vertex_start < mesh_arrays.vertex_count);
+            assert_eq!(mesh_arrays.pick_quads.len(), mesh_arrays.vertex_count / 4);
+
+            for vertex in 0..mesh_arrays.vertex_count {
+                let quad_id = mesh_arrays.quad_ids[vertex];
+                assert_eq!(quad_id as usize, vertex / 4);
+                let quad = mesh_arrays.pick_quads[quad_id as usize];
+                let normal = &mesh_arrays.normals[vertex * 3..vertex * 3 + 3];
+                assert_eq!(voxel_constants::FACES[quad.face_dir as usize].normal, normal);
+                for axis in 0..3 {
+                    let position = mesh_arrays.vertices[vertex * 3 + axis] as u32;
+                    assert!(position >= quad.min[axis] && position <= quad.max[axis]);
+                }
+                let voxel = quad.voxel_at([0.0; 3]).map(|c| c as usize);
+                assert_ne!(data[voxel[0] * dy * dz + voxel[1] * dz + voxel[2]], 0);
+            }
+        }
+    }
+
+    #[test]
+    fn pick_voxel_resolves_hit_inside_merged_quad() {
+        let (dx, dy, dz) = (4, 1, 1);
+        let data = vec![1u8; dx * dy * dz];
+        let mapping = create_block_atlas_mapping(1);
+        let mut wasm_finder = WasmExteriorFacesFinder::new(4);
+        wasm_finder.set_picking_enabled(true);
+        assert!(wasm_finder
+            .find_exterior_faces(&data, 4, &mapping, dx, dy, dz, &[], 0, 0, 0, true)
+            .is_ok());
+
+        let quad_ids = wasm_finder.get_quad_ids();
+        let normals = wasm_finder.get_normals();
+        let top_vertex = (0..quad_ids.len())
+            .find(|&v| normals[v * 3 + 1] == 1.0)
+            .unwrap();
+        let top_quad = quad_ids[top_vertex];
+        assert_eq!(wasm_finder.pick_voxel(top_quad, 2.5, 1.0, 0.5), Some(vec![2, 0, 0, 2]));
+        assert_eq!(wasm_finder.pick_voxel(top_quad, 4.0, 1.0, 1.0), Some(vec![3, 0, 0, 2]));
+        assert_eq!(wasm_finder.pick_voxel(quad_ids.len() as u32, 0.0, 0.0, 0.0), None);
+
+        wasm_finder.set_picking_enabled(false);
+        assert!(wasm_finder
+            .find_exterior_faces(&data, 4, &mapping, dx, dy, dz, &[], 0, 0, 0, true)
+            .is_ok());
+        assert!(wasm_finder.get_quad_ids().is_empty());
+        assert_eq!(wasm_finder.pick_voxel(top_quad, 2.5, 1.0, 0.5), None);
+    }
 }
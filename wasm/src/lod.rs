@@ -0,0 +1,202 @@
+pub const LOD_FACTORS: [usize; 4] = [1, 2, 4, 8];
+
+pub fn lod_dims(dims: [usize; 3], factor: usize) -> [usize; 3] {
+    dims.map(|dim| dim.div_ceil(factor))
+}
+
+pub fn downsample_voxels(
+    voxel_data: &[u8],
+    dims: [usize; 3],
+    factor: usize,
+    output: &mut Vec<u8>,
+) -> [usize; 3] {
+    let out_dims = lod_dims(dims, factor);
+    output.clear();
+    output.resize(out_dims[0] * out_dims[1] * out_dims[2], 0);
+
+    let mut counts = [0u16; 128];
+    for ox in 0..out_dims[0] {
+        for oy in 0..out_dims[1] {
+            for oz in 0..out_dims[2] {
+                counts.fill(0);
+                let mut total = 0usize;
+                let mut solid = 0usize;
+                for x in ox * factor..((ox + 1) * factor).min(dims[0]) {
+                    for y in oy * factor..((oy + 1) * factor).min(dims[1]) {
+                        let row = x * dims[1] * dims[2] + y * dims[2];
+                        let z_end = ((oz + 1) * factor).min(dims[2]);
+                        for &value in &voxel_data[row + oz * factor..row + z_end] {
+                            let block_type = value & 0x7F;
+                            total += 1;
+                            if block_type != 0 {
+                                solid += 1;
+                                counts[block_type as usize] += 1;
+                            }
+                        }
+                    }
+                }
+
+                if solid * 2 < total {
+                    continue;
+                }
+                let mut dominant = 0;
+                for block_type in 1..counts.len() {
+                    if counts[block_type] > counts[dominant] {
+                        dominant = block_type;
+                    }
+                }
+                output[ox * out_dims[1] * out_dims[2] + oy * out_dims[2] + oz] = dominant as u8;
+            }
+        }
+    }
+
+    out_dims
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::find_exterior_faces::ExteriorFacesFinder;
+    use crate::mesh_arrays::MeshArrays;
+    use crate::texture_coords::expand_block_atlas_mapping;
+    use crate::validation::MeshingError;
+
+    #[test]
+    fn majority_keeps_dominant_block_type() {
+        let mut data = vec![0u8; 8];
+        data[..3].fill(2);
+        data[3] = 0x81;
+        let mut output = Vec::new();
+        assert_eq!(downsample_voxels(&data, [2, 2, 2], 2, &mut output), [1, 1, 1]);
+        assert_eq!(output, [2]);
+
+        data[..4].copy_from_slice(&[3, 3, 0x81, 1]);
+        assert_eq!(downsample_voxels(&data, [2, 2, 2], 2, &mut output), [1, 1, 1]);
+        assert_eq!(output, [1]);
+
+        data[0] = 0;
+        downsample_voxels(&data, [2, 2, 2], 2, &mut output);
+        assert_eq!(output, [0]);
+    }
+
+    #[test]
+    fn partial_cells_use_in_bounds_voxels() {
+        let data = vec![0, 0, 0, 0, 5];
+        let mut output = Vec::new();
+        assert_eq!(downsample_voxels(&data, [1, 1, 5], 4, &mut output), [1, 1, 2]);
+        assert_eq!(output, [0, 5]);
+    }
+
+    #[test]
+    fn lod_mesh_scales_downsampled_faces() {
+        let dims = [8, 8, 8];
+        let data = vec![1u8; 512];
+        let mapping = expand_block_atlas_mapping(&[3]);
+        let mut finder = ExteriorFacesFinder::new(8);
+
+        for factor in [2, 4, 8] {
+            let mut mesh_arrays = MeshArrays::new(0, 0);
+            finder
+                .try_find_exterior_faces_lod(
+                    &data,
+                    4,
+                    &mapping,
+                    dims[0],
+                    dims[1],
+                    dims[2],
+                    factor,
+                    &mut mesh_arrays,
+                )
+                .unwrap();
+
+            assert_eq!(mesh_arrays.index_count, 36);
+            let positions = &mesh_arrays.vertices[..mesh_arrays.vertex_count * 3];
+            assert!(positions.iter().all(|&p| p == 0.0 || p == 8.0));
+
+            let mut full = MeshArrays::new(0, 0);
+            finder.find_exterior_faces(&data, 4, &mapping, 8, 8, 8, &mut full, &[], 0, 0, 0, true);
+            assert_eq!(mesh_arrays.uvs[..48], full.uvs[..48]);
+        }
+    }
+
+    #[test]
+    fn packed_lod_mesh_scales_positions() {
+        let data = vec![1u8; 64];
+        let mapping = expand_block_atlas_mapping(&[0]);
+        let mut finder = ExteriorFacesFinder::new(4);
+        let mut float = MeshArrays::new(0, 0);
+        let mut packed = MeshArrays::new_packed(0, 0);
+        finder.find_exterior_faces_lod(&data, 4, &mapping, 4, 4, 4, 2, &mut float);
+        finder.find_exterior_faces_lod(&data, 4, &mapping, 4, 4, 4, 2, &mut packed);
+
+        for v in 0..float.vertex_count {
+            let words = [packed.packed[v * 2], packed.packed[v * 2 + 1]];
+            let decoded = crate::packed_vertex::decode_packed_vertex(words, 4);
+            assert_eq!(decoded.position, float.vertices[v * 3..v * 3 + 3]);
+        }
+    }
+
+    #[test]
+    fn partial_cells_clamp_to_original_dims() {
+        let dims = [10, 6, 10];
+        let data = vec![1u8; 600];
+        let mapping = expand_block_atlas_mapping(&[0]);
+        let mut finder = ExteriorFacesFinder::new(10);
+        let mut float = MeshArrays::new(0, 0);
+        let mut packed = MeshArrays::new_packed(0, 0);
+        finder.find_exterior_faces_lod(&data, 4, &mapping, 10, 6, 10, 4, &mut float);
+        finder.find_exterior_faces_lod(&data, 4, &mapping, 10, 6, 10, 4, &mut packed);
+
+        for (axis, &dim) in dims.iter().enumerate() {
+            let max = (0..float.vertex_count)
+                .map(|v| float.vertices[v * 3 + axis])
+                .fold(0.0, f32::max);
+            assert_eq!(max, dim as f32);
+        }
+        for v in 0..float.vertex_count {
+            let words = [packed.packed[v * 2], packed.packed[v * 2 + 1]];
+            let decoded = crate::packed_vertex::decode_packed_vertex(words, 4);
+            assert_eq!(decoded.position, float.vertices[v * 3..v * 3 + 3]);
+        }
+
+        let tall = vec![1u8; 4095];
+        finder
+            .try_find_exterior_faces_lod(&tall, 4, &mapping, 1, 1, 4095, 8, &mut packed)
+            .unwrap();
+        let max_z = packed.packed[..packed.vertex_count * 2]
+            .chunks_exact(2)
+            .map(|words| crate::packed_vertex::decode_packed_vertex([words[0], words[1]], 4))
+            .map(|decoded| decoded.position[2])
+            .fold(0.0, f32::max);
+        assert_eq!(max_z, 4095.0);
+    }
+
+    #[test]
+    fn rejects_unsupported_lod_factor() {
+        let mapping = expand_block_atlas_mapping(&[0]);
+        let mut finder = ExteriorFacesFinder::new(2);
+        let mut mesh_arrays = MeshArrays::new(0, 0);
+        assert_eq!(
+            finder.try_find_exterior_faces_lod(&[1; 8], 4, &mapping, 2, 2, 2, 3, &mut mesh_arrays),
+            Err(MeshingError::InvalidLodFactor { factor: 3 })
+        );
+    }
+    #[test]
+    fn tiled_uvs_reject_downsampled_lods() {
+        let mapping = expand_block_atlas_mapping(&[0]);
+        let mut finder = ExteriorFacesFinder::new(2);
+        finder.set_uv_mode(crate::texture_coords::UvMode::Tiled);
+        let mut mesh_arrays = MeshArrays::new(0, 0);
+        assert_eq!(
+            finder.try_find_exterior_faces_lod(&[1; 8], 4, &mapping, 2, 2, 2, 2, &mut mesh_arrays),
+            Err(MeshingError::UnsupportedLodOption {
+                option: "tiled UVs"
+            })
+        );
+        assert_eq!(mesh_arrays.vertex_count, 0);
+        assert!(finder
+            .try_find_exterior_faces_lod(&[1; 8], 4, &mapping, 2, 2, 2, 1, &mut mesh_arrays)
+            .is_ok());
+        assert_eq!(mesh_arrays.vertex_count, 24);
+    }
+}
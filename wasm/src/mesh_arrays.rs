@@ -1,36 +1,314 @@
+use std::ops::Range;
+
+use crate::packed_vertex::{
+    scale_packed_position, translate_packed_position, PACKED_WORDS_PER_VERTEX,
+};
+use crate::picking::{PickHit, PickQuad};
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum VertexFormat {
+    Float,
+    Packed,
+}
+
 pub struct MeshArrays {
+    pub format: VertexFormat,
     pub vertices: Vec<f32>,
     pub normals: Vec<f32>,
     pub uvs: Vec<f32>,
     pub ao: Vec<f32>,
     pub is_selected: Vec<f32>,
+    pub block_types: Vec<f32>,
+    pub tile_origins: Vec<f32>,
+    pub colors: Vec<f32>,
+    pub packed: Vec<u32>,
+    pub quad_ids: Vec<u32>,
+    pub pick_quads: Vec<PickQuad>,
     pub indices: Vec<u32>,
     pub vertex_count: usize,
     pub index_count: usize,
+    pub translucent_vertex_start: usize,
+    pub translucent_index_start: usize,
+    pub face_index_ranges: [(usize, usize); 6],
+    pub translucent_face_index_ranges: [(usize, usize); 6],
 }
 
 impl MeshArrays {
     pub fn new(max_vertices: usize, max_indices: usize) -> Self {
         Self {
+            format: VertexFormat::Float,
             vertices: vec![0.0; max_vertices * 3],
             normals: vec![0.0; max_vertices * 3],
             uvs: vec![0.0; max_vertices * 2],
             ao: vec![0.0; max_vertices],
             is_selected: vec![0.0; max_vertices],
+            block_types: Vec::new(),
+            tile_origins: Vec::new(),
+            colors: Vec::new(),
+            packed: Vec::new(),
+            quad_ids: Vec::new(),
+            pick_quads: Vec::new(),
+            indices: vec![0; max_indices],
+            vertex_count: 0,
+            index_count: 0,
+            translucent_vertex_start: 0,
+            translucent_index_start: 0,
+            face_index_ranges: [(0, 0); 6],
+            translucent_face_index_ranges: [(0, 0); 6],
+        }
+    }
+
+    pub fn new_packed(max_vertices: usize, max_indices: usize) -> Self {
+        Self {
+            format: VertexFormat::Packed,
+            vertices: Vec::new(),
+            normals: Vec::new(),
+            uvs: Vec::new(),
+            ao: Vec::new(),
+            is_selected: Vec::new(),
+            block_types: Vec::new(),
+            tile_origins: Vec::new(),
+            colors: Vec::new(),
+            packed: vec![0; max_vertices * PACKED_WORDS_PER_VERTEX],
+            quad_ids: Vec::new(),
+            pick_quads: Vec::new(),
             indices: vec![0; max_indices],
             vertex_count: 0,
             index_count: 0,
+            translucent_vertex_start: 0,
+            translucent_index_start: 0,
+            face_index_ranges: [(0, 0); 6],
+            translucent_face_index_ranges: [(0, 0); 6],
         }
     }
 
+    pub fn with_format(format: VertexFormat, max_vertices: usize, max_indices: usize) -> Self {
+        match format {
+            VertexFormat::Float => Self::new(max_vertices, max_indices),
+            VertexFormat::Packed => Self::new_packed(max_vertices, max_indices),
+        }
+    }
+
+    #[inline(always)]
+    pub fn is_packed(&self) -> bool {
+        self.format == VertexFormat::Packed
+    }
+
     pub fn reset(&mut self) {
         self.vertex_count = 0;
         self.index_count = 0;
+        self.translucent_vertex_start = 0;
+        self.translucent_index_start = 0;
+        self.face_index_ranges = [(0, 0); 6];
+        self.translucent_face_index_ranges = [(0, 0); 6];
+        self.pick_quads.clear();
+    }
+
+    pub fn truncate(&mut self, vertex_count: usize, index_count: usize) {
+        self.vertex_count = vertex_count;
+        self.index_count = index_count;
+        if !self.pick_quads.is_empty() {
+            let quad_count = match vertex_count {
+                0 => 0,
+                _ => self.quad_ids[vertex_count - 1] as usize + 1,
+            };
+            self.pick_quads.truncate(quad_count);
+        }
+    }
+
+    pub fn vertex_capacity(&self) -> usize {
+        match self.format {
+            VertexFormat::Float => self.ao.len(),
+            VertexFormat::Packed => self.packed.len() / PACKED_WORDS_PER_VERTEX,
+        }
+    }
+
+    pub fn index_capacity(&self) -> usize {
+        self.indices.len()
+    }
+
+    pub fn append(&mut self, other: &MeshArrays) {
+        self.append_range(other, 0..other.vertex_count, 0..other.index_count);
+    }
+
+    pub fn append_range(
+        &mut self,
+        other: &MeshArrays,
+        vertex_range: Range<usize>,
+        index_range: Range<usize>,
+    ) {
+        let vertex_offset = self.vertex_count;
+        let vertex_end = vertex_offset + vertex_range.len();
+        let (src_start, src_end) = (vertex_range.start, vertex_range.end);
+        let index_end = self.index_count + index_range.len();
+        self.reserve(vertex_end, index_end);
+
+        if self.is_packed() {
+            self.packed[vertex_offset * PACKED_WORDS_PER_VERTEX..vertex_end * PACKED_WORDS_PER_VERTEX]
+                .copy_from_slice(
+                    &other.packed[src_start * PACKED_WORDS_PER_VERTEX..src_end * PACKED_WORDS_PER_VERTEX],
+                );
+        } else {
+            self.vertices[vertex_offset * 3..vertex_end * 3]
+                .copy_from_slice(&other.vertices[src_start * 3..src_end * 3]);
+            self.normals[vertex_offset * 3..vertex_end * 3]
+                .copy_from_slice(&other.normals[src_start * 3..src_end * 3]);
+            self.uvs[vertex_offset * 2..vertex_end * 2]
+                .copy_from_slice(&other.uvs[src_start * 2..src_end * 2]);
+            self.ao[vertex_offset..vertex_end].copy_from_slice(&other.ao[src_start..src_end]);
+            self.is_selected[vertex_offset..vertex_end]
+                .copy_from_slice(&other.is_selected[src_start..src_end]);
+            let capacity = self.vertex_capacity();
+            let optional = [
+                (&mut self.block_types, &other.block_types, 1),
+                (&mut self.tile_origins, &other.tile_origins, 2),
+                (&mut self.colors, &other.colors, 4),
+            ];
+            for (dst, src, width) in optional {
+                let Some(src) = src.get(src_start * width..src_end * width) else {
+                    continue;
+                };
+                if src.is_empty() {
+                    continue;
+                }
+                if dst.len() < capacity * width {
+                    dst.resize(capacity * width, 0.0);
+                }
+                dst[vertex_offset * width..vertex_end * width].copy_from_slice(src);
+            }
+        }
+
+        if !other.pick_quads.is_empty() && src_start < src_end {
+            let first_quad = other.quad_ids[src_start];
+            let last_quad = other.quad_ids[src_end - 1];
+            let quad_offset = self.pick_quads.len() as u32;
+            if self.quad_ids.len() < vertex_end {
+                self.quad_ids.resize(self.vertex_capacity(), 0);
+            }
+            for (dst, &src) in self.quad_ids[vertex_offset..vertex_end]
+                .iter_mut()
+                .zip(&other.quad_ids[src_start..src_end])
+            {
+                *dst = src - first_quad + quad_offset;
+            }
+            self.pick_quads
+                .extend_from_slice(&other.pick_quads[first_quad as usize..=last_quad as usize]);
+        }
+
+        for (dst, &src) in self.indices[self.index_count..index_end]
+            .iter_mut()
+            .zip(&other.indices[index_range])
+        {
+            *dst = src - src_start as u32 + vertex_offset as u32;
+        }
+
+        self.vertex_count = vertex_end;
+        self.index_count = index_end;
+    }
+
+    pub fn scale_positions(&mut self, factor: u32, bounds: [u32; 3]) {
+        for quad in &mut self.pick_quads {
+            quad.scale(factor, bounds);
+        }
+        if self.is_packed() {
+            for words in self.packed[..self.vertex_count * PACKED_WORDS_PER_VERTEX]
+                .chunks_exact_mut(PACKED_WORDS_PER_VERTEX)
+            {
+                let scaled = scale_packed_position([words[0], words[1]], factor, bounds);
+                words.copy_from_slice(&scaled);
+            }
+        } else {
+            for position in self.vertices[..self.vertex_count * 3].chunks_exact_mut(3) {
+                for (axis, coord) in position.iter_mut().enumerate() {
+                    *coord = (*coord * factor as f32).min(bounds[axis] as f32);
+                }
+            }
+        }
+    }
+
+    pub fn translate_positions(&mut self, vertex_range: Range<usize>, offset: [u32; 3]) {
+        let (start, end) = (vertex_range.start, vertex_range.end);
+        if !self.pick_quads.is_empty() && start < end {
+            let quads = self.quad_ids[start] as usize..=self.quad_ids[end - 1] as usize;
+            for quad in &mut self.pick_quads[quads] {
+                quad.translate(offset);
+            }
+        }
+        if self.is_packed() {
+            for words in self.packed[start * PACKED_WORDS_PER_VERTEX..end * PACKED_WORDS_PER_VERTEX]
+                .chunks_exact_mut(PACKED_WORDS_PER_VERTEX)
+            {
+                let translated = translate_packed_position([words[0], words[1]], offset);
+                words.copy_from_slice(&translated);
+            }
+        } else {
+            for position in self.vertices[start * 3..end * 3].chunks_exact_mut(3) {
+                position[0] += offset[0] as f32;
+                position[1] += offset[1] as f32;
+                position[2] += offset[2] as f32;
+            }
+        }
+    }
+
+    pub fn pick(&self, quad_id: u32, hit_position: [f32; 3]) -> Option<PickHit> {
+        self.pick_quads
+            .get(quad_id as usize)
+            .map(|quad| quad.hit(hit_position))
+    }
+
+    pub fn reserve(&mut self, vertex_count: usize, index_count: usize) {
+        if vertex_count > self.vertex_capacity() {
+            self.grow_vertices(vertex_count);
+        }
+        if index_count > self.index_capacity() {
+            self.grow_indices(index_count);
+        }
+    }
+
+    #[cold]
+    fn grow_vertices(&mut self, min_vertices: usize) {
+        let capacity = grown_capacity(self.vertex_capacity(), min_vertices);
+        match self.format {
+            VertexFormat::Float => {
+                self.vertices.resize(capacity * 3, 0.0);
+                self.normals.resize(capacity * 3, 0.0);
+                self.uvs.resize(capacity * 2, 0.0);
+                self.ao.resize(capacity, 0.0);
+                self.is_selected.resize(capacity, 0.0);
+                let optional = [
+                    (&mut self.block_types, 1),
+                    (&mut self.tile_origins, 2),
+                    (&mut self.colors, 4),
+                ];
+                for (buffer, width) in optional {
+                    if !buffer.is_empty() {
+                        buffer.resize(capacity * width, 0.0);
+                    }
+                }
+            }
+            VertexFormat::Packed => {
+                self.packed.resize(capacity * PACKED_WORDS_PER_VERTEX, 0);
+            }
+        }
+    }
+
+    #[cold]
+    fn grow_indices(&mut self, min_indices: usize) {
+        let capacity = grown_capacity(self.index_capacity(), min_indices);
+        self.indices.resize(capacity, 0);
+    }
+
+    #[inline(always)]
+    fn reserve_vertex(&mut self, buffer_len: usize, offset: usize, width: usize) {
+        if offset + width > buffer_len {
+            self.grow_vertices(self.vertex_count + 1);
+        }
     }
 
     #[inline(always)]
     pub fn push_vertex(&mut self, x: f32, y: f32, z: f32) {
         let offset = self.vertex_count * 3;
+        self.reserve_vertex(self.vertices.len(), offset, 3);
         self.vertices[offset] = x;
         self.vertices[offset + 1] = y;
         self.vertices[offset + 2] = z;
@@ -39,6 +317,7 @@ impl MeshArrays {
     #[inline(always)]
     pub fn push_normal(&mut self, x: f32, y: f32, z: f32) {
         let offset = self.vertex_count * 3;
+        self.reserve_vertex(self.normals.len(), offset, 3);
         self.normals[offset] = x;
         self.normals[offset + 1] = y;
         self.normals[offset + 2] = z;
@@ -47,20 +326,69 @@ impl MeshArrays {
     #[inline(always)]
     pub fn push_uv(&mut self, u: f32, v: f32) {
         let offset = self.vertex_count * 2;
+        self.reserve_vertex(self.uvs.len(), offset, 2);
         self.uvs[offset] = u;
         self.uvs[offset + 1] = v;
     }
 
+    #[inline(always)]
+    pub fn push_tile_origin(&mut self, u: f32, v: f32) {
+        let offset = self.vertex_count * 2;
+        if offset + 2 > self.tile_origins.len() {
+            let capacity = self.vertex_capacity().max(self.vertex_count + 1);
+            self.tile_origins.resize(capacity * 2, 0.0);
+        }
+        self.tile_origins[offset] = u;
+        self.tile_origins[offset + 1] = v;
+    }
+
+    #[inline(always)]
+    pub fn push_color(&mut self, color: [f32; 4]) {
+        let offset = self.vertex_count * 4;
+        if offset + 4 > self.colors.len() {
+            let capacity = self.vertex_capacity().max(self.vertex_count + 1);
+            self.colors.resize(capacity * 4, 0.0);
+        }
+        self.colors[offset..offset + 4].copy_from_slice(&color);
+    }
+
+    #[inline(always)]
+    pub fn push_quad_id(&mut self, quad_id: u32) {
+        if self.vertex_count >= self.quad_ids.len() {
+            let capacity = self.vertex_capacity().max(self.vertex_count + 1);
+            self.quad_ids.resize(capacity, 0);
+        }
+        self.quad_ids[self.vertex_count] = quad_id;
+    }
+
     #[inline(always)]
     pub fn push_ao(&mut self, value: f32) {
+        self.reserve_vertex(self.ao.len(), self.vertex_count, 1);
         self.ao[self.vertex_count] = value;
     }
 
     #[inline(always)]
     pub fn push_is_selected(&mut self, value: u8) {
+        self.reserve_vertex(self.is_selected.len(), self.vertex_count, 1);
         self.is_selected[self.vertex_count] = value as f32;
     }
 
+    #[inline(always)]
+    pub fn push_block_type(&mut self, block_type: u8) {
+        if self.vertex_count >= self.block_types.len() {
+            let capacity = self.vertex_capacity().max(self.vertex_count + 1);
+            self.block_types.resize(capacity, 0.0);
+        }
+        self.block_types[self.vertex_count] = block_type as f32;
+    }
+
+    #[inline(always)]
+    pub fn push_packed(&mut self, words: [u32; PACKED_WORDS_PER_VERTEX]) {
+        let offset = self.vertex_count * PACKED_WORDS_PER_VERTEX;
+        self.reserve_vertex(self.packed.len(), offset, PACKED_WORDS_PER_VERTEX);
+        self.packed[offset..offset + PACKED_WORDS_PER_VERTEX].copy_from_slice(&words);
+    }
+
     #[inline(always)]
     pub fn increment_vertex(&mut self) {
         self.vertex_count += 1;
@@ -68,7 +396,14 @@ impl MeshArrays {
 
     #[inline(always)]
     pub fn push_index(&mut self, index: u32) {
+        if self.index_count >= self.indices.len() {
+            self.grow_indices(self.index_count + 1);
+        }
         self.indices[self.index_count] = index;
         self.index_count += 1;
     }
 }
+
+fn grown_capacity(current: usize, required: usize) -> usize {
+    required.max(current * 2).max(64)
+}
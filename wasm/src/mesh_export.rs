@@ -0,0 +1,564 @@
+use std::fmt::{self, Write};
+
+use crate::block_materials::BLOCK_TYPE_COUNT;
+use crate::color_palette::ColorPalette;
+use crate::find_exterior_faces::ExteriorFacesFinder;
+use crate::mesh_arrays::{MeshArrays, VertexFormat};
+use crate::packed_vertex::{decode_packed_vertex, PACKED_WORDS_PER_VERTEX};
+use crate::texture_coords::FACES_PER_BLOCK;
+use crate::validation::MeshingError;
+
+const STL_HEADER: &[u8] = b"lunavoxel binary STL";
+const STL_HEADER_LEN: usize = 80;
+const STL_TRIANGLE_LEN: usize = 50;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ExportError {
+    InvalidVolume(MeshingError),
+    InvalidFormat { value: u8 },
+    InvalidAxis { value: u8 },
+    InvalidScale,
+}
+
+impl fmt::Display for ExportError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            ExportError::InvalidVolume(error) => error.fmt(f),
+            ExportError::InvalidFormat { value } => {
+                write!(f, "{value} is not a valid export format")
+            }
+            ExportError::InvalidAxis { value } => {
+                write!(f, "{value} is not a valid axis convention")
+            }
+            ExportError::InvalidScale => write!(f, "export scale must be a finite positive value"),
+        }
+    }
+}
+
+impl std::error::Error for ExportError {}
+
+impl From<MeshingError> for ExportError {
+    fn from(error: MeshingError) -> Self {
+        ExportError::InvalidVolume(error)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum ExportFormat {
+    #[default]
+    Stl,
+    PlyAscii,
+    PlyBinary,
+}
+
+impl TryFrom<u8> for ExportFormat {
+    type Error = ExportError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(ExportFormat::Stl),
+            1 => Ok(ExportFormat::PlyAscii),
+            2 => Ok(ExportFormat::PlyBinary),
+            _ => Err(ExportError::InvalidFormat { value }),
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum AxisConvention {
+    #[default]
+    YUp,
+    ZUp,
+}
+
+impl TryFrom<u8> for AxisConvention {
+    type Error = ExportError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        match value {
+            0 => Ok(AxisConvention::YUp),
+            1 => Ok(AxisConvention::ZUp),
+            _ => Err(ExportError::InvalidAxis { value }),
+        }
+    }
+}
+
+impl AxisConvention {
+    #[inline(always)]
+    fn apply(self, [x, y, z]: [f32; 3]) -> [f32; 3] {
+        match self {
+            AxisConvention::YUp => [x, y, z],
+            AxisConvention::ZUp => [x, -z, y],
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct ExportOptions {
+    pub scale: f32,
+    pub axis: AxisConvention,
+    pub vertex_colors: bool,
+}
+
+impl Default for ExportOptions {
+    fn default() -> Self {
+        Self {
+            scale: 1.0,
+            axis: AxisConvention::YUp,
+            vertex_colors: false,
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+struct ExportVertex {
+    position: [f32; 3],
+    normal: [f32; 3],
+    color: [u8; 4],
+    ao: f32,
+}
+
+fn export_vertex(mesh: &MeshArrays, index: usize, options: &ExportOptions) -> ExportVertex {
+    let (position, normal, ao) = match mesh.format {
+        VertexFormat::Float => (
+            [0, 1, 2].map(|a| mesh.vertices[index * 3 + a]),
+            [0, 1, 2].map(|a| mesh.normals[index * 3 + a]),
+            mesh.ao[index],
+        ),
+        VertexFormat::Packed => {
+            let offset = index * PACKED_WORDS_PER_VERTEX;
+            let decoded = decode_packed_vertex([mesh.packed[offset], mesh.packed[offset + 1]], 1);
+            (decoded.position, decoded.normal, decoded.ao)
+        }
+    };
+    let color = match mesh.colors.get(index * 4..index * 4 + 4) {
+        Some(rgba) if options.vertex_colors => {
+            [0, 1, 2, 3].map(|c| (rgba[c].clamp(0.0, 1.0) * 255.0).round() as u8)
+        }
+        _ => [255; 4],
+    };
+    ExportVertex {
+        position: options.axis.apply(position.map(|c| c * options.scale)),
+        normal: options.axis.apply(normal),
+        color,
+        ao,
+    }
+}
+
+pub fn export_mesh(
+    mesh: &MeshArrays,
+    format: ExportFormat,
+    options: &ExportOptions,
+) -> Result<Vec<u8>, ExportError> {
+    if !options.scale.is_finite() || options.scale <= 0.0 {
+        return Err(ExportError::InvalidScale);
+    }
+    Ok(match format {
+        ExportFormat::Stl => write_stl(mesh, options),
+        ExportFormat::PlyAscii => write_ply(mesh, false, options),
+        ExportFormat::PlyBinary => write_ply(mesh, true, options),
+    })
+}
+
+pub fn export_volume(
+    voxel_data: &[u8],
+    dims: [usize; 3],
+    palette: Option<ColorPalette>,
+    format: ExportFormat,
+    options: &ExportOptions,
+) -> Result<Vec<u8>, ExportError> {
+    let options = ExportOptions {
+        vertex_colors: palette.is_some(),
+        ..*options
+    };
+    let mesh = mesh_volume(voxel_data, dims, palette)?;
+    export_mesh(&mesh, format, &options)
+}
+
+pub fn mesh_volume(
+    voxel_data: &[u8],
+    dims: [usize; 3],
+    palette: Option<ColorPalette>,
+) -> Result<MeshArrays, MeshingError> {
+    let [dim_x, dim_y, dim_z] = dims;
+    let mut finder = ExteriorFacesFinder::new(dim_x.max(dim_y).max(dim_z));
+    finder.set_color_palette(palette);
+    let mut mesh = MeshArrays::new(0, 0);
+    finder.try_find_exterior_faces(
+        voxel_data,
+        1,
+        &[0; BLOCK_TYPE_COUNT * FACES_PER_BLOCK],
+        dim_x,
+        dim_y,
+        dim_z,
+        &mut mesh,
+        &[],
+        0,
+        0,
+        0,
+        true,
+    )?;
+    Ok(mesh)
+}
+
+pub fn write_stl(mesh: &MeshArrays, options: &ExportOptions) -> Vec<u8> {
+    let triangle_count = mesh.index_count / 3;
+    let mut out = Vec::with_capacity(STL_HEADER_LEN + 4 + triangle_count * STL_TRIANGLE_LEN);
+    out.extend_from_slice(STL_HEADER);
+    out.resize(STL_HEADER_LEN, 0);
+    out.extend_from_slice(&(triangle_count as u32).to_le_bytes());
+
+    for triangle in mesh.indices[..triangle_count * 3].chunks_exact(3) {
+        let corners = [0, 1, 2].map(|c| export_vertex(mesh, triangle[c] as usize, options));
+        let floats = corners[0]
+            .normal
+            .iter()
+            .chain(corners.iter().flat_map(|v| &v.position));
+        for value in floats {
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+        out.extend_from_slice(&0u16.to_le_bytes());
+    }
+
+    out
+}
+
+pub fn write_ply(mesh: &MeshArrays, binary: bool, options: &ExportOptions) -> Vec<u8> {
+    let triangle_count = mesh.index_count / 3;
+    let encoding = if binary {
+        "binary_little_endian"
+    } else {
+        "ascii"
+    };
+    let mut header = String::new();
+    let _ = write!(
+        header,
+        "ply\nformat {encoding} 1.0\ncomment lunavoxel\n\
+         element vertex {}\n\
+         property float x\nproperty float y\nproperty float z\n\
+         property float nx\nproperty float ny\nproperty float nz\n\
+         property uchar red\nproperty uchar green\nproperty uchar blue\nproperty uchar alpha\n\
+         property float ao\n\
+         element face {triangle_count}\n\
+         property list uchar uint vertex_indices\n\
+         end_header\n",
+        mesh.vertex_count
+    );
+    let mut out = header.into_bytes();
+    let triangles = mesh.indices[..triangle_count * 3].chunks_exact(3);
+
+    if binary {
+        for index in 0..mesh.vertex_count {
+            let vertex = export_vertex(mesh, index, options);
+            for value in vertex.position.iter().chain(&vertex.normal) {
+                out.extend_from_slice(&value.to_le_bytes());
+            }
+            out.extend_from_slice(&vertex.color);
+            out.extend_from_slice(&vertex.ao.to_le_bytes());
+        }
+        for triangle in triangles {
+            out.push(3);
+            for index in triangle {
+                out.extend_from_slice(&index.to_le_bytes());
+            }
+        }
+    } else {
+        let mut body = String::new();
+        for index in 0..mesh.vertex_count {
+            let ExportVertex {
+                position: [x, y, z],
+                normal: [nx, ny, nz],
+                color: [r, g, b, a],
+                ao,
+            } = export_vertex(mesh, index, options);
+            let _ = writeln!(body, "{x} {y} {z} {nx} {ny} {nz} {r} {g} {b} {a} {ao}");
+        }
+        for triangle in triangles {
+            let _ = writeln!(body, "3 {} {} {}", triangle[0], triangle[1], triangle[2]);
+        }
+        out.extend_from_slice(body.as_bytes());
+    }
+
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct ParsedPly {
+        vertices: Vec<[f32; 11]>,
+        faces: Vec<[u32; 3]>,
+    }
+
+    fn parse_ply(bytes: &[u8]) -> ParsedPly {
+        let header_end = bytes
+            .windows(11)
+            .position(|w| w == b"end_header\n")
+            .unwrap()
+            + 11;
+        let header = std::str::from_utf8(&bytes[..header_end]).unwrap();
+        let count = |element: &str| -> usize {
+            header
+                .lines()
+                .find_map(|l| l.strip_prefix(&format!("element {element} ")))
+                .unwrap()
+                .parse()
+                .unwrap()
+        };
+        let (vertex_count, face_count) = (count("vertex"), count("face"));
+        let body = &bytes[header_end..];
+
+        if header.contains("format ascii 1.0") {
+            let lines: Vec<&str> = std::str::from_utf8(body).unwrap().lines().collect();
+            assert_eq!(lines.len(), vertex_count + face_count);
+            let vertices = lines[..vertex_count]
+                .iter()
+                .map(|l| {
+                    let values: Vec<f32> = l.split(' ').map(|v| v.parse().unwrap()).collect();
+                    std::array::from_fn(|i| values[i])
+                })
+                .collect();
+            let faces = lines[vertex_count..]
+                .iter()
+                .map(|l| {
+                    let values: Vec<u32> = l.split(' ').map(|v| v.parse().unwrap()).collect();
+                    assert_eq!(values[0], 3);
+                    [values[1], values[2], values[3]]
+                })
+                .collect();
+            return ParsedPly { vertices, faces };
+        }
+
+        assert!(header.contains("format binary_little_endian 1.0"));
+        let f32_at = |o: usize| f32::from_le_bytes(body[o..o + 4].try_into().unwrap());
+        let vertex_len = 6 * 4 + 4 + 4;
+        let vertices = (0..vertex_count)
+            .map(|v| {
+                let o = v * vertex_len;
+                let mut values = [0.0; 11];
+                for (i, value) in values.iter_mut().take(6).enumerate() {
+                    *value = f32_at(o + i * 4);
+                }
+                for i in 0..4 {
+                    values[6 + i] = body[o + 24 + i] as f32;
+                }
+                values[10] = f32_at(o + 28);
+                values
+            })
+            .collect();
+        let faces_start = vertex_count * vertex_len;
+        assert_eq!(body.len(), faces_start + face_count * 13);
+        let faces = (0..face_count)
+            .map(|f| {
+                let o = faces_start + f * 13;
+                assert_eq!(body[o], 3);
+                std::array::from_fn(|i| {
+                    u32::from_le_bytes(body[o + 1 + i * 4..o + 5 + i * 4].try_into().unwrap())
+                })
+            })
+            .collect();
+        ParsedPly { vertices, faces }
+    }
+
+    fn parse_stl(bytes: &[u8]) -> Vec<[[f32; 3]; 4]> {
+        assert!(bytes.starts_with(STL_HEADER));
+        let count = u32::from_le_bytes(bytes[80..84].try_into().unwrap()) as usize;
+        assert_eq!(bytes.len(), 84 + count * STL_TRIANGLE_LEN);
+        bytes[84..]
+            .chunks_exact(STL_TRIANGLE_LEN)
+            .map(|t| {
+                let f = |i: usize| f32::from_le_bytes(t[i * 4..i * 4 + 4].try_into().unwrap());
+                std::array::from_fn(|v| std::array::from_fn(|a| f(v * 3 + a)))
+            })
+            .collect()
+    }
+
+    fn signed_volume(triangles: &[[[f32; 3]; 3]]) -> f32 {
+        triangles
+            .iter()
+            .map(|[a, b, c]| {
+                (a[0] * (b[1] * c[2] - b[2] * c[1]) - a[1] * (b[0] * c[2] - b[2] * c[0])
+                    + a[2] * (b[0] * c[1] - b[1] * c[0]))
+                    / 6.0
+            })
+            .sum()
+    }
+
+    fn box_volume() -> (Vec<u8>, [usize; 3]) {
+        let dims = [4, 3, 2];
+        let mut data = vec![0u8; 24];
+        data[..12].fill(1);
+        data[12..].fill(2);
+        (data, dims)
+    }
+
+    #[test]
+    fn stl_round_trips_closed_volume() {
+        let (data, dims) = box_volume();
+        let stl = export_volume(
+            &data,
+            dims,
+            None,
+            ExportFormat::Stl,
+            &ExportOptions::default(),
+        )
+        .unwrap();
+        let triangles = parse_stl(&stl);
+        assert_eq!(triangles.len(), 12);
+
+        let corners: Vec<[[f32; 3]; 3]> = triangles.iter().map(|t| [t[1], t[2], t[3]]).collect();
+        assert!((signed_volume(&corners) - 24.0).abs() < 1e-4);
+        for t in &triangles {
+            let [a, b, c] = [t[1], t[2], t[3]];
+            let edge = |p: [f32; 3], q: [f32; 3]| [0, 1, 2].map(|i| q[i] - p[i]);
+            let (u, v) = (edge(a, b), edge(a, c));
+            let cross = [
+                u[1] * v[2] - u[2] * v[1],
+                u[2] * v[0] - u[0] * v[2],
+                u[0] * v[1] - u[1] * v[0],
+            ];
+            let dot: f32 = (0..3).map(|i| cross[i] * t[0][i]).sum();
+            assert!(dot > 0.0);
+        }
+    }
+
+    #[test]
+    fn scale_and_axis_convention_transform_positions() {
+        let (data, dims) = box_volume();
+        let options = ExportOptions {
+            scale: 0.5,
+            axis: AxisConvention::ZUp,
+            vertex_colors: false,
+        };
+        let triangles =
+            parse_stl(&export_volume(&data, dims, None, ExportFormat::Stl, &options).unwrap());
+        let corners: Vec<[[f32; 3]; 3]> = triangles.iter().map(|t| [t[1], t[2], t[3]]).collect();
+        assert!((signed_volume(&corners) - 3.0).abs() < 1e-4);
+
+        let max = |axis: usize| {
+            corners
+                .iter()
+                .flatten()
+                .map(|p| p[axis])
+                .fold(f32::NEG_INFINITY, f32::max)
+        };
+        let min = |axis: usize| {
+            corners
+                .iter()
+                .flatten()
+                .map(|p| p[axis])
+                .fold(f32::INFINITY, f32::min)
+        };
+        assert_eq!([min(0), min(1), min(2)], [0.0, -1.0, 0.0]);
+        assert_eq!([max(0), max(1), max(2)], [2.0, 0.0, 1.5]);
+        assert!(triangles.iter().any(|t| t[0] == [0.0, 0.0, 1.0]));
+    }
+
+    #[test]
+    fn ascii_and_binary_ply_agree() {
+        let (data, dims) = box_volume();
+        let options = ExportOptions {
+            vertex_colors: true,
+            ..ExportOptions::default()
+        };
+        let palette = ColorPalette::from_hex_rgb(&[0xFF0000, 0x0080FF]);
+        let mesh = mesh_volume(&data, dims, Some(palette)).unwrap();
+        let ascii = parse_ply(&export_mesh(&mesh, ExportFormat::PlyAscii, &options).unwrap());
+        let binary = parse_ply(&export_mesh(&mesh, ExportFormat::PlyBinary, &options).unwrap());
+
+        assert_eq!(ascii.vertices.len(), mesh.vertex_count);
+        assert_eq!(ascii.faces.len(), mesh.index_count / 3);
+        assert_eq!(ascii.vertices, binary.vertices);
+        assert_eq!(ascii.faces, binary.faces);
+        assert_eq!(
+            ascii.faces.concat(),
+            mesh.indices[..mesh.index_count].to_vec()
+        );
+
+        for (i, vertex) in binary.vertices.iter().enumerate() {
+            assert_eq!(vertex[..3], mesh.vertices[i * 3..i * 3 + 3]);
+            assert_eq!(vertex[3..6], mesh.normals[i * 3..i * 3 + 3]);
+            assert_eq!(vertex[10], mesh.ao[i]);
+        }
+        for face in &binary.faces {
+            let corners = face.map(|i| binary.vertices[i as usize]);
+            let center_x = corners.iter().map(|v| v[0]).sum::<f32>() / 3.0;
+            let expected = if center_x < 2.0 {
+                [255.0, 0.0, 0.0, 255.0]
+            } else {
+                [0.0, 128.0, 255.0, 255.0]
+            };
+            assert!(corners.iter().all(|v| v[6..10] == expected));
+        }
+    }
+
+    #[test]
+    fn rejects_unknown_format_and_axis_values() {
+        assert_eq!(ExportFormat::try_from(2), Ok(ExportFormat::PlyBinary));
+        assert_eq!(
+            ExportFormat::try_from(3),
+            Err(ExportError::InvalidFormat { value: 3 })
+        );
+        assert_eq!(AxisConvention::try_from(1), Ok(AxisConvention::ZUp));
+        assert_eq!(
+            AxisConvention::try_from(2),
+            Err(ExportError::InvalidAxis { value: 2 })
+        );
+    }
+
+    #[test]
+    fn rejects_non_positive_or_non_finite_scale() {
+        let mesh = MeshArrays::new(0, 0);
+        for scale in [0.0, -1.0, f32::NAN, f32::INFINITY] {
+            let options = ExportOptions {
+                scale,
+                ..ExportOptions::default()
+            };
+            assert_eq!(
+                export_mesh(&mesh, ExportFormat::Stl, &options),
+                Err(ExportError::InvalidScale)
+            );
+        }
+    }
+
+    #[test]
+    fn empty_meshes_export_valid_files() {
+        let mesh = MeshArrays::new(0, 0);
+        let options = ExportOptions::default();
+        let stl = export_mesh(&mesh, ExportFormat::Stl, &options).unwrap();
+        assert_eq!(stl.len(), STL_HEADER_LEN + 4);
+        assert_eq!(stl[STL_HEADER_LEN..], 0u32.to_le_bytes());
+        for format in [ExportFormat::PlyAscii, ExportFormat::PlyBinary] {
+            let ply = parse_ply(&export_mesh(&mesh, format, &options).unwrap());
+            assert!(ply.vertices.is_empty() && ply.faces.is_empty());
+        }
+    }
+
+    #[test]
+    fn packed_meshes_export_decoded_positions() {
+        let (data, dims) = box_volume();
+        let float = mesh_volume(&data, dims, None).unwrap();
+        let mut packed = MeshArrays::new_packed(0, 0);
+        let mut finder = ExteriorFacesFinder::new(4);
+        finder.find_exterior_faces(
+            &data,
+            1,
+            &[0; BLOCK_TYPE_COUNT * FACES_PER_BLOCK],
+            dims[0],
+            dims[1],
+            dims[2],
+            &mut packed,
+            &[],
+            0,
+            0,
+            0,
+            true,
+        );
+        let options = ExportOptions::default();
+        assert_eq!(write_stl(&packed, &options), write_stl(&float, &options));
+
+        let ply = parse_ply(&write_ply(&packed, true, &options));
+        assert!(ply.vertices.iter().all(|v| v[6..10] == [255.0; 4]));
+    }
+}
@@ -0,0 +1,179 @@
+use crate::ambient_occlusion::OCCLUSION_LEVELS;
+use crate::texture_coords::get_texture_coordinates;
+use crate::voxel_constants::FACES;
+
+pub const PACKED_WORDS_PER_VERTEX: usize = 2;
+
+const Z_BITS: u32 = 12;
+const FACE_DIR_SHIFT: u32 = Z_BITS;
+const OCCLUSION_SHIFT: u32 = FACE_DIR_SHIFT + 3;
+const SELECTED_SHIFT: u32 = OCCLUSION_SHIFT + 2;
+const TEXTURE_SHIFT: u32 = SELECTED_SHIFT + 1;
+
+pub const MAX_PACKED_XY: u32 = 0xFFFF;
+pub const MAX_PACKED_Z: u32 = (1 << Z_BITS) - 1;
+pub const MAX_PACKED_TEXTURE_INDEX: u32 = (1 << (32 - TEXTURE_SHIFT)) - 1;
+
+#[inline(always)]
+pub fn pack_vertex(
+    x: u32,
+    y: u32,
+    z: u32,
+    face_dir: usize,
+    texture_index: i32,
+    occlusion_count: u8,
+    is_selected: u8,
+) -> [u32; PACKED_WORDS_PER_VERTEX] {
+    debug_assert!(x <= MAX_PACKED_XY && y <= MAX_PACKED_XY && z <= MAX_PACKED_Z);
+    debug_assert!(texture_index as u32 <= MAX_PACKED_TEXTURE_INDEX);
+    [
+        x | (y << 16),
+        z | ((face_dir as u32) << FACE_DIR_SHIFT)
+            | ((occlusion_count as u32) << OCCLUSION_SHIFT)
+            | (((is_selected != 0) as u32) << SELECTED_SHIFT)
+            | ((texture_index as u32) << TEXTURE_SHIFT),
+    ]
+}
+
+#[inline(always)]
+pub fn scale_packed_position(
+    words: [u32; PACKED_WORDS_PER_VERTEX],
+    factor: u32,
+    bounds: [u32; 3],
+) -> [u32; PACKED_WORDS_PER_VERTEX] {
+    let x = ((words[0] & MAX_PACKED_XY) * factor).min(bounds[0]);
+    let y = ((words[0] >> 16) * factor).min(bounds[1]);
+    let z = ((words[1] & MAX_PACKED_Z) * factor).min(bounds[2]);
+    [x | (y << 16), (words[1] & !MAX_PACKED_Z) | z]
+}
+
+pub fn translate_packed_position(
+    words: [u32; PACKED_WORDS_PER_VERTEX],
+    offset: [u32; 3],
+) -> [u32; PACKED_WORDS_PER_VERTEX] {
+    let x = (words[0] & MAX_PACKED_XY) + offset[0];
+    let y = (words[0] >> 16) + offset[1];
+    let z = (words[1] & MAX_PACKED_Z) + offset[2];
+    [x | (y << 16), (words[1] & !MAX_PACKED_Z) | z]
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DecodedVertex {
+    pub position: [f32; 3],
+    pub normal: [f32; 3],
+    pub uv: [f32; 2],
+    pub ao: f32,
+    pub is_selected: f32,
+}
+
+pub fn decode_packed_vertex(
+    words: [u32; PACKED_WORDS_PER_VERTEX],
+    texture_width: i32,
+) -> DecodedVertex {
+    let x = words[0] & MAX_PACKED_XY;
+    let y = words[0] >> 16;
+    let z = words[1] & MAX_PACKED_Z;
+    let face_dir = ((words[1] >> FACE_DIR_SHIFT) & 0x7) as usize;
+    let occlusion_count = ((words[1] >> OCCLUSION_SHIFT) & 0x3) as usize;
+    let is_selected = (words[1] >> SELECTED_SHIFT) & 0x1;
+    let texture_index = (words[1] >> TEXTURE_SHIFT) as i32;
+    let texture_coords = get_texture_coordinates(texture_index, texture_width);
+
+    DecodedVertex {
+        position: [x as f32, y as f32, z as f32],
+        normal: FACES[face_dir].normal,
+        uv: [texture_coords[0], texture_coords[1]],
+        ao: OCCLUSION_LEVELS[occlusion_count],
+        is_selected: is_selected as f32,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::find_exterior_faces::ExteriorFacesFinder;
+    use crate::mesh_arrays::MeshArrays;
+    use crate::texture_coords::expand_block_atlas_mapping;
+
+    fn mesh(
+        voxel_data: &[u8],
+        dims: [usize; 3],
+        selection_data: &[u8],
+        mesh_arrays: &mut MeshArrays,
+    ) {
+        let mut finder = ExteriorFacesFinder::new(dims[0].max(dims[1]).max(dims[2]));
+        let mapping = expand_block_atlas_mapping(&[0, 5, 9]);
+        finder.find_exterior_faces(
+            voxel_data,
+            4,
+            &mapping,
+            dims[0],
+            dims[1],
+            dims[2],
+            mesh_arrays,
+            selection_data,
+            dims[0],
+            dims[1],
+            dims[2],
+            false,
+        );
+    }
+
+    #[test]
+    fn packed_output_round_trips_to_float_layout() {
+        let dims = [4, 3, 5];
+        let total = dims[0] * dims[1] * dims[2];
+        let voxel_data: Vec<u8> = (0..total)
+            .map(|i| if i % 4 == 0 { 0 } else { (i % 3) as u8 + 1 })
+            .collect();
+        let selection_data: Vec<u8> = (0..total).map(|i| (i % 5 == 0) as u8).collect();
+
+        let mut float_arrays = MeshArrays::new(total * 24, total * 36);
+        mesh(&voxel_data, dims, &selection_data, &mut float_arrays);
+        let mut packed_arrays = MeshArrays::new_packed(total * 24, total * 36);
+        mesh(&voxel_data, dims, &selection_data, &mut packed_arrays);
+
+        assert!(float_arrays.vertex_count > 0);
+        assert_eq!(packed_arrays.vertex_count, float_arrays.vertex_count);
+        assert_eq!(
+            packed_arrays.indices[..packed_arrays.index_count],
+            float_arrays.indices[..float_arrays.index_count]
+        );
+
+        for v in 0..float_arrays.vertex_count {
+            let words = [
+                packed_arrays.packed[v * PACKED_WORDS_PER_VERTEX],
+                packed_arrays.packed[v * PACKED_WORDS_PER_VERTEX + 1],
+            ];
+            let decoded = decode_packed_vertex(words, 4);
+            assert_eq!(decoded.position, float_arrays.vertices[v * 3..v * 3 + 3]);
+            assert_eq!(decoded.normal, float_arrays.normals[v * 3..v * 3 + 3]);
+            assert_eq!(decoded.uv, float_arrays.uvs[v * 2..v * 2 + 2]);
+            assert_eq!(decoded.ao, float_arrays.ao[v]);
+            assert_eq!(decoded.is_selected, float_arrays.is_selected[v]);
+        }
+    }
+
+    #[test]
+    fn pack_vertex_uses_full_field_ranges() {
+        let words = pack_vertex(
+            MAX_PACKED_XY,
+            MAX_PACKED_XY,
+            MAX_PACKED_Z,
+            5,
+            MAX_PACKED_TEXTURE_INDEX as i32,
+            3,
+            1,
+        );
+        let decoded = decode_packed_vertex(words, 128);
+        assert_eq!(
+            decoded.position,
+            [MAX_PACKED_XY as f32, MAX_PACKED_XY as f32, MAX_PACKED_Z as f32]
+        );
+        assert_eq!(decoded.normal, FACES[5].normal);
+        assert_eq!(decoded.ao, OCCLUSION_LEVELS[3]);
+        assert_eq!(decoded.is_selected, 1.0);
+        let expected_uv = get_texture_coordinates(MAX_PACKED_TEXTURE_INDEX as i32, 128);
+        assert_eq!(decoded.uv, [expected_uv[0], expected_uv[1]]);
+    }
+}
@@ -0,0 +1,341 @@
+use std::sync::Mutex;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
+
+use crate::find_exterior_faces::ExteriorFacesFinder;
+use crate::mesh_arrays::{MeshArrays, VertexFormat};
+use crate::texture_coords::FACES_PER_BLOCK;
+use crate::validation::{validate_packed_output, MeshingError};
+
+#[derive(Clone, Copy, Debug)]
+pub struct ChunkVolume<'a> {
+    pub voxel_data: &'a [u8],
+    pub dims: [usize; 3],
+    pub origin: [u32; 3],
+}
+
+pub fn default_worker_count() -> usize {
+    thread::available_parallelism().map_or(1, |count| count.get())
+}
+
+pub fn mesh_chunks<F>(
+    chunks: &[ChunkVolume],
+    texture_width: i32,
+    block_atlas_mapping: &[i32],
+    format: VertexFormat,
+    worker_count: usize,
+    configure: F,
+) -> Result<Vec<MeshArrays>, MeshingError>
+where
+    F: Fn(&mut ExteriorFacesFinder) + Sync,
+{
+    let worker_count = worker_count.clamp(1, chunks.len().max(1));
+    let next_chunk = AtomicUsize::new(0);
+    let results: Mutex<Vec<Option<Result<MeshArrays, MeshingError>>>> =
+        Mutex::new((0..chunks.len()).map(|_| None).collect());
+
+    thread::scope(|scope| {
+        for _ in 0..worker_count {
+            scope.spawn(|| {
+                let mut finder = ExteriorFacesFinder::new(0);
+                configure(&mut finder);
+                loop {
+                    let chunk_idx = next_chunk.fetch_add(1, Ordering::Relaxed);
+                    let Some(chunk) = chunks.get(chunk_idx) else {
+                        break;
+                    };
+                    let mesh_arrays = mesh_chunk(
+                        &mut finder,
+                        chunk,
+                        texture_width,
+                        block_atlas_mapping,
+                        format,
+                    );
+                    results.lock().unwrap()[chunk_idx] = Some(mesh_arrays);
+                }
+            });
+        }
+    });
+
+    results
+        .into_inner()
+        .unwrap()
+        .into_iter()
+        .map(|mesh_arrays| mesh_arrays.expect("every chunk is meshed by a worker"))
+        .collect()
+}
+
+pub fn mesh_chunks_merged<F>(
+    chunks: &[ChunkVolume],
+    texture_width: i32,
+    block_atlas_mapping: &[i32],
+    format: VertexFormat,
+    worker_count: usize,
+    configure: F,
+) -> Result<MeshArrays, MeshingError>
+where
+    F: Fn(&mut ExteriorFacesFinder) + Sync,
+{
+    if format == VertexFormat::Packed {
+        for chunk in chunks {
+            let extent = std::array::from_fn(|a| chunk.origin[a] as usize + chunk.dims[a]);
+            validate_packed_output(extent, &[])?;
+        }
+    }
+    let meshes = mesh_chunks(
+        chunks,
+        texture_width,
+        block_atlas_mapping,
+        format,
+        worker_count,
+        configure,
+    )?;
+    Ok(merge_chunk_meshes(chunks, &meshes, format))
+}
+
+pub fn mesh_chunk(
+    finder: &mut ExteriorFacesFinder,
+    chunk: &ChunkVolume,
+    texture_width: i32,
+    block_atlas_mapping: &[i32],
+    format: VertexFormat,
+) -> Result<MeshArrays, MeshingError> {
+    let [dim_x, dim_y, dim_z] = chunk.dims;
+    let mut mesh_arrays = MeshArrays::with_format(format, 0, 0);
+    finder.try_find_exterior_faces(
+        chunk.voxel_data,
+        texture_width,
+        block_atlas_mapping,
+        dim_x,
+        dim_y,
+        dim_z,
+        &mut mesh_arrays,
+        &[],
+        0,
+        0,
+        0,
+        true,
+    )?;
+    Ok(mesh_arrays)
+}
+
+pub fn merge_chunk_meshes(
+    chunks: &[ChunkVolume],
+    meshes: &[MeshArrays],
+    format: VertexFormat,
+) -> MeshArrays {
+    let vertex_total = meshes.iter().map(|mesh| mesh.vertex_count).sum();
+    let index_total = meshes.iter().map(|mesh| mesh.index_count).sum();
+    let mut merged = MeshArrays::with_format(format, vertex_total, index_total);
+
+    for face_dir in 0..FACES_PER_BLOCK {
+        let start = merged.index_count;
+        for (chunk, mesh) in chunks.iter().zip(meshes) {
+            let (index_start, index_end) = mesh.face_index_ranges[face_dir];
+            append_quads(&mut merged, chunk, mesh, index_start, index_end, 0, 0);
+        }
+        merged.face_index_ranges[face_dir] = (start, merged.index_count);
+    }
+
+    merged.translucent_vertex_start = merged.vertex_count;
+    merged.translucent_index_start = merged.index_count;
+    for face_dir in 0..FACES_PER_BLOCK {
+        let start = merged.index_count;
+        for (chunk, mesh) in chunks.iter().zip(meshes) {
+            let (index_start, index_end) = mesh.translucent_face_index_ranges[face_dir];
+            append_quads(
+                &mut merged,
+                chunk,
+                mesh,
+                index_start,
+                index_end,
+                mesh.translucent_vertex_start,
+                mesh.translucent_index_start,
+            );
+        }
+        merged.translucent_face_index_ranges[face_dir] = (start, merged.index_count);
+    }
+
+    merged
+}
+
+fn append_quads(
+    merged: &mut MeshArrays,
+    chunk: &ChunkVolume,
+    mesh: &MeshArrays,
+    index_start: usize,
+    index_end: usize,
+    section_vertex_start: usize,
+    section_index_start: usize,
+) {
+    if index_start == index_end {
+        return;
+    }
+    let vertex_start = section_vertex_start + (index_start - section_index_start) / 6 * 4;
+    let vertex_end = section_vertex_start + (index_end - section_index_start) / 6 * 4;
+    let merged_vertex_start = merged.vertex_count;
+    merged.append_range(mesh, vertex_start..vertex_end, index_start..index_end);
+    merged.translate_positions(merged_vertex_start..merged.vertex_count, chunk.origin);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitmask_backend::MeshingBackend;
+    use crate::test_support::assert_same_mesh;
+    use crate::texture_coords::expand_block_atlas_mapping;
+
+    fn chunk_data(seed: usize, dims: [usize; 3]) -> Vec<u8> {
+        let mut data = vec![0u8; dims[0] * dims[1] * dims[2]];
+        for (idx, value) in data.iter_mut().enumerate() {
+            let hash = (idx * 7919 + seed * 104729) % 13;
+            if hash < 7 {
+                *value = (hash % 3 + 1) as u8;
+            }
+        }
+        data
+    }
+
+    fn chunk_set() -> (Vec<Vec<u8>>, Vec<[usize; 3]>) {
+        let dims = vec![[8, 8, 8], [5, 9, 3], [16, 4, 7], [1, 1, 1], [6, 6, 12]];
+        let data = dims
+            .iter()
+            .enumerate()
+            .map(|(seed, &chunk_dims)| chunk_data(seed, chunk_dims))
+            .collect();
+        (data, dims)
+    }
+
+    fn volumes<'a>(data: &'a [Vec<u8>], dims: &[[usize; 3]]) -> Vec<ChunkVolume<'a>> {
+        data.iter()
+            .zip(dims)
+            .enumerate()
+            .map(|(i, (voxel_data, &dims))| ChunkVolume {
+                voxel_data,
+                dims,
+                origin: [i as u32 * 16, 0, 0],
+            })
+            .collect()
+    }
+
+    fn configure(finder: &mut ExteriorFacesFinder) {
+        finder.set_backend(MeshingBackend::Bitmask);
+        finder.set_block_materials(&[0, 2, 0]).unwrap();
+    }
+
+    #[test]
+    fn parallel_meshes_match_sequential() {
+        let (data, dims) = chunk_set();
+        let chunks = volumes(&data, &dims);
+        let mapping = expand_block_atlas_mapping(&[0, 1, 2]);
+
+        for format in [VertexFormat::Float, VertexFormat::Packed] {
+            let mut finder = ExteriorFacesFinder::new(16);
+            configure(&mut finder);
+            let sequential: Vec<_> = chunks
+                .iter()
+                .map(|chunk| mesh_chunk(&mut finder, chunk, 4, &mapping, format).unwrap())
+                .collect();
+
+            for worker_count in [1, 3, 8] {
+                let parallel =
+                    mesh_chunks(&chunks, 4, &mapping, format, worker_count, configure).unwrap();
+                assert_eq!(parallel.len(), sequential.len());
+                for (a, b) in parallel.iter().zip(&sequential) {
+                    assert_same_mesh(a, b);
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn merged_mesh_offsets_chunks_and_groups_faces() {
+        let (data, dims) = chunk_set();
+        let chunks = volumes(&data, &dims);
+        let mapping = expand_block_atlas_mapping(&[0, 1, 2]);
+        let meshes = mesh_chunks(&chunks, 4, &mapping, VertexFormat::Float, 4, configure).unwrap();
+        let merged =
+            mesh_chunks_merged(&chunks, 4, &mapping, VertexFormat::Float, 4, configure).unwrap();
+
+        assert_eq!(
+            merged.vertex_count,
+            meshes.iter().map(|m| m.vertex_count).sum::<usize>()
+        );
+        assert_eq!(
+            merged.index_count,
+            meshes.iter().map(|m| m.index_count).sum::<usize>()
+        );
+        assert!(merged.translucent_index_start < merged.index_count);
+
+        let mut expected_start = 0;
+        for range in merged
+            .face_index_ranges
+            .iter()
+            .chain(&merged.translucent_face_index_ranges)
+        {
+            assert_eq!(range.0, expected_start);
+            expected_start = range.1;
+        }
+        assert_eq!(expected_start, merged.index_count);
+
+        for (chunk, mesh) in chunks.iter().zip(&meshes) {
+            let min_x = chunk.origin[0] as f32;
+            let max_x = min_x + chunk.dims[0] as f32;
+            let found = (0..merged.vertex_count).any(|v| {
+                let x = merged.vertices[v * 3];
+                x >= min_x && x <= max_x
+            });
+            assert_eq!(found, mesh.vertex_count > 0);
+        }
+        assert!(
+            merged.indices[..merged.index_count]
+                .iter()
+                .all(|&index| (index as usize) < merged.vertex_count)
+        );
+    }
+
+    #[test]
+    fn rejects_chunks_with_mismatched_data() {
+        let (mut data, dims) = chunk_set();
+        data[2].truncate(10);
+        let chunks = volumes(&data, &dims);
+        let mapping = expand_block_atlas_mapping(&[0, 1, 2]);
+        for worker_count in [1, 4] {
+            assert_eq!(
+                mesh_chunks(
+                    &chunks,
+                    4,
+                    &mapping,
+                    VertexFormat::Float,
+                    worker_count,
+                    configure
+                )
+                .err(),
+                Some(MeshingError::VoxelDataLength {
+                    expected: 16 * 4 * 7,
+                    actual: 10
+                })
+            );
+        }
+        assert!(
+            mesh_chunks_merged(&chunks, 4, &mapping, VertexFormat::Packed, 2, configure).is_err()
+        );
+    }
+
+    #[test]
+    fn packed_merge_rejects_origins_beyond_packed_range() {
+        let (data, dims) = chunk_set();
+        let mut chunks = volumes(&data, &dims);
+        let mapping = expand_block_atlas_mapping(&[0, 1, 2]);
+        chunks[1].origin = [16, 0, 4094];
+        assert_eq!(
+            mesh_chunks_merged(&chunks, 4, &mapping, VertexFormat::Packed, 2, configure).err(),
+            Some(MeshingError::PackedExtentExceeded {
+                extent: [21, 9, 4097]
+            })
+        );
+        assert!(
+            mesh_chunks_merged(&chunks, 4, &mapping, VertexFormat::Float, 2, configure).is_ok()
+        );
+    }
+}
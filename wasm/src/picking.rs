@@ -0,0 +1,100 @@
+use crate::voxel_constants::FACES;
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PickQuad {
+    pub min: [u32; 3],
+    pub max: [u32; 3],
+    pub face_dir: u8,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct PickHit {
+    pub voxel: [u32; 3],
+    pub face_dir: u8,
+}
+
+impl PickQuad {
+    pub fn new(
+        origin: [usize; 3],
+        axis: usize,
+        u: usize,
+        v: usize,
+        quad_width: usize,
+        quad_height: usize,
+        face_dir: usize,
+    ) -> Self {
+        let min = origin.map(|c| c as u32);
+        let mut max = [min[0] + 1, min[1] + 1, min[2] + 1];
+        max[u] = min[u] + quad_width as u32;
+        max[v] = min[v] + quad_height as u32;
+        debug_assert_eq!(max[axis], min[axis] + 1);
+        Self {
+            min,
+            max,
+            face_dir: face_dir as u8,
+        }
+    }
+
+    pub fn scale(&mut self, factor: u32, bounds: [u32; 3]) {
+        self.min = std::array::from_fn(|axis| (self.min[axis] * factor).min(bounds[axis]));
+        self.max = std::array::from_fn(|axis| (self.max[axis] * factor).min(bounds[axis]));
+    }
+
+    pub fn translate(&mut self, offset: [u32; 3]) {
+        self.min = std::array::from_fn(|axis| self.min[axis] + offset[axis]);
+        self.max = std::array::from_fn(|axis| self.max[axis] + offset[axis]);
+    }
+
+    pub fn voxel_at(&self, hit_position: [f32; 3]) -> [u32; 3] {
+        let normal = FACES[self.face_dir as usize].normal;
+        std::array::from_fn(|axis| {
+            if normal[axis] > 0.0 {
+                self.max[axis] - 1
+            } else if normal[axis] < 0.0 {
+                self.min[axis]
+            } else {
+                (hit_position[axis].floor().max(0.0) as u32)
+                    .clamp(self.min[axis], self.max[axis] - 1)
+            }
+        })
+    }
+
+    pub fn hit(&self, hit_position: [f32; 3]) -> PickHit {
+        PickHit {
+            voxel: self.voxel_at(hit_position),
+            face_dir: self.face_dir,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn voxel_at_clamps_to_quad_footprint() {
+        let quad = PickQuad::new([4, 2, 7], 1, 2, 0, 3, 2, 2);
+        assert_eq!(quad.max, [6, 3, 10]);
+        assert_eq!(quad.voxel_at([5.5, 3.0, 8.25]), [5, 2, 8]);
+        assert_eq!(quad.voxel_at([6.0, 3.0, 10.0]), [5, 2, 9]);
+        assert_eq!(quad.voxel_at([3.9, 3.0, 6.5]), [4, 2, 7]);
+    }
+
+    #[test]
+    fn scaled_quad_resolves_face_layer_voxel() {
+        let mut quad = PickQuad::new([1, 0, 0], 0, 1, 2, 1, 1, 0);
+        quad.scale(4, [u32::MAX; 3]);
+        assert_eq!(
+            quad.hit([8.0, 2.5, 1.5]),
+            PickHit {
+                voxel: [7, 2, 1],
+                face_dir: 0
+            }
+        );
+
+        let mut quad = PickQuad::new([1, 0, 0], 0, 1, 2, 1, 1, 1);
+        quad.scale(4, [6, 8, 8]);
+        quad.translate([16, 0, 0]);
+        assert_eq!(quad.hit([20.0, 0.5, 3.5]).voxel, [20, 0, 3]);
+    }
+}
@@ -0,0 +1,354 @@
+use std::fmt;
+
+use crate::validation::{validate_voxel_data, MeshingError};
+use crate::voxel_constants::FACES;
+
+pub const RAYCASTABLE_BIT: u8 = 0x80;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum RaycastError {
+    InvalidVolume(MeshingError),
+    InvalidMaxDistance,
+}
+
+impl fmt::Display for RaycastError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            RaycastError::InvalidVolume(error) => error.fmt(f),
+            RaycastError::InvalidMaxDistance => {
+                write!(f, "ray max distance must not be NaN or negative")
+            }
+        }
+    }
+}
+
+impl std::error::Error for RaycastError {}
+
+impl From<MeshingError> for RaycastError {
+    fn from(error: MeshingError) -> Self {
+        RaycastError::InvalidVolume(error)
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq)]
+pub struct RaycastHit {
+    pub grid_position: [i64; 3],
+    pub face_dir: Option<usize>,
+    pub block_value: u8,
+    pub distance: f32,
+}
+
+impl RaycastHit {
+    pub fn normal(&self) -> [f32; 3] {
+        self.face_dir
+            .map_or([0.0; 3], |face_dir| FACES[face_dir].normal)
+    }
+}
+
+#[inline(always)]
+pub fn is_raycastable(block_value: u8) -> bool {
+    block_value & RAYCASTABLE_BIT != 0
+}
+
+pub fn try_raycast(
+    voxel_data: &[u8],
+    dims: [usize; 3],
+    origin: [f32; 3],
+    direction: [f32; 3],
+    max_distance: f32,
+) -> Result<Option<RaycastHit>, RaycastError> {
+    validate_voxel_data(voxel_data, dims)?;
+    if max_distance.is_nan() || max_distance < 0.0 {
+        return Err(RaycastError::InvalidMaxDistance);
+    }
+    Ok(raycast(voxel_data, dims, origin, direction, max_distance))
+}
+
+pub fn raycast(
+    voxel_data: &[u8],
+    dims: [usize; 3],
+    origin: [f32; 3],
+    direction: [f32; 3],
+    max_distance: f32,
+) -> Option<RaycastHit> {
+    let length = direction
+        .iter()
+        .map(|&d| d as f64 * d as f64)
+        .sum::<f64>()
+        .sqrt();
+    if !(length > 0.0 && length.is_finite()) {
+        return None;
+    }
+    let start = origin.map(|o| o as f64);
+    let dir = direction.map(|d| d as f64 / length);
+    let max_distance = max_distance as f64;
+    let dims_i = dims.map(|d| d as i64);
+
+    let mut cell = start.map(|s| s.floor() as i64);
+    let step: [i64; 3] = std::array::from_fn(|a| if dir[a] >= 0.0 { 1 } else { -1 });
+    let t_delta: [f64; 3] = std::array::from_fn(|a| {
+        if dir[a] != 0.0 {
+            (1.0 / dir[a]).abs()
+        } else {
+            f64::INFINITY
+        }
+    });
+    let mut t_max: [f64; 3] = std::array::from_fn(|a| {
+        if dir[a] == 0.0 {
+            return f64::INFINITY;
+        }
+        let boundary = if step[a] > 0 {
+            start[a].floor() + 1.0
+        } else {
+            start[a].floor()
+        };
+        (boundary - start[a]) / dir[a]
+    });
+
+    let mut last_step_axis: Option<usize> = None;
+    let mut entry_distance = 0.0;
+    let max_iterations = (max_distance.ceil().max(0.0) as i64)
+        .saturating_mul(3)
+        .saturating_add(dims_i.iter().sum::<i64>());
+
+    for _ in 0..max_iterations {
+        let min_t = t_max[0].min(t_max[1]).min(t_max[2]);
+        if min_t > max_distance {
+            break;
+        }
+
+        let can_cross_bounds = (0..3).any(|a| {
+            dir[a] != 0.0 && !(cell[a] < 0 && step[a] < 0) && !(cell[a] >= dims_i[a] && step[a] > 0)
+        });
+        if !can_cross_bounds {
+            break;
+        }
+
+        let inside = (0..3).all(|a| cell[a] >= 0 && cell[a] < dims_i[a]);
+        if inside {
+            let idx = (cell[0] as usize * dims[1] + cell[1] as usize) * dims[2] + cell[2] as usize;
+            let block_value = voxel_data[idx];
+            if is_raycastable(block_value) {
+                let axis = last_step_axis.unwrap_or_else(|| entry_axis(start, dir, step, cell));
+                return Some(RaycastHit {
+                    grid_position: cell,
+                    face_dir: Some(face_dir_facing(axis, step[axis])),
+                    block_value,
+                    distance: entry_distance as f32,
+                });
+            }
+        }
+
+        let axis = if t_max[0] < t_max[1] {
+            if t_max[0] < t_max[2] { 0 } else { 2 }
+        } else if t_max[1] < t_max[2] {
+            1
+        } else {
+            2
+        };
+
+        let previous = cell[axis];
+        entry_distance = t_max[axis];
+        cell[axis] += step[axis];
+        t_max[axis] += t_delta[axis];
+        last_step_axis = Some(axis);
+
+        let was_inside = previous >= 0 && previous < dims_i[axis];
+        let now_outside = cell[axis] < 0 || cell[axis] >= dims_i[axis];
+        if was_inside && now_outside {
+            let mut grid_position = cell;
+            grid_position[axis] = previous;
+            return Some(RaycastHit {
+                grid_position,
+                face_dir: None,
+                block_value: RAYCASTABLE_BIT,
+                distance: entry_distance as f32,
+            });
+        }
+    }
+
+    None
+}
+
+#[inline(always)]
+fn face_dir_facing(axis: usize, step: i64) -> usize {
+    axis * 2 + if step > 0 { 1 } else { 0 }
+}
+
+fn entry_axis(start: [f64; 3], dir: [f64; 3], step: [i64; 3], cell: [i64; 3]) -> usize {
+    let entry = |a: usize| {
+        if dir[a] == 0.0 {
+            return f64::NEG_INFINITY;
+        }
+        let boundary = (if step[a] > 0 { cell[a] } else { cell[a] + 1 }) as f64;
+        (boundary - start[a]) / dir[a]
+    };
+    (0..3)
+        .map(|a| (a, entry(a)))
+        .fold((0, f64::NEG_INFINITY), |best, (a, t)| {
+            if t > best.1 { (a, t) } else { best }
+        })
+        .0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const DIMS: [usize; 3] = [16, 16, 16];
+
+    fn grid(cells: &[([usize; 3], u8)]) -> Vec<u8> {
+        let mut data = vec![0u8; DIMS[0] * DIMS[1] * DIMS[2]];
+        for &(cell, value) in cells {
+            data[cell[0] * DIMS[1] * DIMS[2] + cell[1] * DIMS[2] + cell[2]] = value;
+        }
+        data
+    }
+
+    fn cast(data: &[u8], origin: [f32; 3], direction: [f32; 3]) -> Option<RaycastHit> {
+        raycast(data, DIMS, origin, direction, 1000.0)
+    }
+
+    #[test]
+    fn axis_aligned_rays_hit_facing_side() {
+        let data = grid(&[([5, 8, 8], 0x81), ([8, 5, 8], 0x82), ([8, 8, 5], 0x83)]);
+        let cases = [
+            ([-1.0, 8.5, 8.5], [1.0, 0.0, 0.0], [5, 8, 8], 1, 0x81, 6.0),
+            ([20.0, 8.5, 8.5], [-1.0, 0.0, 0.0], [5, 8, 8], 0, 0x81, 14.0),
+            ([8.5, -1.0, 8.5], [0.0, 1.0, 0.0], [8, 5, 8], 3, 0x82, 6.0),
+            ([8.5, 8.5, -1.0], [0.0, 0.0, 1.0], [8, 8, 5], 5, 0x83, 6.0),
+        ];
+        for (origin, direction, position, face_dir, block_value, distance) in cases {
+            let hit = cast(&data, origin, direction).unwrap();
+            assert_eq!(hit.grid_position, position);
+            assert_eq!(hit.face_dir, Some(face_dir));
+            assert_eq!(hit.normal(), FACES[face_dir].normal);
+            assert_eq!(hit.block_value, block_value);
+            assert_eq!(hit.distance, distance);
+        }
+    }
+
+    #[test]
+    fn skips_voxels_without_raycastable_bit() {
+        let data = grid(&[([5, 8, 8], 1), ([9, 8, 8], 0x81)]);
+        let hit = cast(&data, [-1.0, 8.5, 8.5], [1.0, 0.0, 0.0]).unwrap();
+        assert_eq!(hit.grid_position, [9, 8, 8]);
+
+        let data = grid(&[([5, 8, 8], 1)]);
+        let hit = cast(&data, [-5.0, 8.5, 8.5], [1.0, 0.0, 0.0]).unwrap();
+        assert_eq!(hit.grid_position, [15, 8, 8]);
+        assert_eq!(hit.face_dir, None);
+        assert_eq!(hit.normal(), [0.0; 3]);
+        assert_eq!(hit.block_value, RAYCASTABLE_BIT);
+    }
+
+    #[test]
+    fn grazing_ray_along_face_plane_hits_next_cell() {
+        let data = grid(&[([4, 3, 8], 0x81), ([6, 3, 8], 0x82)]);
+        let hit = cast(&data, [0.5, 3.0, 8.5], [1.0, 0.0, 0.0]).unwrap();
+        assert_eq!(hit.grid_position, [4, 3, 8]);
+        assert_eq!(hit.face_dir, Some(1));
+
+        let hit = cast(&data, [0.5, 4.0, 8.5], [1.0, 0.0, 0.0]).unwrap();
+        assert_eq!(hit.face_dir, None);
+        assert_eq!(hit.grid_position, [15, 4, 8]);
+
+        let data = grid(&[([5, 5, 8], 0x81)]);
+        let hit = cast(&data, [0.0, 0.0, 8.5], [1.0, 1.0, 0.0]).unwrap();
+        assert_eq!(hit.grid_position, [5, 5, 8]);
+        assert!(matches!(hit.face_dir, Some(1) | Some(3)));
+        assert!((hit.distance - 5.0 * 2f32.sqrt()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn shallow_diagonal_enters_through_side_face() {
+        let data = grid(&[([5, 1, 8], 0x81)]);
+        let hit = cast(&data, [0.5, 0.5, 8.5], [10.0, 1.0, 0.0]).unwrap();
+        assert_eq!(hit.grid_position, [5, 1, 8]);
+        assert_eq!(hit.face_dir, Some(3));
+        assert!((hit.distance - 0.5 * 101f32.sqrt()).abs() < 1e-4);
+    }
+
+    #[test]
+    fn starting_inside_voxel_reports_entry_face() {
+        let data = grid(&[([5, 8, 8], 0x81)]);
+        let hit = cast(&data, [5.5, 8.5, 8.5], [1.0, 0.0, 0.0]).unwrap();
+        assert_eq!(hit.grid_position, [5, 8, 8]);
+        assert_eq!(hit.distance, 0.0);
+        assert_eq!(hit.face_dir, Some(1));
+
+        let hit = cast(&data, [5.25, 8.5, 8.75], [1.0, 0.0, -2.0]).unwrap();
+        assert_eq!(hit.face_dir, Some(4));
+    }
+
+    #[test]
+    fn misses_and_limits() {
+        let data = grid(&[([15, 8, 8], 0x81)]);
+        assert_eq!(cast(&data, [-5.0, 8.5, 8.5], [-1.0, 0.0, 0.0]), None);
+        assert_eq!(cast(&data, [-5.0, -5.0, -5.0], [-1.0, -1.0, -1.0]), None);
+        assert_eq!(cast(&data, [8.5, 8.5, 8.5], [0.0, 0.0, 0.0]), None);
+        assert_eq!(
+            raycast(&data, DIMS, [-100.0, 8.5, 8.5], [1.0, 0.0, 0.0], 10.0),
+            None
+        );
+
+        let hit = cast(
+            &vec![0; 16 * 16 * 16],
+            [500.0, 10.0, 500.0],
+            [0.0, -1.0, 0.0],
+        )
+        .unwrap();
+        assert_eq!(hit.grid_position, [500, 0, 500]);
+        assert_eq!(hit.face_dir, None);
+    }
+
+    #[test]
+    fn unbounded_distance_keeps_walking() {
+        let data = grid(&[([15, 8, 8], 0x81)]);
+        let hit = raycast(
+            &data,
+            DIMS,
+            [-1e6, 8.5, 8.5],
+            [1.0, 0.0, 0.0],
+            f32::INFINITY,
+        )
+        .unwrap();
+        assert_eq!(hit.grid_position, [15, 8, 8]);
+        assert_eq!(hit.face_dir, Some(1));
+        assert_eq!(
+            raycast(
+                &data,
+                DIMS,
+                [-5.0, 8.5, 8.5],
+                [-1.0, 0.0, 0.0],
+                f32::INFINITY
+            ),
+            None
+        );
+        assert_eq!(
+            raycast(
+                &data,
+                DIMS,
+                [20.0, 20.0, 8.5],
+                [1.0, 0.0, 0.0],
+                f32::INFINITY
+            ),
+            None
+        );
+        assert_eq!(
+            try_raycast(&data, DIMS, [0.0; 3], [1.0, 0.0, 0.0], f32::NAN),
+            Err(RaycastError::InvalidMaxDistance)
+        );
+    }
+
+    #[test]
+    fn rejects_mismatched_voxel_data() {
+        assert_eq!(
+            try_raycast(&[0; 3], [2, 2, 1], [0.0; 3], [1.0, 0.0, 0.0], 10.0),
+            Err(RaycastError::InvalidVolume(MeshingError::VoxelDataLength {
+                expected: 4,
+                actual: 3
+            }))
+        );
+    }
+}
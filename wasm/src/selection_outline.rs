@@ -0,0 +1,187 @@
+use crate::find_exterior_faces::is_selection_set;
+use crate::validation::{MeshingError, validate_selection_data};
+
+#[derive(Default)]
+pub struct SelectionOutline {
+    segments: Vec<f32>,
+}
+
+impl SelectionOutline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn segments(&self) -> &[f32] {
+        &self.segments
+    }
+
+    pub fn segment_count(&self) -> usize {
+        self.segments.len() / 6
+    }
+
+    pub fn try_build(
+        &mut self,
+        selection_data: &[u8],
+        selection_dim_x: usize,
+        selection_dim_y: usize,
+        selection_dim_z: usize,
+    ) -> Result<&[f32], MeshingError> {
+        validate_selection_data(
+            selection_data,
+            [selection_dim_x, selection_dim_y, selection_dim_z],
+        )?;
+        Ok(self.build(
+            selection_data,
+            selection_dim_x,
+            selection_dim_y,
+            selection_dim_z,
+        ))
+    }
+
+    pub fn build(
+        &mut self,
+        selection_data: &[u8],
+        selection_dim_x: usize,
+        selection_dim_y: usize,
+        selection_dim_z: usize,
+    ) -> &[f32] {
+        self.segments.clear();
+        let dims = [selection_dim_x, selection_dim_y, selection_dim_z];
+        let is_selected = |cell: [usize; 3], offset: [usize; 3]| {
+            cell.iter().zip(offset).all(|(&c, o)| c + o >= 1)
+                && is_selection_set(
+                    selection_data,
+                    cell[0] + offset[0] - 1,
+                    cell[1] + offset[1] - 1,
+                    cell[2] + offset[2] - 1,
+                    selection_dim_x,
+                    selection_dim_y,
+                    selection_dim_z,
+                )
+        };
+
+        for axis in 0..3 {
+            let u = (axis + 1) % 3;
+            let v = (axis + 2) % 3;
+            for pu in 0..=dims[u] {
+                for pv in 0..=dims[v] {
+                    let mut run_start = None;
+                    for k in 0..=dims[axis] {
+                        let mut lattice = [0; 3];
+                        lattice[axis] = k;
+                        lattice[u] = pu;
+                        lattice[v] = pv;
+
+                        let on_edge = k < dims[axis] && {
+                            let mut corners = [false; 4];
+                            for (corner, occupied) in corners.iter_mut().enumerate() {
+                                let mut offset = [1; 3];
+                                offset[u] = corner & 1;
+                                offset[v] = corner >> 1;
+                                *occupied = is_selected(lattice, offset);
+                            }
+                            is_silhouette_edge(corners)
+                        };
+
+                        match (on_edge, run_start) {
+                            (true, None) => run_start = Some(lattice),
+                            (false, Some(start)) => {
+                                self.push_segment(start, lattice);
+                                run_start = None;
+                            }
+                            _ => {}
+                        }
+                    }
+                }
+            }
+        }
+
+        &self.segments
+    }
+
+    fn push_segment(&mut self, start: [usize; 3], end: [usize; 3]) {
+        self.segments
+            .extend(start.iter().chain(&end).map(|&c| c as f32));
+    }
+}
+
+#[inline(always)]
+fn is_silhouette_edge(corners: [bool; 4]) -> bool {
+    let occupied = corners.iter().filter(|&&c| c).count();
+    occupied % 2 == 1 || (occupied == 2 && corners[0] == corners[3])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn selection(dims: [usize; 3], cells: &[[usize; 3]]) -> Vec<u8> {
+        let mut data = vec![0u8; dims[0] * dims[1] * dims[2]];
+        for cell in cells {
+            data[cell[0] * dims[1] * dims[2] + cell[1] * dims[2] + cell[2]] = 1;
+        }
+        data
+    }
+
+    fn segments(dims: [usize; 3], cells: &[[usize; 3]]) -> Vec<[f32; 6]> {
+        let data = selection(dims, cells);
+        let mut outline = SelectionOutline::new();
+        outline
+            .build(&data, dims[0], dims[1], dims[2])
+            .chunks_exact(6)
+            .map(|s| [s[0], s[1], s[2], s[3], s[4], s[5]])
+            .collect()
+    }
+
+    fn length(segment: &[f32; 6]) -> f32 {
+        (0..3).map(|a| (segment[a + 3] - segment[a]).abs()).sum()
+    }
+
+    #[test]
+    fn single_voxel_has_twelve_cube_edges() {
+        let edges = segments([3, 3, 3], &[[1, 1, 1]]);
+        assert_eq!(edges.len(), 12);
+        assert!(edges.iter().all(|e| length(e) == 1.0));
+        assert!(edges.contains(&[1.0, 1.0, 1.0, 2.0, 1.0, 1.0]));
+        assert!(edges.contains(&[2.0, 2.0, 1.0, 2.0, 2.0, 2.0]));
+    }
+
+    #[test]
+    fn straight_runs_merge_into_single_segments() {
+        let edges = segments([4, 1, 1], &[[0, 0, 0], [1, 0, 0], [2, 0, 0], [3, 0, 0]]);
+        assert_eq!(edges.len(), 12);
+        assert_eq!(edges.iter().filter(|e| length(e) == 4.0).count(), 4);
+
+        let plate: Vec<[usize; 3]> = (0..3)
+            .flat_map(|x| (0..3).map(move |z| [x, 0, z]))
+            .collect();
+        let edges = segments([3, 1, 3], &plate);
+        assert_eq!(edges.len(), 12);
+        assert!(edges.iter().all(|e| length(e) == 3.0 || length(e) == 1.0));
+    }
+
+    #[test]
+    fn concave_and_diagonal_edges_are_outlined() {
+        let edges = segments([2, 1, 2], &[[0, 0, 0], [1, 0, 0], [0, 0, 1]]);
+        assert_eq!(edges.len(), 18);
+        assert!(edges.contains(&[1.0, 0.0, 1.0, 1.0, 1.0, 1.0]));
+
+        let edges = segments([2, 2, 1], &[[0, 0, 0], [1, 1, 0]]);
+        assert_eq!(edges.len(), 19);
+        assert!(edges.contains(&[1.0, 1.0, 0.0, 1.0, 1.0, 1.0]));
+        assert!(edges.contains(&[0.0, 1.0, 0.0, 2.0, 1.0, 0.0]));
+    }
+
+    #[test]
+    fn rejects_short_selection_buffer() {
+        let mut outline = SelectionOutline::new();
+        assert_eq!(
+            outline.try_build(&[1, 0], 2, 2, 1).err(),
+            Some(MeshingError::SelectionDataLength {
+                expected: 4,
+                actual: 2
+            })
+        );
+        assert!(outline.try_build(&[], 0, 0, 0).unwrap().is_empty());
+    }
+}
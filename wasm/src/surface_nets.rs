@@ -0,0 +1,766 @@
+use crate::find_exterior_faces::is_selection_set;
+use crate::mesh_arrays::MeshArrays;
+use crate::texture_coords::{get_atlas_index, get_texture_coordinates};
+use crate::validation::{validate_mesh_input, MeshingError};
+
+const NO_VERTEX: u32 = u32::MAX;
+
+const CUBE_EDGES: [(usize, usize); 12] = [
+    (0, 1),
+    (2, 3),
+    (4, 5),
+    (6, 7),
+    (0, 2),
+    (1, 3),
+    (4, 6),
+    (5, 7),
+    (0, 4),
+    (1, 5),
+    (2, 6),
+    (3, 7),
+];
+
+#[derive(Default)]
+pub struct SurfaceNetsMesher {
+    cell_vertices: Vec<u32>,
+    seam_normals: Vec<(u32, [f32; 3])>,
+}
+
+impl SurfaceNetsMesher {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn extract_surface(
+        &mut self,
+        voxel_data: &[u8],
+        texture_width: i32,
+        block_atlas_mapping: &[i32],
+        dim_x: usize,
+        dim_y: usize,
+        dim_z: usize,
+        mesh_arrays: &mut MeshArrays,
+        selection_data: &[u8],
+        selection_dim_x: usize,
+        selection_dim_y: usize,
+        selection_dim_z: usize,
+        selection_empty: bool,
+    ) {
+        self.extract_region(
+            voxel_data,
+            texture_width,
+            block_atlas_mapping,
+            [dim_x, dim_y, dim_z],
+            [0, 0, 0],
+            [dim_x, dim_y, dim_z],
+            mesh_arrays,
+            selection_data,
+            selection_dim_x,
+            selection_dim_y,
+            selection_dim_z,
+            selection_empty,
+        );
+    }
+
+    pub fn try_extract_surface(
+        &mut self,
+        voxel_data: &[u8],
+        texture_width: i32,
+        block_atlas_mapping: &[i32],
+        dim_x: usize,
+        dim_y: usize,
+        dim_z: usize,
+        mesh_arrays: &mut MeshArrays,
+        selection_data: &[u8],
+        selection_dim_x: usize,
+        selection_dim_y: usize,
+        selection_dim_z: usize,
+        selection_empty: bool,
+    ) -> Result<(), MeshingError> {
+        validate_mesh_input(
+            voxel_data,
+            texture_width,
+            block_atlas_mapping,
+            [dim_x, dim_y, dim_z],
+            selection_data,
+            selection_dim_x,
+            selection_dim_y,
+            selection_dim_z,
+            selection_empty,
+        )?;
+        self.extract_surface(
+            voxel_data,
+            texture_width,
+            block_atlas_mapping,
+            dim_x,
+            dim_y,
+            dim_z,
+            mesh_arrays,
+            selection_data,
+            selection_dim_x,
+            selection_dim_y,
+            selection_dim_z,
+            selection_empty,
+        );
+        Ok(())
+    }
+
+    pub fn extract_surface_with_halo(
+        &mut self,
+        voxel_data: &[u8],
+        texture_width: i32,
+        block_atlas_mapping: &[i32],
+        dim_x: usize,
+        dim_y: usize,
+        dim_z: usize,
+        mesh_arrays: &mut MeshArrays,
+        selection_data: &[u8],
+        selection_dim_x: usize,
+        selection_dim_y: usize,
+        selection_dim_z: usize,
+        selection_empty: bool,
+    ) {
+        self.extract_region(
+            voxel_data,
+            texture_width,
+            block_atlas_mapping,
+            [dim_x + 2, dim_y + 2, dim_z + 2],
+            [1, 1, 1],
+            [dim_x, dim_y, dim_z],
+            mesh_arrays,
+            selection_data,
+            selection_dim_x,
+            selection_dim_y,
+            selection_dim_z,
+            selection_empty,
+        );
+    }
+
+    pub fn try_extract_surface_with_halo(
+        &mut self,
+        voxel_data: &[u8],
+        texture_width: i32,
+        block_atlas_mapping: &[i32],
+        dim_x: usize,
+        dim_y: usize,
+        dim_z: usize,
+        mesh_arrays: &mut MeshArrays,
+        selection_data: &[u8],
+        selection_dim_x: usize,
+        selection_dim_y: usize,
+        selection_dim_z: usize,
+        selection_empty: bool,
+    ) -> Result<(), MeshingError> {
+        let dims = [dim_x, dim_y, dim_z];
+        let padded = [
+            dim_x.checked_add(2),
+            dim_y.checked_add(2),
+            dim_z.checked_add(2),
+        ];
+        let [Some(padded_x), Some(padded_y), Some(padded_z)] = padded else {
+            return Err(MeshingError::DimensionOverflow { dims });
+        };
+        validate_mesh_input(
+            voxel_data,
+            texture_width,
+            block_atlas_mapping,
+            [padded_x, padded_y, padded_z],
+            selection_data,
+            selection_dim_x,
+            selection_dim_y,
+            selection_dim_z,
+            selection_empty,
+        )?;
+        self.extract_surface_with_halo(
+            voxel_data,
+            texture_width,
+            block_atlas_mapping,
+            dim_x,
+            dim_y,
+            dim_z,
+            mesh_arrays,
+            selection_data,
+            selection_dim_x,
+            selection_dim_y,
+            selection_dim_z,
+            selection_empty,
+        );
+        Ok(())
+    }
+
+    fn extract_region(
+        &mut self,
+        voxel_data: &[u8],
+        texture_width: i32,
+        block_atlas_mapping: &[i32],
+        data_dims: [usize; 3],
+        origin: [usize; 3],
+        dims: [usize; 3],
+        mesh_arrays: &mut MeshArrays,
+        selection_data: &[u8],
+        selection_dim_x: usize,
+        selection_dim_y: usize,
+        selection_dim_z: usize,
+        selection_empty: bool,
+    ) {
+        debug_assert!(!mesh_arrays.is_packed());
+        mesh_arrays.reset();
+
+        let volume = Volume {
+            voxel_data,
+            data_dims,
+            origin,
+        };
+        let halo = origin != [0; 3];
+        let grid = [dims[0] + 1, dims[1] + 1, dims[2] + 1];
+        self.seam_normals.clear();
+        self.cell_vertices.clear();
+        self.cell_vertices
+            .resize(grid[0] * grid[1] * grid[2], NO_VERTEX);
+
+        for cx in -1..dims[0] as i32 {
+            for cy in -1..dims[1] as i32 {
+                for cz in -1..dims[2] as i32 {
+                    let mut corners = [0u8; 8];
+                    let mut solid_mask = 0u8;
+                    for (k, corner) in corners.iter_mut().enumerate() {
+                        let [ox, oy, oz] = corner_offset(k);
+                        *corner = volume.block_type(cx + ox, cy + oy, cz + oz);
+                        if *corner != 0 {
+                            solid_mask |= 1 << k;
+                        }
+                    }
+                    if solid_mask == 0 || solid_mask == 0xFF {
+                        continue;
+                    }
+
+                    let mut sum = [0.0f32; 3];
+                    let mut crossings = 0;
+                    for &(a, b) in &CUBE_EDGES {
+                        if (solid_mask >> a) & 1 != (solid_mask >> b) & 1 {
+                            for (axis, value) in sum.iter_mut().enumerate() {
+                                *value += (((a >> axis) & 1) + ((b >> axis) & 1)) as f32 * 0.5;
+                            }
+                            crossings += 1;
+                        }
+                    }
+
+                    let is_selected = !selection_empty
+                        && (0..8).any(|k| {
+                            let [ox, oy, oz] = corner_offset(k);
+                            let (x, y, z) = (cx + ox, cy + oy, cz + oz);
+                            x >= 0
+                                && y >= 0
+                                && z >= 0
+                                && is_selection_set(
+                                    selection_data,
+                                    x as usize,
+                                    y as usize,
+                                    z as usize,
+                                    selection_dim_x,
+                                    selection_dim_y,
+                                    selection_dim_z,
+                                )
+                        });
+
+                    let cell = cell_index(grid, cx, cy, cz);
+                    self.cell_vertices[cell] = mesh_arrays.vertex_count as u32;
+                    let on_seam = [cx, cy, cz]
+                        .iter()
+                        .zip(dims)
+                        .any(|(&c, dim)| c == -1 || c == dim as i32 - 1);
+                    if halo && on_seam {
+                        let gradient = corner_gradient(solid_mask);
+                        if gradient != [0.0; 3] {
+                            self.seam_normals
+                                .push((mesh_arrays.vertex_count as u32, gradient));
+                        }
+                    }
+                    let inv = 1.0 / crossings as f32;
+                    mesh_arrays.push_vertex(
+                        cx as f32 + 0.5 + sum[0] * inv,
+                        cy as f32 + 0.5 + sum[1] * inv,
+                        cz as f32 + 0.5 + sum[2] * inv,
+                    );
+                    mesh_arrays.push_normal(0.0, 0.0, 0.0);
+                    mesh_arrays.push_uv(0.0, 0.0);
+                    mesh_arrays.push_ao(1.0);
+                    mesh_arrays.push_is_selected(is_selected as u8);
+                    mesh_arrays.push_block_type(dominant_block_type(&corners));
+                    mesh_arrays.increment_vertex();
+                }
+            }
+        }
+
+        for axis in 0..3 {
+            let u = (axis + 1) % 3;
+            let v = (axis + 2) % 3;
+            for a in -1..dims[axis] as i32 {
+                for iu in 0..dims[u] as i32 {
+                    for iv in 0..dims[v] as i32 {
+                        let mut p = [0i32; 3];
+                        p[axis] = a;
+                        p[u] = iu;
+                        p[v] = iv;
+                        let mut q = p;
+                        q[axis] += 1;
+
+                        let p_solid = volume.block_type(p[0], p[1], p[2]) != 0;
+                        let q_solid = volume.block_type(q[0], q[1], q[2]) != 0;
+                        if p_solid == q_solid {
+                            continue;
+                        }
+                        let solid_layer = if p_solid { p[axis] } else { q[axis] };
+                        if solid_layer < 0 || solid_layer >= dims[axis] as i32 {
+                            continue;
+                        }
+
+                        let corner_cell = |du: i32, dv: i32| {
+                            let mut c = p;
+                            c[u] -= du;
+                            c[v] -= dv;
+                            self.cell_vertices[cell_index(grid, c[0], c[1], c[2])]
+                        };
+                        let quad = if p_solid {
+                            [
+                                corner_cell(1, 1),
+                                corner_cell(0, 1),
+                                corner_cell(0, 0),
+                                corner_cell(1, 0),
+                            ]
+                        } else {
+                            [
+                                corner_cell(1, 1),
+                                corner_cell(1, 0),
+                                corner_cell(0, 0),
+                                corner_cell(0, 1),
+                            ]
+                        };
+                        emit_quad(mesh_arrays, quad);
+                    }
+                }
+            }
+        }
+
+        for &(vertex, gradient) in &self.seam_normals {
+            let n = vertex as usize * 3;
+            mesh_arrays.normals[n..n + 3].copy_from_slice(&gradient);
+        }
+
+        for vertex in 0..mesh_arrays.vertex_count {
+            let normal = &mut mesh_arrays.normals[vertex * 3..vertex * 3 + 3];
+            let length =
+                (normal[0] * normal[0] + normal[1] * normal[1] + normal[2] * normal[2]).sqrt();
+            if length > 0.0 {
+                normal.iter_mut().for_each(|n| *n /= length);
+            }
+
+            let dominant_axis = (0..3)
+                .max_by(|&a, &b| normal[a].abs().total_cmp(&normal[b].abs()))
+                .unwrap_or(1);
+            let face_dir = dominant_axis * 2 + (normal[dominant_axis] < 0.0) as usize;
+            let block_type = mesh_arrays.block_types[vertex] as u8;
+            let texture_index = get_atlas_index(block_atlas_mapping, block_type, face_dir);
+            let texture_coords = get_texture_coordinates(texture_index, texture_width);
+            mesh_arrays.uvs[vertex * 2] = texture_coords[0];
+            mesh_arrays.uvs[vertex * 2 + 1] = texture_coords[1];
+        }
+
+        mesh_arrays.translucent_vertex_start = mesh_arrays.vertex_count;
+        mesh_arrays.translucent_index_start = mesh_arrays.index_count;
+    }
+}
+
+struct Volume<'a> {
+    voxel_data: &'a [u8],
+    data_dims: [usize; 3],
+    origin: [usize; 3],
+}
+
+impl Volume<'_> {
+    #[inline(always)]
+    fn block_type(&self, x: i32, y: i32, z: i32) -> u8 {
+        let dx = self.origin[0] as i32 + x;
+        let dy = self.origin[1] as i32 + y;
+        let dz = self.origin[2] as i32 + z;
+        if dx < 0
+            || dy < 0
+            || dz < 0
+            || dx >= self.data_dims[0] as i32
+            || dy >= self.data_dims[1] as i32
+            || dz >= self.data_dims[2] as i32
+        {
+            return 0;
+        }
+        let idx = dx as usize * self.data_dims[1] * self.data_dims[2]
+            + dy as usize * self.data_dims[2]
+            + dz as usize;
+        self.voxel_data[idx] & 0x7F
+    }
+}
+
+#[inline(always)]
+fn corner_offset(corner: usize) -> [i32; 3] {
+    [
+        (corner & 1) as i32,
+        ((corner >> 1) & 1) as i32,
+        ((corner >> 2) & 1) as i32,
+    ]
+}
+
+fn corner_gradient(solid_mask: u8) -> [f32; 3] {
+    let mut gradient = [0.0f32; 3];
+    for k in 0..8 {
+        let sign = if (solid_mask >> k) & 1 != 0 {
+            -1.0
+        } else {
+            1.0
+        };
+        for (value, offset) in gradient.iter_mut().zip(corner_offset(k)) {
+            *value += sign * (offset * 2 - 1) as f32;
+        }
+    }
+    gradient
+}
+
+#[inline(always)]
+fn cell_index(grid: [usize; 3], cx: i32, cy: i32, cz: i32) -> usize {
+    ((cx + 1) as usize * grid[1] + (cy + 1) as usize) * grid[2] + (cz + 1) as usize
+}
+
+fn dominant_block_type(corners: &[u8; 8]) -> u8 {
+    let mut best = 0;
+    let mut best_count = 0;
+    for &candidate in corners {
+        if candidate == 0 {
+            continue;
+        }
+        let count = corners.iter().filter(|&&c| c == candidate).count();
+        if count > best_count || (count == best_count && candidate < best) {
+            best = candidate;
+            best_count = count;
+        }
+    }
+    best
+}
+
+fn emit_quad(mesh_arrays: &mut MeshArrays, quad: [u32; 4]) {
+    let position = |vertex: u32| {
+        let v = vertex as usize * 3;
+        [
+            mesh_arrays.vertices[v],
+            mesh_arrays.vertices[v + 1],
+            mesh_arrays.vertices[v + 2],
+        ]
+    };
+    let (p0, p1, p2, p3) = (
+        position(quad[0]),
+        position(quad[1]),
+        position(quad[2]),
+        position(quad[3]),
+    );
+    let d0 = [p2[0] - p0[0], p2[1] - p0[1], p2[2] - p0[2]];
+    let d1 = [p3[0] - p1[0], p3[1] - p1[1], p3[2] - p1[2]];
+    let face_normal = [
+        d0[1] * d1[2] - d0[2] * d1[1],
+        d0[2] * d1[0] - d0[0] * d1[2],
+        d0[0] * d1[1] - d0[1] * d1[0],
+    ];
+    for &vertex in &quad {
+        let n = vertex as usize * 3;
+        for (normal, component) in mesh_arrays.normals[n..n + 3].iter_mut().zip(face_normal) {
+            *normal += component;
+        }
+    }
+
+    mesh_arrays.push_index(quad[0]);
+    mesh_arrays.push_index(quad[1]);
+    mesh_arrays.push_index(quad[2]);
+    mesh_arrays.push_index(quad[0]);
+    mesh_arrays.push_index(quad[2]);
+    mesh_arrays.push_index(quad[3]);
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::texture_coords::expand_block_atlas_mapping;
+    use std::collections::HashMap;
+
+    fn extract(voxel_data: &[u8], dims: [usize; 3], block_types: usize) -> MeshArrays {
+        let mut mesher = SurfaceNetsMesher::new();
+        let mut mesh_arrays = MeshArrays::new(0, 0);
+        let mapping = expand_block_atlas_mapping(&(0..block_types as i32).collect::<Vec<_>>());
+        mesher.extract_surface(
+            voxel_data,
+            4,
+            &mapping,
+            dims[0],
+            dims[1],
+            dims[2],
+            &mut mesh_arrays,
+            &[],
+            0,
+            0,
+            0,
+            true,
+        );
+        mesh_arrays
+    }
+
+    fn sphere(dim: usize, radius: f32) -> Vec<u8> {
+        let center = dim as f32 / 2.0;
+        let mut data = vec![0u8; dim * dim * dim];
+        for x in 0..dim {
+            for y in 0..dim {
+                for z in 0..dim {
+                    let d = [x, y, z].map(|c| c as f32 + 0.5 - center);
+                    if d[0] * d[0] + d[1] * d[1] + d[2] * d[2] <= radius * radius {
+                        data[x * dim * dim + y * dim + z] = if d[1] < 0.0 { 1 } else { 2 };
+                    }
+                }
+            }
+        }
+        data
+    }
+
+    fn assert_closed_manifold(mesh_arrays: &MeshArrays) {
+        let mut edges: HashMap<(u32, u32), i32> = HashMap::new();
+        for triangle in mesh_arrays.indices[..mesh_arrays.index_count].chunks_exact(3) {
+            for k in 0..3 {
+                let (a, b) = (triangle[k], triangle[(k + 1) % 3]);
+                *edges.entry((a.min(b), a.max(b))).or_default() += if a < b { 1 } else { -1 };
+            }
+        }
+        assert!(edges.values().all(|&balance| balance == 0));
+    }
+
+    #[test]
+    fn single_voxel_produces_closed_blob() {
+        let mesh_arrays = extract(&[1], [1, 1, 1], 1);
+        assert_eq!(mesh_arrays.vertex_count, 8);
+        assert_eq!(mesh_arrays.index_count, 36);
+        assert_closed_manifold(&mesh_arrays);
+    }
+
+    #[test]
+    fn sphere_has_outward_unit_normals() {
+        let dim = 12;
+        let mesh_arrays = extract(&sphere(dim, 4.5), [dim, dim, dim], 2);
+        assert!(mesh_arrays.vertex_count > 0);
+        assert_closed_manifold(&mesh_arrays);
+
+        let center = dim as f32 / 2.0;
+        for v in 0..mesh_arrays.vertex_count {
+            let n = &mesh_arrays.normals[v * 3..v * 3 + 3];
+            let p = &mesh_arrays.vertices[v * 3..v * 3 + 3];
+            let length = (n[0] * n[0] + n[1] * n[1] + n[2] * n[2]).sqrt();
+            assert!((length - 1.0).abs() < 1e-4);
+            let outward = (p[0] - center) * n[0] + (p[1] - center) * n[1] + (p[2] - center) * n[2];
+            assert!(outward > 0.0);
+        }
+    }
+
+    #[test]
+    fn triangles_wind_along_smooth_normals() {
+        let dim = 10;
+        let mesh_arrays = extract(&sphere(dim, 3.5), [dim, dim, dim], 2);
+        let position = |i: u32| {
+            let v = i as usize * 3;
+            [
+                mesh_arrays.vertices[v],
+                mesh_arrays.vertices[v + 1],
+                mesh_arrays.vertices[v + 2],
+            ]
+        };
+        for triangle in mesh_arrays.indices[..mesh_arrays.index_count].chunks_exact(3) {
+            let (p0, p1, p2) = (
+                position(triangle[0]),
+                position(triangle[1]),
+                position(triangle[2]),
+            );
+            let e1 = [p1[0] - p0[0], p1[1] - p0[1], p1[2] - p0[2]];
+            let e2 = [p2[0] - p0[0], p2[1] - p0[1], p2[2] - p0[2]];
+            let cross = [
+                e1[1] * e2[2] - e1[2] * e2[1],
+                e1[2] * e2[0] - e1[0] * e2[2],
+                e1[0] * e2[1] - e1[1] * e2[0],
+            ];
+            let n = triangle[0] as usize * 3;
+            let normal = &mesh_arrays.normals[n..n + 3];
+            assert!(cross[0] * normal[0] + cross[1] * normal[1] + cross[2] * normal[2] > 0.0);
+        }
+    }
+
+    #[test]
+    fn vertices_carry_block_type_and_atlas_uvs() {
+        let dim = 10;
+        let mesh_arrays = extract(&sphere(dim, 3.5), [dim, dim, dim], 2);
+        let center = dim as f32 / 2.0;
+        for v in 0..mesh_arrays.vertex_count {
+            let y = mesh_arrays.vertices[v * 3 + 1];
+            let block_type = mesh_arrays.block_types[v];
+            if y < center - 1.0 {
+                assert_eq!(block_type, 1.0);
+            } else if y > center + 1.0 {
+                assert_eq!(block_type, 2.0);
+            }
+            let expected_u = get_texture_coordinates(block_type as i32 - 1, 4)[0];
+            assert_eq!(mesh_arrays.uvs[v * 2], expected_u);
+        }
+    }
+
+    fn extract_chunk(
+        world: &[u8],
+        world_dims: [usize; 3],
+        origin: [usize; 3],
+        size: [usize; 3],
+    ) -> MeshArrays {
+        let padded_dims = size.map(|d| d + 2);
+        let mut padded = vec![0u8; padded_dims.iter().product()];
+        for x in 0..padded_dims[0] {
+            for y in 0..padded_dims[1] {
+                for z in 0..padded_dims[2] {
+                    let world_pos = [x, y, z].map(|c| c as i32 - 1);
+                    let world_pos: [i32; 3] =
+                        std::array::from_fn(|a| world_pos[a] + origin[a] as i32);
+                    if (0..3).any(|a| world_pos[a] < 0 || world_pos[a] >= world_dims[a] as i32) {
+                        continue;
+                    }
+                    let [wx, wy, wz] = world_pos.map(|c| c as usize);
+                    padded[(x * padded_dims[1] + y) * padded_dims[2] + z] =
+                        world[(wx * world_dims[1] + wy) * world_dims[2] + wz];
+                }
+            }
+        }
+
+        let mut mesher = SurfaceNetsMesher::new();
+        let mut mesh_arrays = MeshArrays::new(0, 0);
+        let mapping = expand_block_atlas_mapping(&[0]);
+        mesher.extract_surface_with_halo(
+            &padded,
+            4,
+            &mapping,
+            size[0],
+            size[1],
+            size[2],
+            &mut mesh_arrays,
+            &[],
+            0,
+            0,
+            0,
+            true,
+        );
+        mesh_arrays
+    }
+
+    fn triangles(mesh_arrays: &MeshArrays, offset: [usize; 3]) -> Vec<[[i32; 3]; 3]> {
+        mesh_arrays.indices[..mesh_arrays.index_count]
+            .chunks_exact(3)
+            .map(|triangle| {
+                std::array::from_fn(|k| {
+                    let v = triangle[k] as usize * 3;
+                    std::array::from_fn(|a| {
+                        ((mesh_arrays.vertices[v + a] + offset[a] as f32) * 1000.0).round() as i32
+                    })
+                })
+            })
+            .collect()
+    }
+
+    #[test]
+    fn adjacent_halo_chunks_emit_seam_quads_once() {
+        let world_dims = [4, 3, 3];
+        let world: Vec<u8> = (0..36).map(|i| ((i * 7 + 3) % 5 < 2) as u8).collect();
+        let mut expected = triangles(&extract(&world, world_dims, 1), [0; 3]);
+
+        let left = extract_chunk(&world, world_dims, [0, 0, 0], [2, 3, 3]);
+        let right = extract_chunk(&world, world_dims, [2, 0, 0], [2, 3, 3]);
+        let mut combined = triangles(&left, [0; 3]);
+        combined.extend(triangles(&right, [2, 0, 0]));
+
+        let seam_solids = (0..9)
+            .filter(|&i| world[9 + i] != 0 || world[18 + i] != 0)
+            .count();
+        assert!(seam_solids > 0);
+        expected.sort();
+        combined.sort();
+        assert_eq!(combined, expected);
+    }
+
+    #[test]
+    fn adjacent_halo_chunks_share_seam_normals() {
+        let world_dims = [4, 3, 3];
+        let world: Vec<u8> = (0..36).map(|i| ((i * 7 + 3) % 5 < 2) as u8).collect();
+        let left = extract_chunk(&world, world_dims, [0, 0, 0], [2, 3, 3]);
+        let right = extract_chunk(&world, world_dims, [2, 0, 0], [2, 3, 3]);
+        let normals_by_position = |mesh_arrays: &MeshArrays, offset: f32| {
+            (0..mesh_arrays.vertex_count)
+                .map(|vertex| {
+                    let v = vertex * 3;
+                    let position: [i32; 3] = std::array::from_fn(|a| {
+                        let shift = if a == 0 { offset } else { 0.0 };
+                        ((mesh_arrays.vertices[v + a] + shift) * 1000.0).round() as i32
+                    });
+                    let normal: [i32; 3] = std::array::from_fn(|a| {
+                        (mesh_arrays.normals[v + a] * 1000.0).round() as i32
+                    });
+                    (position, normal)
+                })
+                .collect::<HashMap<_, _>>()
+        };
+
+        let left_normals = normals_by_position(&left, 0.0);
+        let right_normals = normals_by_position(&right, 2.0);
+        let mut shared = 0;
+        for (position, normal) in &left_normals {
+            if let Some(right_normal) = right_normals.get(position) {
+                assert_eq!(normal, right_normal, "seam vertex {position:?}");
+                shared += 1;
+            }
+        }
+        assert!(shared > 0);
+    }
+
+    #[test]
+    fn halo_matches_unpadded_volume_interior() {
+        let data = [1u8, 1, 0, 1, 1, 1, 0, 1];
+        let mut padded = vec![0u8; 4 * 4 * 4];
+        for x in 0..2 {
+            for y in 0..2 {
+                for z in 0..2 {
+                    padded[(x + 1) * 16 + (y + 1) * 4 + z + 1] = data[x * 4 + y * 2 + z];
+                }
+            }
+        }
+        let plain = extract(&data, [2, 2, 2], 1);
+
+        let mut mesher = SurfaceNetsMesher::new();
+        let mut halo = MeshArrays::new(0, 0);
+        let mapping = expand_block_atlas_mapping(&[0]);
+        mesher.extract_surface_with_halo(
+            &padded,
+            4,
+            &mapping,
+            2,
+            2,
+            2,
+            &mut halo,
+            &[],
+            0,
+            0,
+            0,
+            true,
+        );
+
+        assert_eq!(halo.vertex_count, plain.vertex_count);
+        assert_eq!(
+            halo.indices[..halo.index_count],
+            plain.indices[..plain.index_count]
+        );
+        assert_eq!(
+            halo.vertices[..halo.vertex_count * 3],
+            plain.vertices[..plain.vertex_count * 3]
+        );
+    }
+}
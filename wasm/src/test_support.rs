@@ -0,0 +1,39 @@
+use crate::mesh_arrays::MeshArrays;
+use crate::packed_vertex::PACKED_WORDS_PER_VERTEX;
+
+pub(crate) fn assert_same_mesh(a: &MeshArrays, b: &MeshArrays) {
+    assert_eq!(a.format, b.format);
+    assert_eq!(a.vertex_count, b.vertex_count);
+    assert_eq!(a.index_count, b.index_count);
+    assert_eq!(a.translucent_vertex_start, b.translucent_vertex_start);
+    assert_eq!(a.translucent_index_start, b.translucent_index_start);
+    assert_eq!(a.face_index_ranges, b.face_index_ranges);
+    assert_eq!(
+        a.translucent_face_index_ranges,
+        b.translucent_face_index_ranges
+    );
+    assert_eq!(a.indices[..a.index_count], b.indices[..b.index_count]);
+
+    let vertex_count = a.vertex_count;
+    let buffers = [
+        ("vertices", &a.vertices, &b.vertices, 3),
+        ("normals", &a.normals, &b.normals, 3),
+        ("uvs", &a.uvs, &b.uvs, 2),
+        ("ao", &a.ao, &b.ao, 1),
+        ("is_selected", &a.is_selected, &b.is_selected, 1),
+        ("block_types", &a.block_types, &b.block_types, 1),
+        ("tile_origins", &a.tile_origins, &b.tile_origins, 2),
+        ("colors", &a.colors, &b.colors, 4),
+    ];
+    for (name, a_buffer, b_buffer, components) in buffers {
+        let len = vertex_count * components;
+        assert_eq!(a_buffer.get(..len), b_buffer.get(..len), "{name} differ");
+    }
+    let packed_len = vertex_count * PACKED_WORDS_PER_VERTEX;
+    assert_eq!(a.packed.get(..packed_len), b.packed.get(..packed_len));
+    assert_eq!(
+        a.quad_ids.get(..vertex_count),
+        b.quad_ids.get(..vertex_count)
+    );
+    assert_eq!(a.pick_quads, b.pick_quads);
+}
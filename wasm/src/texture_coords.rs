@@ -1,3 +1,26 @@
+use crate::voxel_constants::FACES;
+
+pub const FACES_PER_BLOCK: usize = FACES.len();
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Default)]
+pub enum UvMode {
+    #[default]
+    Atlas,
+    Tiled,
+}
+
+#[inline(always)]
+pub fn get_atlas_index(block_atlas_mapping: &[i32], block_type: u8, face_dir: usize) -> i32 {
+    block_atlas_mapping[(block_type as usize - 1) * FACES_PER_BLOCK + face_dir]
+}
+
+pub fn expand_block_atlas_mapping(per_block_mapping: &[i32]) -> Vec<i32> {
+    per_block_mapping
+        .iter()
+        .flat_map(|&texture_index| [texture_index; FACES_PER_BLOCK])
+        .collect()
+}
+
 pub fn get_texture_coordinates(
     texture_index: i32,
     texture_width: i32,
@@ -9,3 +32,22 @@ pub fn get_texture_coordinates(
     let flipped_v = 1.0_f32 - v;
     [u, flipped_v, u, flipped_v, u, flipped_v, u, flipped_v]
 }
+
+pub fn get_tile_origin(texture_index: i32, texture_width: i32) -> [f32; 2] {
+    let texture_size = 1.0_f32 / texture_width as f32;
+    let u = (texture_index % texture_width) as f32 * texture_size;
+    let v = (texture_index / texture_width + 1) as f32 * texture_size;
+    [u, 1.0_f32 - v]
+}
+
+#[inline(always)]
+pub fn get_tiled_uv(face_dir: usize, local: [f32; 3], extent: [f32; 3]) -> [f32; 2] {
+    match face_dir {
+        0 => [extent[2] - local[2], local[1]],
+        1 => [local[2], local[1]],
+        2 => [local[0], extent[2] - local[2]],
+        3 => [local[0], local[2]],
+        4 => [local[0], local[1]],
+        _ => [extent[0] - local[0], local[1]],
+    }
+}
@@ -0,0 +1,453 @@
+use std::fmt;
+
+use crate::packed_vertex::{MAX_PACKED_TEXTURE_INDEX, MAX_PACKED_XY, MAX_PACKED_Z};
+use crate::texture_coords::FACES_PER_BLOCK;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum MeshingError {
+    DimensionOverflow {
+        dims: [usize; 3],
+    },
+    VoxelDataLength {
+        expected: usize,
+        actual: usize,
+    },
+    SelectionDataLength {
+        expected: usize,
+        actual: usize,
+    },
+    InvalidTextureWidth {
+        texture_width: i32,
+    },
+    AtlasMappingTooShort {
+        block_type: u8,
+        required: usize,
+        actual: usize,
+    },
+    InvalidAtlasIndex {
+        position: usize,
+        texture_index: i32,
+    },
+    InvalidLodFactor {
+        factor: usize,
+    },
+    UnsupportedLodOption {
+        option: &'static str,
+    },
+    InvalidRegion {
+        min: [usize; 3],
+        max: [usize; 3],
+    },
+    InvalidMaterialClass {
+        class: u8,
+    },
+    InvalidAoStrength,
+    InvalidAoLevel {
+        index: usize,
+    },
+    PackedExtentExceeded {
+        extent: [usize; 3],
+    },
+    UnsupportedPackedOption {
+        option: &'static str,
+    },
+}
+
+impl fmt::Display for MeshingError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            MeshingError::DimensionOverflow { dims } => write!(
+                f,
+                "volume dimensions {}x{}x{} overflow the addressable size",
+                dims[0], dims[1], dims[2]
+            ),
+            MeshingError::VoxelDataLength { expected, actual } => write!(
+                f,
+                "voxel data has {actual} bytes but the dimensions require {expected}"
+            ),
+            MeshingError::SelectionDataLength { expected, actual } => write!(
+                f,
+                "selection data has {actual} bytes but the selection dimensions require {expected}"
+            ),
+            MeshingError::InvalidTextureWidth { texture_width } => {
+                write!(f, "texture width must be positive, got {texture_width}")
+            }
+            MeshingError::AtlasMappingTooShort {
+                block_type,
+                required,
+                actual,
+            } => write!(
+                f,
+                "block type {block_type} needs {required} atlas mapping entries but only {actual} were provided"
+            ),
+            MeshingError::InvalidAtlasIndex {
+                position,
+                texture_index,
+            } => write!(
+                f,
+                "atlas mapping entry {position} has out of range texture index {texture_index}"
+            ),
+            MeshingError::InvalidLodFactor { factor } => {
+                write!(f, "LOD factor must be one of 1, 2, 4 or 8, got {factor}")
+            }
+            MeshingError::UnsupportedLodOption { option } => {
+                write!(f, "{option} are not supported with LOD meshing")
+            }
+            MeshingError::InvalidRegion { min, max } => write!(
+                f,
+                "remesh region {min:?}..={max:?} must be ordered and inside the volume"
+            ),
+            MeshingError::InvalidMaterialClass { class } => write!(
+                f,
+                "{class} is not a valid material class, expected 0 (opaque), 1 (cutout) or 2 (translucent)"
+            ),
+            MeshingError::InvalidAoStrength => {
+                write!(f, "AO strength must be a finite value between 0 and 1")
+            }
+            MeshingError::InvalidAoLevel { index } => {
+                write!(f, "AO level {index} must be a finite value between 0 and 1")
+            }
+            MeshingError::PackedExtentExceeded { extent } => write!(
+                f,
+                "mesh extent {extent:?} exceeds the packed vertex range of \
+                 {MAX_PACKED_XY}x{MAX_PACKED_XY}x{MAX_PACKED_Z}"
+            ),
+            MeshingError::UnsupportedPackedOption { option } => {
+                write!(f, "{option} is not supported with packed vertex output")
+            }
+        }
+    }
+}
+
+impl std::error::Error for MeshingError {}
+
+fn volume_len(dims: [usize; 3]) -> Result<usize, MeshingError> {
+    dims[0]
+        .checked_mul(dims[1])
+        .and_then(|n| n.checked_mul(dims[2]))
+        .ok_or(MeshingError::DimensionOverflow { dims })
+}
+
+fn max_block_type(data: &[u8]) -> u8 {
+    data.iter().fold(0, |max, &value| max.max(value & 0x7F))
+}
+
+pub fn validate_voxel_data(voxel_data: &[u8], dims: [usize; 3]) -> Result<(), MeshingError> {
+    let expected = volume_len(dims)?;
+    if voxel_data.len() != expected {
+        return Err(MeshingError::VoxelDataLength {
+            expected,
+            actual: voxel_data.len(),
+        });
+    }
+    Ok(())
+}
+
+pub fn validate_selection_data(
+    selection_data: &[u8],
+    selection_dims: [usize; 3],
+) -> Result<usize, MeshingError> {
+    let expected = volume_len(selection_dims)?;
+    if selection_data.len() < expected {
+        return Err(MeshingError::SelectionDataLength {
+            expected,
+            actual: selection_data.len(),
+        });
+    }
+    Ok(expected)
+}
+
+pub fn validate_mesh_input(
+    voxel_data: &[u8],
+    texture_width: i32,
+    block_atlas_mapping: &[i32],
+    data_dims: [usize; 3],
+    selection_data: &[u8],
+    selection_dim_x: usize,
+    selection_dim_y: usize,
+    selection_dim_z: usize,
+    selection_empty: bool,
+) -> Result<usize, MeshingError> {
+    let max_type = validate_volume_input(
+        voxel_data,
+        texture_width,
+        data_dims,
+        selection_data,
+        selection_dim_x,
+        selection_dim_y,
+        selection_dim_z,
+        selection_empty,
+    )?;
+    validate_atlas_mapping(block_atlas_mapping, max_type)
+}
+
+pub fn validate_volume_input(
+    voxel_data: &[u8],
+    texture_width: i32,
+    data_dims: [usize; 3],
+    selection_data: &[u8],
+    selection_dim_x: usize,
+    selection_dim_y: usize,
+    selection_dim_z: usize,
+    selection_empty: bool,
+) -> Result<u8, MeshingError> {
+    validate_voxel_data(voxel_data, data_dims)?;
+
+    if texture_width <= 0 {
+        return Err(MeshingError::InvalidTextureWidth { texture_width });
+    }
+
+    let mut max_type = max_block_type(voxel_data);
+    if !selection_empty {
+        let selection_dims = [selection_dim_x, selection_dim_y, selection_dim_z];
+        let expected = validate_selection_data(selection_data, selection_dims)?;
+        max_type = max_type.max(max_block_type(&selection_data[..expected]).max(1));
+    }
+    Ok(max_type)
+}
+
+pub fn validate_atlas_mapping(
+    block_atlas_mapping: &[i32],
+    max_type: u8,
+) -> Result<usize, MeshingError> {
+    let required = max_type as usize * FACES_PER_BLOCK;
+    if block_atlas_mapping.len() < required {
+        return Err(MeshingError::AtlasMappingTooShort {
+            block_type: max_type,
+            required,
+            actual: block_atlas_mapping.len(),
+        });
+    }
+
+    if let Some((position, &texture_index)) = block_atlas_mapping[..required]
+        .iter()
+        .enumerate()
+        .find(|&(_, &index)| index < 0 || index > i16::MAX as i32)
+    {
+        return Err(MeshingError::InvalidAtlasIndex {
+            position,
+            texture_index,
+        });
+    }
+
+    Ok(required)
+}
+
+pub fn validate_packed_output(
+    extent: [usize; 3],
+    block_atlas_mapping: &[i32],
+) -> Result<(), MeshingError> {
+    let max = [MAX_PACKED_XY, MAX_PACKED_XY, MAX_PACKED_Z];
+    if (0..3).any(|a| extent[a] > max[a] as usize) {
+        return Err(MeshingError::PackedExtentExceeded { extent });
+    }
+
+    if let Some((position, &texture_index)) = block_atlas_mapping
+        .iter()
+        .enumerate()
+        .find(|&(_, &index)| index as u32 > MAX_PACKED_TEXTURE_INDEX)
+    {
+        return Err(MeshingError::InvalidAtlasIndex {
+            position,
+            texture_index,
+        });
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::find_exterior_faces::ExteriorFacesFinder;
+    use crate::mesh_arrays::MeshArrays;
+    use crate::texture_coords::expand_block_atlas_mapping;
+
+    fn try_mesh(
+        voxel_data: &[u8],
+        texture_width: i32,
+        mapping: &[i32],
+        dims: [usize; 3],
+        selection_data: &[u8],
+        selection_empty: bool,
+    ) -> Result<MeshArrays, MeshingError> {
+        let mut finder = ExteriorFacesFinder::new(4);
+        let mut mesh_arrays = MeshArrays::new(0, 0);
+        finder.try_find_exterior_faces(
+            voxel_data,
+            texture_width,
+            mapping,
+            dims[0],
+            dims[1],
+            dims[2],
+            &mut mesh_arrays,
+            selection_data,
+            dims[0],
+            dims[1],
+            dims[2],
+            selection_empty,
+        )?;
+        Ok(mesh_arrays)
+    }
+
+    #[test]
+    fn valid_input_meshes() {
+        let mapping = expand_block_atlas_mapping(&[0, 1]);
+        let mesh_arrays = try_mesh(&[1, 2], 4, &mapping, [2, 1, 1], &[0, 0], false).unwrap();
+        assert_eq!(mesh_arrays.index_count, 60);
+    }
+
+    #[test]
+    fn rejects_voxel_data_length_mismatch() {
+        let mapping = expand_block_atlas_mapping(&[0]);
+        assert_eq!(
+            try_mesh(&[1, 1, 1], 4, &mapping, [2, 2, 1], &[], true).err(),
+            Some(MeshingError::VoxelDataLength {
+                expected: 4,
+                actual: 3
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_material_classes() {
+        let mut finder = ExteriorFacesFinder::new(4);
+        assert_eq!(
+            finder.set_block_materials(&[0, 2, 3]),
+            Err(MeshingError::InvalidMaterialClass { class: 3 })
+        );
+        assert!(finder.set_block_materials(&[0, 1, 2]).is_ok());
+    }
+
+    #[test]
+    fn rejects_overflowing_dimensions() {
+        let mapping = expand_block_atlas_mapping(&[0]);
+        assert!(matches!(
+            try_mesh(&[1], 4, &mapping, [usize::MAX, 2, 1], &[], true),
+            Err(MeshingError::DimensionOverflow { .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_short_selection_data() {
+        let mapping = expand_block_atlas_mapping(&[0]);
+        assert_eq!(
+            try_mesh(&[1, 0], 4, &mapping, [2, 1, 1], &[1], false).err(),
+            Some(MeshingError::SelectionDataLength {
+                expected: 2,
+                actual: 1
+            })
+        );
+        assert!(try_mesh(&[1, 0], 4, &mapping, [2, 1, 1], &[], true).is_ok());
+    }
+
+    #[test]
+    fn rejects_non_positive_texture_width() {
+        let mapping = expand_block_atlas_mapping(&[0]);
+        assert_eq!(
+            try_mesh(&[1], 0, &mapping, [1, 1, 1], &[], true).err(),
+            Some(MeshingError::InvalidTextureWidth { texture_width: 0 })
+        );
+    }
+
+    #[test]
+    fn rejects_block_types_beyond_atlas_mapping() {
+        let mapping = expand_block_atlas_mapping(&[0]);
+        assert_eq!(
+            try_mesh(&[1, 0x83], 4, &mapping, [2, 1, 1], &[], true).err(),
+            Some(MeshingError::AtlasMappingTooShort {
+                block_type: 3,
+                required: 18,
+                actual: 6
+            })
+        );
+        assert!(matches!(
+            try_mesh(&[1, 0], 4, &mapping, [2, 1, 1], &[0, 2], false),
+            Err(MeshingError::AtlasMappingTooShort { block_type: 2, .. })
+        ));
+    }
+
+    #[test]
+    fn rejects_out_of_range_atlas_indices() {
+        let mapping = vec![0, 0, -1, 0, 0, 0];
+        assert_eq!(
+            try_mesh(&[1], 4, &mapping, [1, 1, 1], &[], true).err(),
+            Some(MeshingError::InvalidAtlasIndex {
+                position: 2,
+                texture_index: -1
+            })
+        );
+    }
+
+    #[test]
+    fn validates_halo_padded_length() {
+        let mapping = expand_block_atlas_mapping(&[0]);
+        let mut finder = ExteriorFacesFinder::new(1);
+        let mut mesh_arrays = MeshArrays::new(0, 0);
+        let result = finder.try_find_exterior_faces_with_halo(
+            &[0; 8],
+            4,
+            &mapping,
+            1,
+            1,
+            1,
+            &mut mesh_arrays,
+            &[],
+            0,
+            0,
+            0,
+            true,
+        );
+        assert_eq!(
+            result,
+            Err(MeshingError::VoxelDataLength {
+                expected: 27,
+                actual: 8
+            })
+        );
+    }
+
+    #[test]
+    fn packed_output_rejects_values_beyond_packed_range() {
+        let try_mesh_packed = |voxel_data: &[u8], mapping: &[i32], dims: [usize; 3]| {
+            let mut finder = ExteriorFacesFinder::new(4);
+            let mut mesh_arrays = MeshArrays::new_packed(0, 0);
+            finder.try_find_exterior_faces(
+                voxel_data,
+                4,
+                mapping,
+                dims[0],
+                dims[1],
+                dims[2],
+                &mut mesh_arrays,
+                &[],
+                0,
+                0,
+                0,
+                true,
+            )
+        };
+
+        let mapping = expand_block_atlas_mapping(&[0]);
+        let mut tall = vec![0u8; 4096];
+        tall[4095] = 1;
+        assert_eq!(
+            try_mesh_packed(&tall, &mapping, [1, 1, 4096]),
+            Err(MeshingError::PackedExtentExceeded {
+                extent: [1, 1, 4096]
+            })
+        );
+        assert!(try_mesh_packed(&tall[1..], &mapping, [1, 1, 4095]).is_ok());
+        assert!(try_mesh(&tall, 4, &mapping, [1, 1, 4096], &[], true).is_ok());
+
+        let mapping = vec![16384, 0, 0, 0, 0, 0];
+        assert_eq!(
+            try_mesh_packed(&[1], &mapping, [1, 1, 1]),
+            Err(MeshingError::InvalidAtlasIndex {
+                position: 0,
+                texture_index: 16384
+            })
+        );
+        assert!(try_mesh(&[1], 4, &mapping, [1, 1, 1], &[], true).is_ok());
+    }
+}
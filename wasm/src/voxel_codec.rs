@@ -0,0 +1,530 @@
+use std::fmt;
+
+const MAX_RUN_LENGTH: usize = 0xFFFF;
+const RLE_HEADER_LEN: usize = 4;
+const RLE_RUN_LEN: usize = 3;
+
+const LZ4_MAGIC: u32 = 0x184D_2204;
+const LZ4_FRAME_HEADER: [u8; 7] = [0x04, 0x22, 0x4D, 0x18, 0x40, 0x70, 0xDF];
+const LZ4_VERSION: u8 = 0x40;
+const LZ4_VERSION_MASK: u8 = 0xC0;
+const LZ4_BLOCK_CHECKSUM: u8 = 0x10;
+const LZ4_CONTENT_SIZE: u8 = 0x08;
+const LZ4_CONTENT_CHECKSUM: u8 = 0x04;
+const LZ4_UNCOMPRESSED_BLOCK: u32 = 0x8000_0000;
+const LZ4_MAX_BLOCK_SIZE: usize = 0x40_0000;
+
+const MIN_MATCH: usize = 4;
+const MIN_BLOCK_LENGTH: usize = 13;
+const SEARCH_LIMIT: usize = 5;
+const SKIP_TRIGGER: u32 = 6;
+const HASH_SIZE: usize = 1 << 16;
+const RUN_MASK: usize = 0xF;
+const MATCH_LENGTH_MASK: usize = 0xF;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CodecError {
+    EmptyVoxelData,
+    InvalidMagic,
+    UnsupportedFrameVersion { descriptor: u8 },
+    InvalidBlockSize { block_size_id: u8 },
+    Truncated,
+    InvalidMatchOffset { offset: usize },
+    MalformedRle,
+    TargetLength { expected: usize, actual: usize },
+}
+
+impl fmt::Display for CodecError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            CodecError::EmptyVoxelData => write!(f, "voxel data must not be empty"),
+            CodecError::InvalidMagic => {
+                write!(f, "compressed data has an invalid LZ4 magic number")
+            }
+            CodecError::UnsupportedFrameVersion { descriptor } => {
+                write!(f, "unsupported LZ4 frame descriptor {descriptor:#04x}")
+            }
+            CodecError::InvalidBlockSize { block_size_id } => {
+                write!(f, "invalid LZ4 block size id {block_size_id}")
+            }
+            CodecError::Truncated => write!(f, "compressed data ended unexpectedly"),
+            CodecError::InvalidMatchOffset { offset } => {
+                write!(
+                    f,
+                    "LZ4 match offset {offset} points before the start of the output"
+                )
+            }
+            CodecError::MalformedRle => {
+                write!(f, "RLE runs do not add up to the encoded voxel count")
+            }
+            CodecError::TargetLength { expected, actual } => write!(
+                f,
+                "target buffer has {actual} bytes but the decompressed data has {expected}"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for CodecError {}
+
+pub fn compress_voxel_data(voxel_data: &[u8]) -> Result<Vec<u8>, CodecError> {
+    Ok(lz4_compress(&rle_compress(voxel_data)?))
+}
+
+pub fn decompress_voxel_data(compressed: &[u8]) -> Result<Vec<u8>, CodecError> {
+    let rle = lz4_decompress(compressed)?;
+    let mut voxel_data = vec![0; rle_decompressed_len(&rle)?];
+    rle_decompress_into(&rle, &mut voxel_data)?;
+    Ok(voxel_data)
+}
+
+pub fn decompress_into(compressed: &[u8], target: &mut [u8]) -> Result<usize, CodecError> {
+    rle_decompress_into(&lz4_decompress(compressed)?, target)
+}
+
+pub fn rle_compress(voxel_data: &[u8]) -> Result<Vec<u8>, CodecError> {
+    if voxel_data.is_empty() {
+        return Err(CodecError::EmptyVoxelData);
+    }
+    let mut compressed = Vec::with_capacity(RLE_HEADER_LEN + RLE_RUN_LEN * 8);
+    compressed.extend_from_slice(&(voxel_data.len() as u32).to_le_bytes());
+
+    let mut i = 0;
+    while i < voxel_data.len() {
+        let value = voxel_data[i];
+        let run_length = voxel_data[i..]
+            .iter()
+            .take(MAX_RUN_LENGTH)
+            .take_while(|&&v| v == value)
+            .count();
+        compressed.push(value);
+        compressed.extend_from_slice(&(run_length as u16).to_le_bytes());
+        i += run_length;
+    }
+
+    Ok(compressed)
+}
+
+pub fn rle_decompressed_len(rle: &[u8]) -> Result<usize, CodecError> {
+    let header = rle.get(..RLE_HEADER_LEN).ok_or(CodecError::Truncated)?;
+    let expected = u32::from_le_bytes([header[0], header[1], header[2], header[3]]) as usize;
+    let runs = &rle[RLE_HEADER_LEN..];
+    if !runs.len().is_multiple_of(RLE_RUN_LEN) {
+        return Err(CodecError::MalformedRle);
+    }
+    let total: usize = runs.chunks_exact(RLE_RUN_LEN).map(run_length).sum();
+    if total != expected {
+        return Err(CodecError::MalformedRle);
+    }
+    Ok(expected)
+}
+
+pub fn rle_decompress_into(rle: &[u8], target: &mut [u8]) -> Result<usize, CodecError> {
+    let expected = rle_decompressed_len(rle)?;
+    if target.len() != expected {
+        return Err(CodecError::TargetLength {
+            expected,
+            actual: target.len(),
+        });
+    }
+
+    let mut write_index = 0;
+    for run in rle[RLE_HEADER_LEN..].chunks_exact(RLE_RUN_LEN) {
+        let end = write_index + run_length(run);
+        target[write_index..end].fill(run[0]);
+        write_index = end;
+    }
+
+    Ok(expected)
+}
+
+#[inline(always)]
+fn run_length(run: &[u8]) -> usize {
+    u16::from_le_bytes([run[1], run[2]]) as usize
+}
+
+pub fn lz4_compress(src: &[u8]) -> Vec<u8> {
+    let mut dst = Vec::with_capacity(src.len() + src.len() / 255 + 16);
+    dst.extend_from_slice(&LZ4_FRAME_HEADER);
+
+    let mut hash_table = vec![0u32; HASH_SIZE];
+    let mut block = Vec::new();
+    let mut s_index = 0;
+    while s_index < src.len() {
+        let block_size = (src.len() - s_index).min(LZ4_MAX_BLOCK_SIZE);
+        block.clear();
+        let compressed = compress_block(src, &mut block, s_index, block_size, &mut hash_table);
+        if compressed {
+            dst.extend_from_slice(&(block.len() as u32).to_le_bytes());
+            dst.extend_from_slice(&block);
+        } else {
+            dst.extend_from_slice(&(LZ4_UNCOMPRESSED_BLOCK | block_size as u32).to_le_bytes());
+            dst.extend_from_slice(&src[s_index..s_index + block_size]);
+        }
+        s_index += block_size;
+    }
+
+    dst.extend_from_slice(&0u32.to_le_bytes());
+    dst
+}
+
+pub fn lz4_decompress(src: &[u8]) -> Result<Vec<u8>, CodecError> {
+    let mut reader = Reader { src, index: 0 };
+    if reader.u32()? != LZ4_MAGIC {
+        return Err(CodecError::InvalidMagic);
+    }
+
+    let descriptor = reader.u8()?;
+    if descriptor & LZ4_VERSION_MASK != LZ4_VERSION {
+        return Err(CodecError::UnsupportedFrameVersion { descriptor });
+    }
+    let block_size_id = (reader.u8()? >> 4) & 0x7;
+    if !(4..=7).contains(&block_size_id) {
+        return Err(CodecError::InvalidBlockSize { block_size_id });
+    }
+    let max_block_size = 1usize << (8 + 2 * block_size_id);
+    if descriptor & LZ4_CONTENT_SIZE != 0 {
+        reader.skip(8)?;
+    }
+    reader.skip(1)?;
+
+    let mut dst = Vec::new();
+    loop {
+        let block_size = reader.u32()?;
+        if block_size == 0 {
+            break;
+        }
+        if block_size & LZ4_UNCOMPRESSED_BLOCK != 0 {
+            let len = (block_size & !LZ4_UNCOMPRESSED_BLOCK) as usize;
+            dst.extend_from_slice(reader.take(len)?);
+        } else {
+            let block = reader.take(block_size as usize)?;
+            dst.reserve(decompressed_block_len(block)?.min(max_block_size));
+            decompress_block(block, &mut dst)?;
+        }
+        if descriptor & LZ4_BLOCK_CHECKSUM != 0 {
+            reader.skip(4)?;
+        }
+    }
+    if descriptor & LZ4_CONTENT_CHECKSUM != 0 {
+        reader.skip(4)?;
+    }
+
+    Ok(dst)
+}
+
+struct Reader<'a> {
+    src: &'a [u8],
+    index: usize,
+}
+
+impl<'a> Reader<'a> {
+    fn take(&mut self, len: usize) -> Result<&'a [u8], CodecError> {
+        let bytes = self
+            .src
+            .get(self.index..self.index + len)
+            .ok_or(CodecError::Truncated)?;
+        self.index += len;
+        Ok(bytes)
+    }
+
+    fn skip(&mut self, len: usize) -> Result<(), CodecError> {
+        self.take(len).map(|_| ())
+    }
+
+    fn u8(&mut self) -> Result<u8, CodecError> {
+        Ok(self.take(1)?[0])
+    }
+
+    fn u32(&mut self) -> Result<u32, CodecError> {
+        let bytes = self.take(4)?;
+        Ok(u32::from_le_bytes([bytes[0], bytes[1], bytes[2], bytes[3]]))
+    }
+
+    fn length(&mut self, base: usize) -> Result<usize, CodecError> {
+        let mut length = base;
+        if base == 0xF {
+            loop {
+                let byte = self.u8()?;
+                length += byte as usize;
+                if byte != 0xFF {
+                    break;
+                }
+            }
+        }
+        Ok(length)
+    }
+}
+
+fn decompressed_block_len(block: &[u8]) -> Result<usize, CodecError> {
+    let mut reader = Reader {
+        src: block,
+        index: 0,
+    };
+    let mut len = 0;
+    while reader.index < block.len() {
+        let token = reader.u8()? as usize;
+        let literal_count = reader.length(token >> 4)?;
+        reader.skip(literal_count)?;
+        len += literal_count;
+        if reader.index >= block.len() {
+            break;
+        }
+        reader.skip(2)?;
+        len += reader.length(token & MATCH_LENGTH_MASK)? + MIN_MATCH;
+    }
+    Ok(len)
+}
+
+fn decompress_block(block: &[u8], dst: &mut Vec<u8>) -> Result<(), CodecError> {
+    let mut reader = Reader {
+        src: block,
+        index: 0,
+    };
+    while reader.index < block.len() {
+        let token = reader.u8()? as usize;
+        let literal_count = reader.length(token >> 4)?;
+        dst.extend_from_slice(reader.take(literal_count)?);
+        if reader.index >= block.len() {
+            break;
+        }
+
+        let offset = u16::from_le_bytes([reader.u8()?, reader.u8()?]) as usize;
+        let match_length = reader.length(token & MATCH_LENGTH_MASK)? + MIN_MATCH;
+        if offset == 0 || offset > dst.len() {
+            return Err(CodecError::InvalidMatchOffset { offset });
+        }
+        let start = dst.len() - offset;
+        if offset >= match_length {
+            dst.extend_from_within(start..start + match_length);
+        } else {
+            let mut remaining = match_length;
+            while remaining > 0 {
+                let len = remaining.min(dst.len() - start);
+                dst.extend_from_within(start..start + len);
+                remaining -= len;
+            }
+        }
+    }
+    Ok(())
+}
+
+fn compress_block(
+    src: &[u8],
+    dst: &mut Vec<u8>,
+    block_start: usize,
+    block_len: usize,
+    hash_table: &mut [u32],
+) -> bool {
+    let s_end = block_start + block_len;
+    let mut s_index = block_start;
+    let mut anchor = block_start;
+
+    if block_len >= MIN_BLOCK_LENGTH {
+        let mut search_match_count = (1 << SKIP_TRIGGER) + 3;
+        while s_index + MIN_MATCH + SEARCH_LIMIT < s_end {
+            let sequence = read_u32(src, s_index);
+            let hash = hash_u32(sequence);
+            let hash = ((hash >> 16) ^ hash) as usize & 0xFFFF;
+            let candidate = hash_table[hash] as usize;
+            hash_table[hash] = s_index as u32 + 1;
+
+            let is_match = candidate > 0
+                && s_index - (candidate - 1) <= 0xFFFF
+                && read_u32(src, candidate - 1) == sequence;
+            if !is_match {
+                s_index += search_match_count >> SKIP_TRIGGER;
+                search_match_count += 1;
+                continue;
+            }
+            search_match_count = (1 << SKIP_TRIGGER) + 3;
+
+            let mut m_index = candidate - 1;
+            let literal_count = s_index - anchor;
+            let offset = s_index - m_index;
+            s_index += MIN_MATCH;
+            m_index += MIN_MATCH;
+            let match_start = s_index;
+            while s_index + SEARCH_LIMIT < s_end && src[s_index] == src[m_index] {
+                s_index += 1;
+                m_index += 1;
+            }
+            let match_length = s_index - match_start;
+
+            let token = match_length.min(MATCH_LENGTH_MASK);
+            push_token(dst, literal_count, token);
+            dst.extend_from_slice(&src[anchor..anchor + literal_count]);
+            dst.extend_from_slice(&(offset as u16).to_le_bytes());
+            if match_length >= MATCH_LENGTH_MASK {
+                push_length(dst, match_length - MATCH_LENGTH_MASK);
+            }
+            anchor = s_index;
+        }
+    }
+
+    if anchor == 0 {
+        return false;
+    }
+
+    push_token(dst, s_end - anchor, 0);
+    dst.extend_from_slice(&src[anchor..s_end]);
+    dst.len() <= block_len
+}
+
+fn push_token(dst: &mut Vec<u8>, literal_count: usize, match_token: usize) {
+    if literal_count >= RUN_MASK {
+        dst.push(((RUN_MASK << 4) + match_token) as u8);
+        push_length(dst, literal_count - RUN_MASK);
+    } else {
+        dst.push(((literal_count << 4) + match_token) as u8);
+    }
+}
+
+fn push_length(dst: &mut Vec<u8>, mut length: usize) {
+    while length >= 0xFF {
+        dst.push(0xFF);
+        length -= 0xFF;
+    }
+    dst.push(length as u8);
+}
+
+#[inline(always)]
+fn read_u32(src: &[u8], index: usize) -> u32 {
+    u32::from_le_bytes([src[index], src[index + 1], src[index + 2], src[index + 3]])
+}
+
+#[inline(always)]
+fn hash_u32(value: u32) -> u32 {
+    let mut a = value as i32;
+    a = a.wrapping_add(2127912214).wrapping_add(a << 12);
+    a = a ^ -949894596 ^ ((a as u32) >> 19) as i32;
+    a = a.wrapping_add(374761393).wrapping_add(a << 5);
+    a = a.wrapping_add(-744332180) ^ (a << 9);
+    a = a.wrapping_add(-42973499).wrapping_add(a << 3);
+    (a ^ -1252372727 ^ ((a as u32) >> 16) as i32) as u32
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn hex(s: &str) -> Vec<u8> {
+        (0..s.len())
+            .step_by(2)
+            .map(|i| u8::from_str_radix(&s[i..i + 2], 16).unwrap())
+            .collect()
+    }
+
+    fn chunk_fixture_data() -> Vec<u8> {
+        let mut data = vec![0u8; 16 * 4 * 16];
+        for x in 0..16 {
+            for z in 0..16 {
+                data[x * 64 + z] = 0x81;
+                if (x + z) % 5 == 0 {
+                    data[x * 64 + 16 + z] = 0x82;
+                }
+            }
+        }
+        data
+    }
+
+    // Generated by frontend/scripts/generate-voxel-codec-fixtures.ts.
+    const SMALL_FIXTURE: &str =
+        "04224d184070df160000800800000001010002010003010004010005010000030000000000";
+    const CHUNK_FIXTURE: &str = "04224d184070df3d000000cd000400008110008201000004060010201b000f180000112118001d033000112218001d021800112318001d011800102418000f7b00f650010000200000000000";
+    const LONG_RUN_FIXTURE: &str = "04224d184070df0a0000807011010003ffff03711100000000";
+
+    #[test]
+    fn matches_js_encoder_fixtures() {
+        let cases = [
+            (SMALL_FIXTURE, vec![1, 2, 3, 4, 5, 0, 0, 0]),
+            (CHUNK_FIXTURE, chunk_fixture_data()),
+            (LONG_RUN_FIXTURE, vec![3; 70000]),
+        ];
+        for (fixture, data) in cases {
+            let fixture = hex(fixture);
+            assert_eq!(compress_voxel_data(&data).unwrap(), fixture);
+            assert_eq!(decompress_voxel_data(&fixture).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn round_trips_varied_data() {
+        let mut seed = 0x1234_5678u32;
+        let noisy: Vec<u8> = (0..50_000)
+            .map(|i| {
+                seed = seed.wrapping_mul(1_103_515_245).wrapping_add(12_345);
+                if i % 7 < 3 {
+                    (seed >> 24) as u8
+                } else {
+                    (i / 300) as u8
+                }
+            })
+            .collect();
+        let striped: Vec<u8> = (0..200_000).map(|i| ((i / 17) % 4) as u8).collect();
+        for data in [noisy, striped, vec![0x81], vec![0; 1 << 20]] {
+            let compressed = compress_voxel_data(&data).unwrap();
+            assert_eq!(decompress_voxel_data(&compressed).unwrap(), data);
+        }
+    }
+
+    #[test]
+    fn large_rle_streams_span_multiple_blocks() {
+        let data: Vec<u8> = (0..6_000_000u32).map(|i| (i % 251) as u8).collect();
+        let compressed = compress_voxel_data(&data).unwrap();
+        assert_eq!(decompress_voxel_data(&compressed).unwrap(), data);
+    }
+
+    #[test]
+    fn decompress_into_reuses_target_buffer() {
+        let mut target = vec![9u8; 8];
+        for data in [[1u8; 8], [2, 2, 2, 0, 0, 0, 0, 7]] {
+            let compressed = compress_voxel_data(&data).unwrap();
+            assert_eq!(decompress_into(&compressed, &mut target), Ok(8));
+            assert_eq!(target, data);
+        }
+
+        let compressed = compress_voxel_data(&[1, 2, 3, 4]).unwrap();
+        assert_eq!(
+            decompress_into(&compressed, &mut target),
+            Err(CodecError::TargetLength {
+                expected: 4,
+                actual: 8
+            })
+        );
+    }
+
+    #[test]
+    fn rejects_malformed_input() {
+        assert_eq!(compress_voxel_data(&[]), Err(CodecError::EmptyVoxelData));
+        assert_eq!(
+            decompress_voxel_data(&[0, 1, 2, 3]),
+            Err(CodecError::InvalidMagic)
+        );
+
+        let fixture = hex(CHUNK_FIXTURE);
+        assert_eq!(
+            decompress_voxel_data(&fixture[..fixture.len() - 10]),
+            Err(CodecError::Truncated)
+        );
+
+        let mut bad_runs = LZ4_FRAME_HEADER.to_vec();
+        bad_runs.extend_from_slice(&(LZ4_UNCOMPRESSED_BLOCK | 7).to_le_bytes());
+        bad_runs.extend_from_slice(&[4, 0, 0, 0, 1, 3, 0]);
+        bad_runs.extend_from_slice(&[0; 4]);
+        assert_eq!(
+            decompress_voxel_data(&bad_runs),
+            Err(CodecError::MalformedRle)
+        );
+
+        let mut oversized = LZ4_FRAME_HEADER.to_vec();
+        oversized.extend_from_slice(&(LZ4_UNCOMPRESSED_BLOCK | 7).to_le_bytes());
+        oversized.extend_from_slice(&[0xFF, 0xFF, 0xFF, 0xFF, 1, 3, 0]);
+        oversized.extend_from_slice(&[0; 4]);
+        assert_eq!(oversized.len(), 22);
+        assert_eq!(
+            decompress_voxel_data(&oversized),
+            Err(CodecError::MalformedRle)
+        );
+    }
+}